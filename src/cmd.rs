@@ -1,5 +1,37 @@
-use crate::errors::{Error, ErrorKind, Result};
-use crate::problems;
+use rosalind_rust::common::seq::Dna;
+use rosalind_rust::common::{self, faidx, fmindex, msa, orf, profile, stats};
+use rosalind_rust::errors::{Error, ErrorKind, Result};
+use rosalind_rust::problems;
+
+const LIST_SUBCOMMAND: &str = "list";
+const INFO_SUBCOMMAND: &str = "info";
+const VERIFY_SUBCOMMAND: &str = "verify";
+const FAIDX_SUBCOMMAND: &str = "faidx";
+const BUILD_SUBCOMMAND: &str = "build";
+const FETCH_SUBCOMMAND: &str = "fetch";
+const ORFS_SUBCOMMAND: &str = "orfs";
+const TRANSLATE_SUBCOMMAND: &str = "translate";
+const STATS_SUBCOMMAND: &str = "stats";
+const MSA_SUBCOMMAND: &str = "msa";
+const SCAN_SUBCOMMAND: &str = "scan";
+const SEARCH_SUBCOMMAND: &str = "search";
+const ID_ARG: &str = "id";
+const FASTA_ARG: &str = "fasta";
+const REGION_ARG: &str = "region";
+const MIN_LENGTH_ARG: &str = "min-length";
+const ALT_STARTS_ARG: &str = "alt-starts";
+const NESTED_ARG: &str = "nested";
+const GFF_ARG: &str = "gff";
+const SIX_FRAME_ARG: &str = "six-frame";
+const TSV_ARG: &str = "tsv";
+const SUMMARY_ARG: &str = "summary";
+const PROTEIN_ARG: &str = "protein";
+const CLUSTAL_ARG: &str = "clustal";
+const MOTIF_ARG: &str = "motif";
+const THRESHOLD_ARG: &str = "threshold";
+const REFERENCE_ARG: &str = "reference";
+const MISMATCHES_ARG: &str = "mismatches";
+const SAVE_INDEX_ARG: &str = "save-index";
 
 /// Entrypoint for the application
 pub fn run() -> Result<()> {
@@ -7,60 +39,520 @@ pub fn run() -> Result<()> {
         .author("Mimi Wang, mimikwang@gmail.com")
         .version("0.1.0")
         .about("Rosalind problems solver")
-        .subcommand(problems::dna::command())
-        .subcommand(problems::rna::command())
-        .subcommand(problems::revc::command())
-        .subcommand(problems::fib::command())
-        .subcommand(problems::gc::command())
-        .subcommand(problems::hamm::command())
-        .subcommand(problems::iprb::command())
-        .subcommand(problems::prot::command())
-        .subcommand(problems::subs::command())
-        .subcommand(problems::cons::command())
-        .subcommand(problems::fibd::command())
-        .subcommand(problems::grph::command())
-        .subcommand(problems::iev::command())
-        .subcommand(problems::lcsm::command())
-        .subcommand(problems::lia::command())
-        .subcommand(problems::mprt::command())
-        .subcommand(problems::mrna::command())
-        .subcommand(problems::orf::command())
-        .subcommand(problems::perm::command())
-        .subcommand(problems::prtm::command())
-        .subcommand(problems::revp::command())
-        .subcommand(problems::splc::command())
-        .subcommand(problems::lexf::command())
-        .subcommand(problems::lgis::command())
-        .subcommand(problems::tran::command())
+        .arg(
+            clap::Arg::new(problems::FORMAT_ARG)
+                .long(problems::FORMAT_ARG)
+                .global(true)
+                .takes_value(true)
+                .possible_values(problems::Format::NAMES)
+                .default_value("text")
+                .help(
+                    "Output format of the answer of a problem, other subcommands only print text",
+                ),
+        )
+        .subcommand(clap::Command::new(LIST_SUBCOMMAND).about("List all supported problems"))
+        .subcommand(
+            clap::Command::new(INFO_SUBCOMMAND)
+                .about("Show information about a problem")
+                .arg(clap::Arg::new(ID_ARG).required(true)),
+        )
+        .subcommand(
+            clap::Command::new(VERIFY_SUBCOMMAND)
+                .about("Verify solvers against the sample datasets")
+                .arg(clap::Arg::new(ID_ARG)),
+        )
+        .subcommand(
+            clap::Command::new(FAIDX_SUBCOMMAND)
+                .about("Index fasta files for random access")
+                .subcommand(
+                    clap::Command::new(BUILD_SUBCOMMAND)
+                        .about("Write the .fai index of a fasta file")
+                        .arg(clap::Arg::new(FASTA_ARG).required(true)),
+                )
+                .subcommand(
+                    clap::Command::new(FETCH_SUBCOMMAND)
+                        .about("Print regions of an indexed fasta file")
+                        .arg(clap::Arg::new(FASTA_ARG).required(true))
+                        .arg(
+                            clap::Arg::new(REGION_ARG)
+                                .required(true)
+                                .multiple_values(true)
+                                .help("Region such as chr2 or chr2:1000-2000"),
+                        ),
+                )
+                .subcommand_required(true),
+        )
+        .subcommand(
+            common::with_table(common::subcommand_file(ORFS_SUBCOMMAND))
+                .about("Find open reading frames in fasta records")
+                .arg(
+                    clap::Arg::new(MIN_LENGTH_ARG)
+                        .long(MIN_LENGTH_ARG)
+                        .takes_value(true)
+                        .value_name("bases")
+                        .help("Minimum length of an ORF in bases, including the stop codon"),
+                )
+                .arg(
+                    clap::Arg::new(ALT_STARTS_ARG)
+                        .long(ALT_STARTS_ARG)
+                        .help("Start ORFs at every start codon of the genetic code"),
+                )
+                .arg(
+                    clap::Arg::new(NESTED_ARG)
+                        .long(NESTED_ARG)
+                        .help("Report ORFs that start inside a longer ORF"),
+                )
+                .arg(
+                    clap::Arg::new(GFF_ARG)
+                        .long(GFF_ARG)
+                        .help("Write GFF3 instead of a tab separated table"),
+                ),
+        )
+        .subcommand(
+            common::with_table(common::subcommand_file(TRANSLATE_SUBCOMMAND))
+                .about("Translate fasta records into protein")
+                .arg(
+                    clap::Arg::new(SIX_FRAME_ARG)
+                        .long(SIX_FRAME_ARG)
+                        .help("Translate all six frames instead of only the first"),
+                ),
+        )
+        .subcommand(
+            common::subcommand_file(STATS_SUBCOMMAND)
+                .about("Report sequence statistics of a fasta or fastq file")
+                .arg(
+                    clap::Arg::new(TSV_ARG)
+                        .long(TSV_ARG)
+                        .help("Write tab separated tables instead of text"),
+                )
+                .arg(
+                    clap::Arg::new(SUMMARY_ARG)
+                        .long(SUMMARY_ARG)
+                        .help("Only report the statistics of the whole file"),
+                ),
+        )
+        .subcommand(
+            common::subcommand_file(MSA_SUBCOMMAND)
+                .about("Align fasta records by progressive multiple alignment")
+                .arg(
+                    clap::Arg::new(PROTEIN_ARG)
+                        .long(PROTEIN_ARG)
+                        .help("Score protein with BLOSUM62 instead of DNA matches"),
+                )
+                .arg(
+                    clap::Arg::new(CLUSTAL_ARG)
+                        .long(CLUSTAL_ARG)
+                        .help("Write Clustal instead of aligned fasta"),
+                ),
+        )
+        .subcommand(
+            common::with_pseudocount(common::subcommand_file(SCAN_SUBCOMMAND))
+                .about("Scan both strands of fasta records for a DNA motif")
+                .arg(
+                    clap::Arg::new(MOTIF_ARG)
+                        .long(MOTIF_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("fasta")
+                        .help("Fasta file of aligned sites of the motif"),
+                )
+                .arg(
+                    clap::Arg::new(THRESHOLD_ARG)
+                        .long(THRESHOLD_ARG)
+                        .takes_value(true)
+                        .value_name("score")
+                        .help("Minimum log2-odds score of a hit [default: 80% of the best score]"),
+                ),
+        )
+        .subcommand(
+            common::subcommand_file(SEARCH_SUBCOMMAND)
+                .about("Search a fasta reference for the sequences of fasta or fastq records")
+                .arg(
+                    clap::Arg::new(REFERENCE_ARG)
+                        .long(REFERENCE_ARG)
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("fasta")
+                        .help("Fasta file to search, read from its .fmi index when there is one"),
+                )
+                .arg(
+                    clap::Arg::new(MISMATCHES_ARG)
+                        .long(MISMATCHES_ARG)
+                        .takes_value(true)
+                        .value_name("count")
+                        .help("Maximum number of mismatches of a hit [default: 0]"),
+                )
+                .arg(
+                    clap::Arg::new(SAVE_INDEX_ARG)
+                        .long(SAVE_INDEX_ARG)
+                        .help("Write the .fmi index next to the reference"),
+                ),
+        )
+        .subcommands(
+            problems::registry()
+                .iter()
+                .map(|problem| problem.command().about(problem.title())),
+        )
         .subcommand_required(true)
         .get_matches();
 
+    // Only the answers of problems are typed, so the other subcommands print text alone
+    if let Some((id, matches)) = matches.subcommand() {
+        if problems::find(id).is_none() {
+            text_only(id, matches)?;
+        }
+    }
     match matches.subcommand() {
-        Some((problems::dna::SUBCOMMAND, matches)) => problems::dna::run(matches),
-        Some((problems::rna::SUBCOMMAND, matches)) => problems::rna::run(matches),
-        Some((problems::revc::SUBCOMMAND, matches)) => problems::revc::run(matches),
-        Some((problems::fib::SUBCOMMAND, matches)) => problems::fib::run(matches),
-        Some((problems::gc::SUBCOMMAND, matches)) => problems::gc::run(matches),
-        Some((problems::hamm::SUBCOMMAND, matches)) => problems::hamm::run(matches),
-        Some((problems::iprb::SUBCOMMAND, matches)) => problems::iprb::run(matches),
-        Some((problems::prot::SUBCOMMAND, matches)) => problems::prot::run(matches),
-        Some((problems::subs::SUBCOMMAND, matches)) => problems::subs::run(matches),
-        Some((problems::cons::SUBCOMMAND, matches)) => problems::cons::run(matches),
-        Some((problems::fibd::SUBCOMMAND, matches)) => problems::fibd::run(matches),
-        Some((problems::grph::SUBCOMMAND, matches)) => problems::grph::run(matches),
-        Some((problems::iev::SUBCOMMAND, matches)) => problems::iev::run(matches),
-        Some((problems::lcsm::SUBCOMMAND, matches)) => problems::lcsm::run(matches),
-        Some((problems::lia::SUBCOMMAND, matches)) => problems::lia::run(matches),
-        Some((problems::mprt::SUBCOMMAND, matches)) => problems::mprt::run(matches),
-        Some((problems::mrna::SUBCOMMAND, matches)) => problems::mrna::run(matches),
-        Some((problems::orf::SUBCOMMAND, matches)) => problems::orf::run(matches),
-        Some((problems::perm::SUBCOMMAND, matches)) => problems::perm::run(matches),
-        Some((problems::prtm::SUBCOMMAND, matches)) => problems::prtm::run(matches),
-        Some((problems::revp::SUBCOMMAND, matches)) => problems::revp::run(matches),
-        Some((problems::splc::SUBCOMMAND, matches)) => problems::splc::run(matches),
-        Some((problems::lexf::SUBCOMMAND, matches)) => problems::lexf::run(matches),
-        Some((problems::lgis::SUBCOMMAND, matches)) => problems::lgis::run(matches),
-        Some((problems::tran::SUBCOMMAND, matches)) => problems::tran::run(matches),
+        Some((LIST_SUBCOMMAND, _)) => {
+            list();
+            Ok(())
+        }
+        Some((INFO_SUBCOMMAND, matches)) => info(matches),
+        Some((VERIFY_SUBCOMMAND, matches)) => verify(matches),
+        Some((FAIDX_SUBCOMMAND, matches)) => match matches.subcommand() {
+            Some((BUILD_SUBCOMMAND, matches)) => faidx_build(matches),
+            Some((FETCH_SUBCOMMAND, matches)) => faidx_fetch(matches),
+            _ => Err(Error::new(ErrorKind::User, "unknown subcommand")),
+        },
+        Some((ORFS_SUBCOMMAND, matches)) => orfs(matches),
+        Some((TRANSLATE_SUBCOMMAND, matches)) => translate(matches),
+        Some((STATS_SUBCOMMAND, matches)) => stats(matches),
+        Some((MSA_SUBCOMMAND, matches)) => align(matches),
+        Some((SCAN_SUBCOMMAND, matches)) => scan(matches),
+        Some((SEARCH_SUBCOMMAND, matches)) => search(matches),
+        Some((id, matches)) => {
+            let problem = find(id)?;
+            let format = matches
+                .value_of(problems::FORMAT_ARG)
+                .unwrap_or_default()
+                .parse()?;
+            let answer = problem.run(matches, format)?;
+            // The answer is empty when it was written to a file instead
+            if !answer.is_empty() {
+                println!("{}", answer);
+            }
+            Ok(())
+        }
         _ => Err(Error::new(ErrorKind::User, "unknown subcommand")),
     }
 }
+
+/// Print the ID and title of every registered problem
+fn list() {
+    for problem in problems::registry() {
+        println!("{:<6}{}", problem.id(), problem.title());
+    }
+}
+
+/// Print the metadata of a single problem
+fn info(matches: &clap::ArgMatches) -> Result<()> {
+    let id = matches
+        .value_of(ID_ARG)
+        .ok_or_else(|| Error::new(ErrorKind::User, &format!("{} argument required", ID_ARG)))?;
+    let problem = find(id)?;
+    println!("ID:    {}", problem.id());
+    println!("Title: {}", problem.title());
+    println!("URL:   {}", problem.url());
+    println!("\nSample Dataset\n{}", problem.sample_dataset());
+    println!("\nSample Output\n{}", problem.sample_output());
+    Ok(())
+}
+
+/// Verify one or all problems against their sample datasets
+fn verify(matches: &clap::ArgMatches) -> Result<()> {
+    let problems = match matches.value_of(ID_ARG) {
+        Some(id) => vec![find(id)?],
+        None => problems::registry().to_vec(),
+    };
+    let mut failed = 0;
+    for problem in problems {
+        match problem.verify() {
+            Ok(verification) if verification.passed => println!("{:<6}ok", problem.id()),
+            Ok(verification) => {
+                failed += 1;
+                println!("{:<6}FAILED", problem.id());
+                println!("  expected:\n{}", indent(&verification.expected));
+                println!("  actual:\n{}", indent(&verification.actual));
+            }
+            Err(err) => {
+                failed += 1;
+                println!("{:<6}ERROR {}", problem.id(), err);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Error::new(
+            ErrorKind::User,
+            &format!("{} problem(s) failed verification", failed),
+        ));
+    }
+    Ok(())
+}
+
+/// Write the index of a fasta file next to it
+fn faidx_build(matches: &clap::ArgMatches) -> Result<()> {
+    faidx::write_index(fasta_arg(matches)?)
+}
+
+/// Print regions of an indexed fasta file as fasta records
+fn faidx_fetch(matches: &clap::ArgMatches) -> Result<()> {
+    let path = fasta_arg(matches)?;
+    let regions = matches
+        .values_of(REGION_ARG)
+        .into_iter()
+        .flatten()
+        .map(str::parse)
+        .collect::<Result<Vec<faidx::Region>>>()?;
+    faidx::IndexedReader::from_path(path)
+        .and_then(|mut reader| reader.write_regions(&regions, std::io::stdout().lock()))
+        .map_err(|e| e.in_file(path))
+}
+
+/// Print the open reading frames of every fasta record as a table or GFF
+fn orfs(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    let min_length = match matches.value_of(MIN_LENGTH_ARG) {
+        Some(length) => common::parse_token(length, length)?,
+        None => 0,
+    };
+    orf::Finder::new(common::genetic_code(matches)?)
+        .min_length(min_length)
+        .alternative_starts(matches.contains_id(ALT_STARTS_ARG))
+        .nested(matches.contains_id(NESTED_ARG))
+        .write_orfs(
+            &input,
+            matches.contains_id(GFF_ARG),
+            std::io::stdout().lock(),
+        )
+}
+
+/// Print the translation of every fasta record as fasta
+fn translate(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    orf::write_translations(
+        &input,
+        common::genetic_code(matches)?,
+        matches.contains_id(SIX_FRAME_ARG),
+        std::io::stdout().lock(),
+    )
+}
+
+/// Print the statistics of every record of a fasta or fastq file, followed by the whole file
+fn stats(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    stats::write_report(
+        &input,
+        matches.contains_id(TSV_ARG),
+        matches.contains_id(SUMMARY_ARG),
+        std::io::stdout().lock(),
+    )
+}
+
+/// Print the multiple alignment of the fasta records as aligned fasta or Clustal
+fn align(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    let scoring = match matches.contains_id(PROTEIN_ARG) {
+        true => msa::PROTEIN,
+        false => msa::DNA,
+    };
+    msa::write_alignment(
+        &input,
+        &scoring,
+        matches.contains_id(CLUSTAL_ARG),
+        std::io::stdout().lock(),
+    )
+}
+
+/// Print the windows of every fasta record that match the motif as a table
+fn scan(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    let motif = matches
+        .value_of(MOTIF_ARG)
+        .ok_or_else(|| Error::new(ErrorKind::User, &format!("{} argument required", MOTIF_ARG)))?;
+    let pwm = profile::Pwm::<Dna>::from_fasta(
+        &common::input::Input::File(motif.to_owned()),
+        common::pseudocount(matches)?,
+    )?;
+    let threshold = match matches.value_of(THRESHOLD_ARG) {
+        Some(score) => common::parse_token(score, score)?,
+        None => pwm.default_threshold(),
+    };
+    pwm.write_scan(&input, threshold, std::io::stdout().lock())
+}
+
+/// Print the occurrences of every query in the reference as a table
+fn search(matches: &clap::ArgMatches) -> Result<()> {
+    let input = common::input::Input::from_matches(matches)?;
+    let reference = matches.value_of(REFERENCE_ARG).ok_or_else(|| {
+        Error::new(
+            ErrorKind::User,
+            &format!("{} argument required", REFERENCE_ARG),
+        )
+    })?;
+    let mismatches = match matches.value_of(MISMATCHES_ARG) {
+        Some(count) => common::parse_token(count, count)?,
+        None => 0,
+    };
+    let index = fmindex::FmIndex::from_path(reference).map_err(|e| e.in_file(reference))?;
+    if matches.contains_id(SAVE_INDEX_ARG) {
+        index.save(reference)?;
+    }
+    index.write_search(&input, mismatches, std::io::stdout().lock())
+}
+
+/// Return the fasta path argument
+fn fasta_arg(matches: &clap::ArgMatches) -> Result<&str> {
+    matches
+        .value_of(FASTA_ARG)
+        .ok_or_else(|| Error::new(ErrorKind::User, &format!("{} argument required", FASTA_ARG)))
+}
+
+/// Indent every line of the text
+fn indent(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Return an error if a format other than text is selected for the subcommand
+fn text_only(id: &str, matches: &clap::ArgMatches) -> Result<()> {
+    let format = matches.value_of(problems::FORMAT_ARG).unwrap_or_default();
+    match format.parse()? {
+        problems::Format::Text => Ok(()),
+        _ => Err(Error::new(
+            ErrorKind::User,
+            &format!("{} only supports text output, not {}", id, format),
+        )),
+    }
+}
+
+/// Find a registered problem by its ID
+fn find(id: &str) -> Result<&'static dyn problems::DynProblem> {
+    problems::find(id)
+        .ok_or_else(|| Error::new(ErrorKind::User, &format!("unknown problem: {}", id)))
+}
+
+/// Render an error for the terminal
+///
+/// When the location of the error is known, the offending line of the input is shown with a marker
+/// under the offending column, for example:
+///
+/// ```text
+/// error[parse]: invalid digit found in string
+///  --> fib.txt:1:3
+///   |
+/// 1 | 5 x
+///   |   ^
+/// ```
+///
+pub fn report(err: &Error) -> String {
+    let mut output = format!("error[{}]: {}", err.kind(), err.message());
+    match (err.location(), err.file()) {
+        (Some(location), file) => {
+            let number = location.line.to_string();
+            let padding = " ".repeat(number.len());
+            output.push_str(&format!(
+                "\n{}--> {}:{}:{}",
+                padding,
+                file.unwrap_or("<input>"),
+                location.line,
+                location.column
+            ));
+            output.push_str(&format!("\n{} |", padding));
+            output.push_str(&format!("\n{} | {}", number, location.text));
+            output.push_str(&format!(
+                "\n{} | {}^",
+                padding,
+                " ".repeat(location.column.saturating_sub(1))
+            ));
+        }
+        (None, Some(file)) => output.push_str(&format!("\n --> {}", file)),
+        (None, None) => (),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosalind_rust::errors::Location;
+
+    #[test]
+    fn test_report() {
+        struct TestCase<'a> {
+            name: &'a str,
+            err: Error,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should render a snippet of the offending input",
+                err: Error::new(ErrorKind::Parse, "invalid digit found in string")
+                    .with_location(Location::new(1, 3, "5 x"))
+                    .in_file("fib.txt"),
+                expected: "error[parse]: invalid digit found in string\n --> fib.txt:1:3\n  |\n1 | 5 x\n  |   ^",
+            },
+            TestCase {
+                name: "Should render the file without a location",
+                err: Error::new(ErrorKind::IO, "No such file or directory").in_file("fib.txt"),
+                expected: "error[io]: No such file or directory\n --> fib.txt",
+            },
+            TestCase {
+                name: "Should render the message only",
+                err: Error::new(ErrorKind::User, "unknown problem: abc"),
+                expected: "error[usage]: unknown problem: abc",
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                report(&test_case.err),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_only() {
+        struct TestCase<'a> {
+            name: &'a str,
+            args: &'a [&'a str],
+            expected: std::result::Result<(), ErrorKind>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should accept the default format",
+                args: &["stats"],
+                expected: Ok(()),
+            },
+            TestCase {
+                name: "Should accept text",
+                args: &["stats", "--format", "text"],
+                expected: Ok(()),
+            },
+            TestCase {
+                name: "Should reject json",
+                args: &["stats", "--format", "json"],
+                expected: Err(ErrorKind::User),
+            },
+        ];
+        for test_case in test_cases {
+            let matches = clap::Command::new(STATS_SUBCOMMAND)
+                .arg(
+                    clap::Arg::new(problems::FORMAT_ARG)
+                        .long(problems::FORMAT_ARG)
+                        .takes_value(true)
+                        .default_value("text"),
+                )
+                .get_matches_from(test_case.args);
+            assert_eq!(
+                test_case.expected,
+                text_only(STATS_SUBCOMMAND, &matches).map_err(|e| *e.kind()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
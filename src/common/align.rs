@@ -0,0 +1,1120 @@
+//! Pairwise sequence alignment
+//!
+//! Alignments are scored with a substitution score for every aligned pair of symbols and a gap
+//! penalty for every run of gaps.  Affine gaps cost `open` for the first symbol of a run and
+//! `extend` for every further symbol, and linear gaps are the special case where both are equal.
+//! Alignments are computed with the three matrices of Gotoh's algorithm, in O(nm) time and space.
+//! Global, local (Smith-Waterman), fitting, overlap and semi-global alignments differ only in
+//! which leading and trailing gaps are free.
+//!
+use crate::errors::{Error, ErrorKind, Result};
+use std::ops::Range;
+
+/// Symbol used for gaps in aligned strings
+pub const GAP: u8 = b'-';
+/// Amino acids in the order of the rows and columns of the built-in matrices
+const AMINO_ACIDS: &[u8; 20] = b"ARNDCQEGHILKMFPSTWYV";
+/// Score that is never reached, low enough to not overflow when penalties are subtracted
+const NEG_INF: i32 = i32::MIN / 4;
+
+/// Substitution matrix over the 20 amino acids
+#[derive(Debug, PartialEq)]
+pub struct Matrix {
+    name: &'static str,
+    scores: [[i32; 20]; 20],
+}
+
+impl Matrix {
+    /// BLOSUM62 matrix
+    pub const BLOSUM62: Self = Self {
+        name: "BLOSUM62",
+        scores: [
+            [
+                4, -1, -2, -2, 0, -1, -1, 0, -2, -1, -1, -1, -1, -2, -1, 1, 0, -3, -2, 0,
+            ],
+            [
+                -1, 5, 0, -2, -3, 1, 0, -2, 0, -3, -2, 2, -1, -3, -2, -1, -1, -3, -2, -3,
+            ],
+            [
+                -2, 0, 6, 1, -3, 0, 0, 0, 1, -3, -3, 0, -2, -3, -2, 1, 0, -4, -2, -3,
+            ],
+            [
+                -2, -2, 1, 6, -3, 0, 2, -1, -1, -3, -4, -1, -3, -3, -1, 0, -1, -4, -3, -3,
+            ],
+            [
+                0, -3, -3, -3, 9, -3, -4, -3, -3, -1, -1, -3, -1, -2, -3, -1, -1, -2, -2, -1,
+            ],
+            [
+                -1, 1, 0, 0, -3, 5, 2, -2, 0, -3, -2, 1, 0, -3, -1, 0, -1, -2, -1, -2,
+            ],
+            [
+                -1, 0, 0, 2, -4, 2, 5, -2, 0, -3, -3, 1, -2, -3, -1, 0, -1, -3, -2, -2,
+            ],
+            [
+                0, -2, 0, -1, -3, -2, -2, 6, -2, -4, -4, -2, -3, -3, -2, 0, -2, -2, -3, -3,
+            ],
+            [
+                -2, 0, 1, -1, -3, 0, 0, -2, 8, -3, -3, -1, -2, -1, -2, -1, -2, -2, 2, -3,
+            ],
+            [
+                -1, -3, -3, -3, -1, -3, -3, -4, -3, 4, 2, -3, 1, 0, -3, -2, -1, -3, -1, 3,
+            ],
+            [
+                -1, -2, -3, -4, -1, -2, -3, -4, -3, 2, 4, -2, 2, 0, -3, -2, -1, -2, -1, 1,
+            ],
+            [
+                -1, 2, 0, -1, -3, 1, 1, -2, -1, -3, -2, 5, -1, -3, -1, 0, -1, -3, -2, -2,
+            ],
+            [
+                -1, -1, -2, -3, -1, 0, -2, -3, -2, 1, 2, -1, 5, 0, -2, -1, -1, -1, -1, 1,
+            ],
+            [
+                -2, -3, -3, -3, -2, -3, -3, -3, -1, 0, 0, -3, 0, 6, -4, -2, -2, 1, 3, -1,
+            ],
+            [
+                -1, -2, -2, -1, -3, -1, -1, -2, -2, -3, -3, -1, -2, -4, 7, -1, -1, -4, -3, -2,
+            ],
+            [
+                1, -1, 1, 0, -1, 0, 0, 0, -1, -2, -2, 0, -1, -2, -1, 4, 1, -3, -2, -2,
+            ],
+            [
+                0, -1, 0, -1, -1, -1, -1, -2, -2, -1, -1, -1, -1, -2, -1, 1, 5, -2, -2, 0,
+            ],
+            [
+                -3, -3, -4, -4, -2, -2, -3, -2, -2, -3, -2, -3, -1, 1, -4, -3, -2, 11, 2, -3,
+            ],
+            [
+                -2, -2, -2, -3, -2, -1, -2, -3, 2, -1, -1, -2, -1, 3, -3, -2, -2, 2, 7, -1,
+            ],
+            [
+                0, -3, -3, -3, -1, -2, -2, -3, -3, 3, 1, -2, 1, -1, -2, -2, 0, -3, -1, 4,
+            ],
+        ],
+    };
+
+    /// PAM250 matrix
+    pub const PAM250: Self = Self {
+        name: "PAM250",
+        scores: [
+            [
+                2, -2, 0, 0, -2, 0, 0, 1, -1, -1, -2, -1, -1, -3, 1, 1, 1, -6, -3, 0,
+            ],
+            [
+                -2, 6, 0, -1, -4, 1, -1, -3, 2, -2, -3, 3, 0, -4, 0, 0, -1, 2, -4, -2,
+            ],
+            [
+                0, 0, 2, 2, -4, 1, 1, 0, 2, -2, -3, 1, -2, -3, 0, 1, 0, -4, -2, -2,
+            ],
+            [
+                0, -1, 2, 4, -5, 2, 3, 1, 1, -2, -4, 0, -3, -6, -1, 0, 0, -7, -4, -2,
+            ],
+            [
+                -2, -4, -4, -5, 12, -5, -5, -3, -3, -2, -6, -5, -5, -4, -3, 0, -2, -8, 0, -2,
+            ],
+            [
+                0, 1, 1, 2, -5, 4, 2, -1, 3, -2, -2, 1, -1, -5, 0, -1, -1, -5, -4, -2,
+            ],
+            [
+                0, -1, 1, 3, -5, 2, 4, 0, 1, -2, -3, 0, -2, -5, -1, 0, 0, -7, -4, -2,
+            ],
+            [
+                1, -3, 0, 1, -3, -1, 0, 5, -2, -3, -4, -2, -3, -5, 0, 1, 0, -7, -5, -1,
+            ],
+            [
+                -1, 2, 2, 1, -3, 3, 1, -2, 6, -2, -2, 0, -2, -2, 0, -1, -1, -3, 0, -2,
+            ],
+            [
+                -1, -2, -2, -2, -2, -2, -2, -3, -2, 5, 2, -2, 2, 1, -2, -1, 0, -5, -1, 4,
+            ],
+            [
+                -2, -3, -3, -4, -6, -2, -3, -4, -2, 2, 6, -3, 4, 2, -3, -3, -2, -2, -1, 2,
+            ],
+            [
+                -1, 3, 1, 0, -5, 1, 0, -2, 0, -2, -3, 5, 0, -5, -1, 0, 0, -3, -4, -2,
+            ],
+            [
+                -1, 0, -2, -3, -5, -1, -2, -3, -2, 2, 4, 0, 6, 0, -2, -2, -1, -4, -2, 2,
+            ],
+            [
+                -3, -4, -3, -6, -4, -5, -5, -5, -2, 1, 2, -5, 0, 9, -5, -3, -3, 0, 7, -1,
+            ],
+            [
+                1, 0, 0, -1, -3, 0, -1, 0, 0, -2, -3, -1, -2, -5, 6, 1, 0, -6, -5, -1,
+            ],
+            [
+                1, 0, 1, 0, 0, -1, 0, 1, -1, -1, -3, 0, -2, -3, 1, 2, 1, -2, -3, -1,
+            ],
+            [
+                1, -1, 0, 0, -2, -1, 0, 0, -1, 0, -2, 0, -1, -3, 0, 1, 3, -5, -3, 0,
+            ],
+            [
+                -6, 2, -4, -7, -8, -5, -7, -7, -3, -5, -2, -3, -4, 0, -6, -2, -5, 17, 0, -6,
+            ],
+            [
+                -3, -4, -2, -4, 0, -4, -4, -5, 0, -1, -1, -4, -2, 7, -5, -3, -3, 0, 10, -2,
+            ],
+            [
+                0, -2, -2, -2, -2, -2, -2, -1, -2, 4, 2, -2, 2, -1, -1, -1, 0, -6, -2, 4,
+            ],
+        ],
+    };
+
+    /// Returns the name of the matrix
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return the score of two amino acids, in either case
+    pub fn score(&self, a: u8, b: u8) -> Option<i32> {
+        Some(self.scores[amino_acid_index(a)?][amino_acid_index(b)?])
+    }
+}
+
+/// Score of aligning two symbols
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Substitution {
+    /// Scores looked up in a substitution matrix
+    Matrix(&'static Matrix),
+    /// One score for identical symbols and another for different symbols
+    MatchMismatch { matching: i32, mismatch: i32 },
+}
+
+impl Substitution {
+    /// Default DNA scores of +1 for a match and -1 for a mismatch
+    pub const DNA: Self = Self::MatchMismatch {
+        matching: 1,
+        mismatch: -1,
+    };
+
+    /// Return the score of two symbols, or None if the matrix has no score for them
+    pub fn score(&self, a: u8, b: u8) -> Option<i32> {
+        match self {
+            Self::Matrix(matrix) => matrix.score(a, b),
+            Self::MatchMismatch { matching, mismatch } => match a.eq_ignore_ascii_case(&b) {
+                true => Some(*matching),
+                false => Some(*mismatch),
+            },
+        }
+    }
+
+    /// Check that every symbol of the sequence can be scored
+    pub(crate) fn validate(&self, sequence: &[u8]) -> Result<()> {
+        let Self::Matrix(matrix) = self else {
+            return Ok(());
+        };
+        match sequence.iter().position(|a| matrix.score(*a, *a).is_none()) {
+            Some(i) => Err(Error::new(
+                ErrorKind::Alphabet,
+                &format!(
+                    "not a {} symbol: {} at position {}",
+                    matrix.name,
+                    sequence[i] as char,
+                    i + 1
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Penalty of a run of gaps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gap {
+    /// Every gap costs the same
+    Linear(i32),
+    /// The first gap of a run costs `open` and every further gap costs `extend`
+    Affine { open: i32, extend: i32 },
+}
+
+impl Gap {
+    /// Returns the penalties of opening and of extending a run of gaps
+    pub(crate) fn penalties(&self) -> (i32, i32) {
+        match self {
+            Self::Linear(penalty) => (*penalty, *penalty),
+            Self::Affine { open, extend } => (*open, *extend),
+        }
+    }
+}
+
+/// Substitution scores and gap penalties
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scoring {
+    pub substitution: Substitution,
+    pub gap: Gap,
+}
+
+impl Scoring {
+    pub fn new(substitution: Substitution, gap: Gap) -> Self {
+        Self { substitution, gap }
+    }
+
+    /// Scoring whose optimal global alignment score is the negated edit distance
+    pub fn edit_distance() -> Self {
+        Self::new(
+            Substitution::MatchMismatch {
+                matching: 0,
+                mismatch: -1,
+            },
+            Gap::Linear(1),
+        )
+    }
+}
+
+/// Which parts of the sequences are aligned
+///
+/// Gaps outside the aligned parts are free, which is what distinguishes the modes.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Both sequences end to end (Needleman-Wunsch)
+    Global,
+    /// A substring of each sequence (Smith-Waterman)
+    Local,
+    /// All of the second sequence against a substring of the first
+    Fitting,
+    /// A suffix of the first sequence against a prefix of the second
+    Overlap,
+    /// Both sequences end to end, with free gaps before and after either sequence
+    SemiGlobal,
+}
+
+impl Mode {
+    /// Check to see if the alignment may start after the first symbol of the first sequence
+    fn skips_start1(&self) -> bool {
+        *self != Self::Global
+    }
+
+    /// Check to see if the alignment may start after the first symbol of the second sequence
+    fn skips_start2(&self) -> bool {
+        matches!(self, Self::Local | Self::SemiGlobal)
+    }
+
+    /// Check to see if the alignment may end before the last symbol of the first sequence
+    fn skips_end1(&self) -> bool {
+        matches!(self, Self::Local | Self::Fitting | Self::SemiGlobal)
+    }
+
+    /// Check to see if the alignment may end before the last symbol of the second sequence
+    fn skips_end2(&self) -> bool {
+        matches!(self, Self::Local | Self::Overlap | Self::SemiGlobal)
+    }
+}
+
+/// Alignment of two sequences
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Alignment {
+    pub score: i32,
+    /// Aligned part of the first sequence with gaps inserted
+    pub aligned1: String,
+    /// Aligned part of the second sequence with gaps inserted
+    pub aligned2: String,
+    /// Positions of the first sequence that are aligned, 0-based and half-open
+    pub range1: Range<usize>,
+    /// Positions of the second sequence that are aligned, 0-based and half-open
+    pub range2: Range<usize>,
+}
+
+impl Alignment {
+    /// Add the unaligned parts of the sequences to the aligned strings, against gaps
+    ///
+    /// Only one sequence can have an unaligned part at each end in global and semi-global
+    /// alignments, which is what this is meant for.
+    ///
+    pub fn pad(&self, seq1: &[u8], seq2: &[u8]) -> (String, String) {
+        let gaps = |n: usize| char::from(GAP).to_string().repeat(n);
+        let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+        let before1 = &seq1[..self.range1.start];
+        let before2 = &seq2[..self.range2.start];
+        let after1 = &seq1[self.range1.end..];
+        let after2 = &seq2[self.range2.end..];
+        (
+            [
+                text(before1),
+                gaps(before2.len()),
+                self.aligned1.clone(),
+                text(after1),
+                gaps(after2.len()),
+            ]
+            .concat(),
+            [
+                gaps(before1.len()),
+                text(before2),
+                self.aligned2.clone(),
+                gaps(after1.len()),
+                text(after2),
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// Best local alignment score and the aligned substrings, without their gaps
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct LocalAlignment {
+    pub score: i32,
+    pub substring1: String,
+    pub substring2: String,
+}
+
+impl LocalAlignment {
+    /// Locally align two sequences (Smith-Waterman)
+    pub fn new(seq1: &str, seq2: &str, scoring: &Scoring) -> Result<Self> {
+        let alignment = align(seq1.as_bytes(), seq2.as_bytes(), scoring, Mode::Local)?;
+        Ok(Self {
+            score: alignment.score,
+            substring1: seq1[alignment.range1].to_owned(),
+            substring2: seq2[alignment.range2].to_owned(),
+        })
+    }
+
+    /// Check that the substrings occur in the sequences and align globally with the score
+    pub fn achieves_score(&self, seq1: &str, seq2: &str, scoring: &Scoring) -> Result<bool> {
+        if !seq1.contains(&self.substring1) || !seq2.contains(&self.substring2) {
+            return Ok(false);
+        }
+        let substrings = (self.substring1.as_bytes(), self.substring2.as_bytes());
+        Ok(global(substrings.0, substrings.1, scoring)?.score == self.score)
+    }
+}
+
+impl std::fmt::Display for LocalAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\n{}\n{}",
+            self.score, self.substring1, self.substring2
+        )
+    }
+}
+
+/// Globally align two sequences end to end (Needleman-Wunsch)
+pub fn global(seq1: &[u8], seq2: &[u8], scoring: &Scoring) -> Result<Alignment> {
+    align(seq1, seq2, scoring, Mode::Global)
+}
+
+/// Align two sequences
+///
+/// Among alignments with the optimal score, the one ending earliest in the first sequence and
+/// then in the second sequence is returned.  When tracing back from the end, gaps in the first
+/// sequence are preferred over gaps in the second sequence, which are preferred over
+/// substitutions, and an alignment is ended as soon as it can be.
+///
+pub fn align(seq1: &[u8], seq2: &[u8], scoring: &Scoring, mode: Mode) -> Result<Alignment> {
+    scoring.substitution.validate(seq1)?;
+    scoring.substitution.validate(seq2)?;
+    let pairwise = Pairwise {
+        seq1,
+        seq2,
+        scoring,
+    };
+    let path = Path::find(&pairwise, seq1.len(), seq2.len(), mode);
+    let (mut i, mut j) = (path.range1.start, path.range2.start);
+    let mut aligned1 = Vec::with_capacity(path.steps.len());
+    let mut aligned2 = Vec::with_capacity(path.steps.len());
+    for step in &path.steps {
+        let (a, b) = match step {
+            Step::Substitution => (seq1[i], seq2[j]),
+            Step::Deletion => (seq1[i], GAP),
+            Step::Insertion => (GAP, seq2[j]),
+        };
+        aligned1.push(a);
+        aligned2.push(b);
+        (i, j) = step.advance(i, j);
+    }
+    Ok(Alignment {
+        score: path.score,
+        aligned1: aligned1.into_iter().map(char::from).collect(),
+        aligned2: aligned2.into_iter().map(char::from).collect(),
+        range1: path.range1,
+        range2: path.range2,
+    })
+}
+
+/// Return the row of an alignment without its gaps
+pub fn ungapped(row: &str) -> String {
+    row.replace(char::from(GAP), "")
+}
+
+/// Return the score of an alignment given as gapped rows
+///
+/// Every run of gaps in a row costs the open penalty for its first gap and the extend penalty for
+/// the others, as in `align`.  Runs of gaps at either end of a row are free when `free_ends` is
+/// set, as in semi-global alignment.
+///
+pub fn score(row1: &[u8], row2: &[u8], scoring: &Scoring, free_ends: bool) -> Result<i32> {
+    if row1.len() != row2.len() {
+        return Err(Error::new(
+            ErrorKind::Validation,
+            &format!(
+                "expected rows of equal length, found lengths {} and {}",
+                row1.len(),
+                row2.len()
+            ),
+        ));
+    }
+    let (mut start, mut end) = (0, row1.len());
+    if free_ends {
+        let leading = |row: &[u8]| row.iter().take_while(|a| **a == GAP).count();
+        let trailing = |row: &[u8]| row.iter().rev().take_while(|a| **a == GAP).count();
+        start = leading(row1).max(leading(row2));
+        end = end
+            .saturating_sub(trailing(row1).max(trailing(row2)))
+            .max(start);
+    }
+    let (open, extend) = scoring.gap.penalties();
+    let mut score = 0;
+    let mut previous = None;
+    for i in start..end {
+        let step = match (row1[i], row2[i]) {
+            (GAP, GAP) => {
+                return Err(Error::new(
+                    ErrorKind::Validation,
+                    &format!("gap aligned to a gap at position {}", i + 1),
+                ))
+            }
+            (GAP, _) => Step::Insertion,
+            (_, GAP) => Step::Deletion,
+            (a, b) => {
+                score += scoring.substitution.score(a, b).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::Alphabet,
+                        &format!(
+                            "cannot score {} against {} at position {}",
+                            a as char,
+                            b as char,
+                            i + 1
+                        ),
+                    )
+                })?;
+                Step::Substitution
+            }
+        };
+        match step {
+            Step::Substitution => (),
+            step if previous == Some(step) => score -= extend,
+            _ => score -= open,
+        }
+        previous = Some(step);
+    }
+    Ok(score)
+}
+
+/// Scores of aligning the positions of two sequences
+///
+/// Positions are symbols of pairwise alignments, and columns of profile alignments.
+///
+pub(crate) trait Scores {
+    /// Return the score of aligning position i of the first sequence to position j of the second
+    fn substitution(&self, i: usize, j: usize) -> i32;
+
+    /// Return the penalties of opening and of extending a gap against position i of the first
+    /// sequence
+    fn deletion(&self, i: usize) -> (i32, i32);
+
+    /// Return the penalties of opening and of extending a gap against position j of the second
+    /// sequence
+    fn insertion(&self, j: usize) -> (i32, i32);
+}
+
+/// Scores of aligning two sequences of symbols
+struct Pairwise<'a> {
+    seq1: &'a [u8],
+    seq2: &'a [u8],
+    scoring: &'a Scoring,
+}
+
+impl Scores for Pairwise<'_> {
+    fn substitution(&self, i: usize, j: usize) -> i32 {
+        self.scoring
+            .substitution
+            .score(self.seq1[i], self.seq2[j])
+            .unwrap_or(NEG_INF)
+    }
+
+    fn deletion(&self, _: usize) -> (i32, i32) {
+        self.scoring.gap.penalties()
+    }
+
+    fn insertion(&self, _: usize) -> (i32, i32) {
+        self.scoring.gap.penalties()
+    }
+}
+
+/// Step of an alignment, which is also the matrix of the best alignments of prefixes ending in it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Step {
+    /// Two aligned positions
+    Substitution,
+    /// A position of the first sequence aligned to a gap
+    Deletion,
+    /// A position of the second sequence aligned to a gap
+    Insertion,
+}
+
+impl Step {
+    /// Return the positions of both sequences after the step
+    pub(crate) fn advance(&self, i: usize, j: usize) -> (usize, usize) {
+        match self {
+            Self::Substitution => (i + 1, j + 1),
+            Self::Deletion => (i + 1, j),
+            Self::Insertion => (i, j + 1),
+        }
+    }
+}
+
+/// Order in which steps are chosen among equally good alignments during traceback
+const PREFERENCE: [Step; 3] = [Step::Insertion, Step::Deletion, Step::Substitution];
+
+/// Steps of an optimal alignment and the positions of both sequences that it covers
+pub(crate) struct Path {
+    pub(crate) score: i32,
+    pub(crate) steps: Vec<Step>,
+    pub(crate) range1: Range<usize>,
+    pub(crate) range2: Range<usize>,
+}
+
+impl Path {
+    /// Find an optimal alignment of two sequences of the given lengths
+    pub(crate) fn find<S: Scores>(scores: &S, length1: usize, length2: usize, mode: Mode) -> Self {
+        Matrices::fill(scores, length1, length2, mode).traceback(scores)
+    }
+}
+
+/// Dynamic programming matrices of Gotoh's algorithm
+///
+/// An alignment starting at a cell is represented by a score of 0 in the substitution matrix,
+/// which is where traceback stops.  Local alignments may also restart with the substitution at a
+/// cell, which is flagged since the cell before it need not score 0.
+///
+struct Matrices {
+    mode: Mode,
+    rows: usize,
+    columns: usize,
+    substitution: Vec<i32>,
+    deletion: Vec<i32>,
+    insertion: Vec<i32>,
+    /// Whether the best alignment ending with the substitution at a cell starts there
+    restarts: Vec<bool>,
+}
+
+impl Matrices {
+    /// Fill the matrices for the prefixes of both sequences
+    fn fill<S: Scores>(scores: &S, length1: usize, length2: usize, mode: Mode) -> Self {
+        let rows = length1 + 1;
+        let columns = length2 + 1;
+        let size = rows * columns;
+        let mut matrices = Self {
+            mode,
+            rows,
+            columns,
+            substitution: vec![NEG_INF; size],
+            deletion: vec![NEG_INF; size],
+            insertion: vec![NEG_INF; size],
+            restarts: vec![false; size],
+        };
+        matrices.substitution[0] = 0;
+        let start = match mode {
+            Mode::Local => 0,
+            _ => NEG_INF,
+        };
+        for i in 0..rows {
+            for j in 0..columns {
+                let here = i * columns + j;
+                if i == 0 && j > 0 && mode.skips_start2() || j == 0 && i > 0 && mode.skips_start1()
+                {
+                    matrices.substitution[here] = 0;
+                    continue;
+                }
+                if i > 0 && j > 0 {
+                    let diagonal = here - columns - 1;
+                    let best = matrices.best(diagonal);
+                    matrices.substitution[here] =
+                        scores.substitution(i - 1, j - 1) + best.max(start);
+                    matrices.restarts[here] = mode == Mode::Local && best <= start;
+                }
+                if i > 0 {
+                    let up = here - columns;
+                    let (open, extend) = scores.deletion(i - 1);
+                    matrices.deletion[here] = (matrices.substitution[up] - open)
+                        .max(matrices.deletion[up] - extend)
+                        .max(matrices.insertion[up] - open);
+                }
+                if j > 0 {
+                    let left = here - 1;
+                    let (open, extend) = scores.insertion(j - 1);
+                    matrices.insertion[here] = (matrices.substitution[left] - open)
+                        .max(matrices.insertion[left] - extend)
+                        .max(matrices.deletion[left] - open);
+                }
+            }
+        }
+        matrices
+    }
+
+    /// Return the score of a cell of one of the matrices
+    fn get(&self, step: Step, cell: usize) -> i32 {
+        match step {
+            Step::Substitution => self.substitution[cell],
+            Step::Deletion => self.deletion[cell],
+            Step::Insertion => self.insertion[cell],
+        }
+    }
+
+    /// Return the best score of a cell across the matrices
+    fn best(&self, cell: usize) -> i32 {
+        self.substitution[cell]
+            .max(self.deletion[cell])
+            .max(self.insertion[cell])
+    }
+
+    /// Return the cell where the best alignment of the mode ends
+    fn end(&self) -> usize {
+        let last = self.rows * self.columns - 1;
+        let mut candidates: Vec<usize> = vec![last];
+        match self.mode {
+            Mode::Local => candidates.extend(0..=last),
+            mode => {
+                if mode.skips_end1() {
+                    candidates.extend((0..self.rows).map(|i| i * self.columns + self.columns - 1));
+                }
+                if mode.skips_end2() {
+                    candidates.extend((self.rows - 1) * self.columns..=last);
+                }
+            }
+        }
+        candidates.sort_unstable();
+        candidates
+            .into_iter()
+            .fold(last, |end, cell| match self.best(cell) > self.best(end) {
+                true => cell,
+                false => end,
+            })
+    }
+
+    /// Return the first step, in order of preference, whose score at the cell less the penalty
+    /// of leaving it is the target
+    fn find<F>(&self, cell: usize, target: i32, penalty: F) -> Step
+    where
+        F: Fn(Step) -> i32,
+    {
+        PREFERENCE
+            .into_iter()
+            .find(|step| self.get(*step, cell) - penalty(*step) == target)
+            .unwrap_or(Step::Substitution)
+    }
+
+    /// Check to see if an alignment can start at the cell
+    fn is_start(&self, cell: usize) -> bool {
+        let (i, j) = (cell / self.columns, cell % self.columns);
+        self.substitution[cell] == 0
+            && match self.mode {
+                Mode::Local => true,
+                _ => i == 0 || j == 0,
+            }
+    }
+
+    /// Trace the best alignment back from where it ends
+    fn traceback<S: Scores>(&self, scores: &S) -> Path {
+        let end = self.end();
+        let (mut i, mut j) = (end / self.columns, end % self.columns);
+        let (end1, end2) = (i, j);
+        let score = self.best(end);
+        let mut step = self.find(end, score, |_| 0);
+        let mut steps = Vec::new();
+        loop {
+            let here = i * self.columns + j;
+            if step == Step::Substitution && self.is_start(here) {
+                break;
+            }
+            steps.push(step);
+            let target = self.get(step, here);
+            match step {
+                Step::Substitution => {
+                    let previous = here - self.columns - 1;
+                    let target = target - scores.substitution(i - 1, j - 1);
+                    i -= 1;
+                    j -= 1;
+                    if self.restarts[here] {
+                        break;
+                    }
+                    step = match target {
+                        0 if self.is_start(previous) => Step::Substitution,
+                        target => self.find(previous, target, |_| 0),
+                    };
+                }
+                Step::Deletion => {
+                    let (open, extend) = scores.deletion(i - 1);
+                    step = self.find(here - self.columns, target, |step| match step {
+                        Step::Deletion => extend,
+                        _ => open,
+                    });
+                    i -= 1;
+                }
+                Step::Insertion => {
+                    let (open, extend) = scores.insertion(j - 1);
+                    step = self.find(here - 1, target, |step| match step {
+                        Step::Insertion => extend,
+                        _ => open,
+                    });
+                    j -= 1;
+                }
+            }
+        }
+        steps.reverse();
+        Path {
+            score,
+            steps,
+            range1: i..end1,
+            range2: j..end2,
+        }
+    }
+}
+
+/// Return the index of an amino acid in the built-in matrices
+fn amino_acid_index(a: u8) -> Option<usize> {
+    AMINO_ACIDS
+        .iter()
+        .position(|b| *b == a.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_is_symmetric() {
+        for matrix in [&Matrix::BLOSUM62, &Matrix::PAM250] {
+            for a in AMINO_ACIDS {
+                for b in AMINO_ACIDS {
+                    assert_eq!(
+                        matrix.score(*a, *b),
+                        matrix.score(*b, *a),
+                        "Should be symmetric: {} {}{}",
+                        matrix.name(),
+                        *a as char,
+                        *b as char
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_global() {
+        struct TestCase<'a> {
+            name: &'a str,
+            seq1: &'a str,
+            seq2: &'a str,
+            scoring: Scoring,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should align with the edit distance",
+                seq1: "PRETTY",
+                seq2: "PRTTEIN",
+                scoring: Scoring::edit_distance(),
+                expected: (-4, "PRETTY--", "PR-TTEIN"),
+            },
+            TestCase {
+                name: "Should align with a linear gap penalty",
+                seq1: "PLEASANTLY",
+                seq2: "MEANLY",
+                scoring: Scoring::new(Substitution::Matrix(&Matrix::BLOSUM62), Gap::Linear(5)),
+                expected: (8, "PLEASANTLY", "-MEA--N-LY"),
+            },
+            TestCase {
+                name: "Should align with an affine gap penalty",
+                seq1: "PRTEINS",
+                seq2: "PRTWPSEIN",
+                scoring: Scoring::new(
+                    Substitution::Matrix(&Matrix::BLOSUM62),
+                    Gap::Affine {
+                        open: 11,
+                        extend: 1,
+                    },
+                ),
+                expected: (8, "PRT---EINS", "PRTWPSEIN-"),
+            },
+            TestCase {
+                name: "Should align against an empty sequence",
+                seq1: "ACG",
+                seq2: "",
+                scoring: Scoring::new(Substitution::DNA, Gap::Linear(2)),
+                expected: (-6, "ACG", "---"),
+            },
+        ];
+        for test_case in test_cases {
+            let alignment = global(
+                test_case.seq1.as_bytes(),
+                test_case.seq2.as_bytes(),
+                &test_case.scoring,
+            )
+            .unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    alignment.score,
+                    alignment.aligned1.as_str(),
+                    alignment.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_align() {
+        struct TestCase<'a> {
+            name: &'a str,
+            seq1: &'a str,
+            seq2: &'a str,
+            scoring: Scoring,
+            mode: Mode,
+            expected: (i32, &'a str, &'a str),
+            ranges: (Range<usize>, Range<usize>),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should align substrings locally",
+                seq1: "PLEASANTLY",
+                seq2: "MEANLY",
+                scoring: Scoring::new(
+                    Substitution::Matrix(&Matrix::BLOSUM62),
+                    Gap::Affine {
+                        open: 11,
+                        extend: 1,
+                    },
+                ),
+                mode: Mode::Local,
+                expected: (12, "LEAS", "MEAN"),
+                ranges: (1..5, 0..4),
+            },
+            TestCase {
+                name: "Should align nothing locally without a positive score",
+                seq1: "AAA",
+                seq2: "CCC",
+                scoring: Scoring::new(Substitution::DNA, Gap::Linear(1)),
+                mode: Mode::Local,
+                expected: (0, "", ""),
+                ranges: (0..0, 0..0),
+            },
+            TestCase {
+                name: "Should stop a local alignment where it restarts",
+                seq1: "TTTTACGT",
+                seq2: "GGGGACGT",
+                scoring: Scoring::new(Substitution::DNA, Gap::Linear(1)),
+                mode: Mode::Local,
+                expected: (4, "ACGT", "ACGT"),
+                ranges: (4..8, 4..8),
+            },
+            TestCase {
+                name: "Should fit the second sequence into the first",
+                seq1: "GCAAACCATAAGCCCTACGTGCCGCCTGTTTAAAC",
+                seq2: "GCCGTCAGGCTGGTGTCCG",
+                scoring: Scoring::new(Substitution::DNA, Gap::Linear(1)),
+                mode: Mode::Fitting,
+                expected: (5, "GCCCT-A--C-G-TG-CCG", "GCCGTCAGGCTGGTGTCCG"),
+                ranges: (11..24, 0..19),
+            },
+            TestCase {
+                name: "Should overlap a suffix of the first sequence with a prefix of the second",
+                seq1: "CTAAGGGATTCCGGTAATTAGACAG",
+                seq2: "ATAGACCATATGTCAGTGACTGTGTAA",
+                scoring: Scoring::new(
+                    Substitution::MatchMismatch {
+                        matching: 1,
+                        mismatch: -2,
+                    },
+                    Gap::Linear(2),
+                ),
+                mode: Mode::Overlap,
+                expected: (1, "ACAG", "ATAG"),
+                ranges: (21..25, 0..4),
+            },
+            TestCase {
+                name: "Should align semi-globally with free end gaps",
+                seq1: "CAGCACTTGGATTCTCGG",
+                seq2: "CAGCGTGG",
+                scoring: Scoring::new(Substitution::DNA, Gap::Linear(1)),
+                mode: Mode::SemiGlobal,
+                expected: (4, "CAGCACTTGG", "CAGCG-T-GG"),
+                ranges: (0..10, 0..8),
+            },
+        ];
+        for test_case in test_cases {
+            let alignment = align(
+                test_case.seq1.as_bytes(),
+                test_case.seq2.as_bytes(),
+                &test_case.scoring,
+                test_case.mode,
+            )
+            .unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    alignment.score,
+                    alignment.aligned1.as_str(),
+                    alignment.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+            assert_eq!(
+                test_case.ranges,
+                (alignment.range1, alignment.range2),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_align_rescore() {
+        let scorings = [
+            Scoring::new(Substitution::DNA, Gap::Linear(1)),
+            Scoring::new(
+                Substitution::MatchMismatch {
+                    matching: 1,
+                    mismatch: -2,
+                },
+                Gap::Affine { open: 3, extend: 1 },
+            ),
+        ];
+        let modes = [
+            Mode::Global,
+            Mode::Local,
+            Mode::Fitting,
+            Mode::Overlap,
+            Mode::SemiGlobal,
+        ];
+        let mut state: u32 = 11;
+        let mut random = |n: u32| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) % n
+        };
+        for _ in 0..300 {
+            let mut sequence = || -> Vec<u8> {
+                let length = random(12);
+                (0..length).map(|_| b"ACGT"[random(4) as usize]).collect()
+            };
+            let (seq1, seq2) = (sequence(), sequence());
+            for scoring in &scorings {
+                for mode in modes {
+                    let alignment = align(&seq1, &seq2, scoring, mode).unwrap();
+                    assert_eq!(
+                        (
+                            alignment.score,
+                            seq1[alignment.range1.clone()].to_vec(),
+                            seq2[alignment.range2.clone()].to_vec(),
+                        ),
+                        (
+                            score(
+                                alignment.aligned1.as_bytes(),
+                                alignment.aligned2.as_bytes(),
+                                scoring,
+                                false
+                            )
+                            .unwrap(),
+                            ungapped(&alignment.aligned1).into_bytes(),
+                            ungapped(&alignment.aligned2).into_bytes(),
+                        ),
+                        "Should return an alignment of the ranges achieving the score for {:?} and \
+                         {:?} in {:?} mode",
+                        std::str::from_utf8(&seq1),
+                        std::str::from_utf8(&seq2),
+                        mode
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        struct TestCase<'a> {
+            name: &'a str,
+            rows: (&'a str, &'a str),
+            free_ends: bool,
+            expected: Result<i32>,
+        }
+        let scoring = Scoring::new(Substitution::DNA, Gap::Affine { open: 3, extend: 1 });
+        let test_cases = [
+            TestCase {
+                name: "Should open every run of gaps once",
+                rows: ("AC--GT-A", "ACTTG-CA"),
+                free_ends: false,
+                expected: Ok(-6),
+            },
+            TestCase {
+                name: "Should not charge gaps at the ends of rows when they are free",
+                rows: ("--CAGCACTT", "TTCAG-A---"),
+                free_ends: true,
+                expected: Ok(1),
+            },
+            TestCase {
+                name: "Should reject rows of different lengths",
+                rows: ("ACGT", "ACG"),
+                free_ends: false,
+                expected: Err(Error::new(
+                    ErrorKind::Validation,
+                    "expected rows of equal length, found lengths 4 and 3",
+                )),
+            },
+            TestCase {
+                name: "Should reject a gap aligned to a gap",
+                rows: ("A-GT", "A-GT"),
+                free_ends: false,
+                expected: Err(Error::new(
+                    ErrorKind::Validation,
+                    "gap aligned to a gap at position 2",
+                )),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                score(
+                    test_case.rows.0.as_bytes(),
+                    test_case.rows.1.as_bytes(),
+                    &scoring,
+                    test_case.free_ends
+                ),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_pad() {
+        let seq1 = b"CAGCACTTGGATTCTCGG";
+        let seq2 = b"TTCAGCGTGG";
+        let scoring = Scoring::new(Substitution::DNA, Gap::Linear(1));
+        let alignment = align(seq1, seq2, &scoring, Mode::SemiGlobal).unwrap();
+        assert_eq!(
+            (
+                "--CAGCACTTGGATTCTCGG".to_owned(),
+                "TTCAGCG-T-GG--------".to_owned()
+            ),
+            alignment.pad(seq1, seq2),
+            "{}",
+            "Should pad the unaligned ends with gaps"
+        );
+    }
+
+    #[test]
+    fn test_global_invalid_symbol() {
+        let scoring = Scoring::new(Substitution::Matrix(&Matrix::BLOSUM62), Gap::Linear(5));
+        assert_eq!(
+            Err(Error::new(
+                ErrorKind::Alphabet,
+                "not a BLOSUM62 symbol: B at position 2"
+            )),
+            global(b"ABC", b"AC", &scoring),
+            "{}",
+            "Should reject symbols missing from the matrix"
+        );
+    }
+}
@@ -1,59 +1,264 @@
+//! DNA helpers
+//!
+//! Bases may be any IUPAC nucleotide code.  Lowercase bases are treated as soft-masked: they
+//! match and complement like their uppercase counterparts, and these helpers keep their case.
+//! Sequences parsed into an `IupacSeq` are stored in uppercase, so soft-masking has to be read
+//! from the raw bytes, as `report` does.
+//!
 use crate::errors::{Error, ErrorKind, Result};
+use std::collections::BTreeMap;
+
+/// Bases that are not ambiguous
+pub const BASES: &[u8] = b"ACGT";
+/// IUPAC nucleotide codes, starting with the unambiguous bases
+pub const IUPAC_CODES: &[u8] = b"ACGTRYSWKMBDHVN";
+
+/// Return the set of bases an IUPAC code stands for, as a bit mask of A, C, G and T
+fn bases(code: &u8) -> Option<u8> {
+    const A: u8 = 1;
+    const C: u8 = 2;
+    const G: u8 = 4;
+    const T: u8 = 8;
+    match code.to_ascii_uppercase() {
+        b'A' => Some(A),
+        b'C' => Some(C),
+        b'G' => Some(G),
+        b'T' => Some(T),
+        b'R' => Some(A | G),
+        b'Y' => Some(C | T),
+        b'S' => Some(C | G),
+        b'W' => Some(A | T),
+        b'K' => Some(G | T),
+        b'M' => Some(A | C),
+        b'B' => Some(C | G | T),
+        b'D' => Some(A | G | T),
+        b'H' => Some(A | C | T),
+        b'V' => Some(A | C | G),
+        b'N' => Some(A | C | G | T),
+        _ => None,
+    }
+}
 
 /// Complement a DNA base
+///
+/// Ambiguity codes are complemented to the code of the complementary bases, so 'R' (A or G)
+/// becomes 'Y' (T or C).  The case of the base is kept.
+///
 pub fn complement(base: &u8) -> Result<u8> {
-    match base {
-        b'A' => Ok(b'T'),
-        b'T' => Ok(b'A'),
-        b'C' => Ok(b'G'),
-        b'G' => Ok(b'C'),
-        _ => Err(Error::new(ErrorKind::IO, "not a dna base")),
+    let complement = match base.to_ascii_uppercase() {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'R' => b'Y',
+        b'Y' => b'R',
+        b'K' => b'M',
+        b'M' => b'K',
+        b'B' => b'V',
+        b'V' => b'B',
+        b'D' => b'H',
+        b'H' => b'D',
+        code @ (b'S' | b'W' | b'N') => code,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::Alphabet,
+                &format!("not a dna base: {}", *base as char),
+            ))
+        }
+    };
+    if base.is_ascii_lowercase() {
+        return Ok(complement.to_ascii_lowercase());
     }
+    Ok(complement)
 }
 
-/// Reverse complement a dna string
-pub fn reverse_complement(dna_string: &[u8]) -> Result<Vec<u8>> {
-    dna_string.iter().rev().map(complement).collect()
+/// Check to see if the base is an IUPAC ambiguity code rather than A, C, G or T
+pub fn is_ambiguous(base: &u8) -> bool {
+    bases(base).is_some() && !BASES.contains(&base.to_ascii_uppercase())
 }
 
-/// Translate DNA to protein
-pub fn dna_to_protein(substring: &[u8]) -> Result<Option<u8>> {
-    match substring {
-        b"GCT" | b"GCC" | b"GCA" | b"GCG" => Ok(Some(b'A')),
-        b"TGT" | b"TGC" => Ok(Some(b'C')),
-        b"GAT" | b"GAC" => Ok(Some(b'D')),
-        b"GAA" | b"GAG" => Ok(Some(b'E')),
-        b"TTT" | b"TTC" => Ok(Some(b'F')),
-        b"GGT" | b"GGC" | b"GGA" | b"GGG" => Ok(Some(b'G')),
-        b"CAT" | b"CAC" => Ok(Some(b'H')),
-        b"ATT" | b"ATC" | b"ATA" => Ok(Some(b'I')),
-        b"AAA" | b"AAG" => Ok(Some(b'K')),
-        b"TTA" | b"TTG" | b"CTT" | b"CTC" | b"CTA" | b"CTG" => Ok(Some(b'L')),
-        b"ATG" => Ok(Some(b'M')),
-        b"AAT" | b"AAC" => Ok(Some(b'N')),
-        b"CCT" | b"CCC" | b"CCA" | b"CCG" => Ok(Some(b'P')),
-        b"CAA" | b"CAG" => Ok(Some(b'Q')),
-        b"CGT" | b"CGC" | b"CGA" | b"CGG" | b"AGA" | b"AGG" => Ok(Some(b'R')),
-        b"TCT" | b"TCC" | b"TCA" | b"TCG" | b"AGT" | b"AGC" => Ok(Some(b'S')),
-        b"ACT" | b"ACC" | b"ACA" | b"ACG" => Ok(Some(b'T')),
-        b"GTT" | b"GTC" | b"GTA" | b"GTG" => Ok(Some(b'V')),
-        b"TGG" => Ok(Some(b'W')),
-        b"TAT" | b"TAC" => Ok(Some(b'Y')),
-        b"TAA" | b"TAG" | b"TGA" => Ok(None),
-        _ => Err(Error::new(
-            ErrorKind::IO,
-            &format!(
-                "unrecognized substring: {}",
-                String::from_utf8(substring.to_vec())?
-            ),
-        )),
+/// Check to see if the base is soft-masked
+pub fn is_masked(base: &u8) -> bool {
+    base.is_ascii_lowercase()
+}
+
+/// Check to see if two bases could be the same base
+///
+/// Ambiguity codes match any base they stand for, so 'N' matches everything and 'R' matches 'A',
+/// 'G' and any code that includes either.  Invalid bases match nothing.
+///
+pub fn matches(base1: &u8, base2: &u8) -> bool {
+    match (bases(base1), bases(base2)) {
+        (Some(bases1), Some(bases2)) => bases1 & bases2 != 0,
+        _ => false,
     }
 }
 
+/// Report of the ambiguous and masked bases of a sequence
+#[derive(Debug, Default, PartialEq, serde::Serialize)]
+pub struct Report {
+    /// Number of bases in the sequence
+    pub length: usize,
+    /// Number of IUPAC ambiguity codes
+    pub ambiguous: usize,
+    /// Number of soft-masked (lowercase) bases
+    pub masked: usize,
+    /// Count of each ambiguity code, in uppercase
+    pub codes: BTreeMap<char, usize>,
+}
+
+/// Report the ambiguous and masked bases of a sequence
+///
+/// An error naming the 1-based position of an invalid base is returned.
+///
+pub fn report(sequence: &[u8]) -> Result<Report> {
+    let mut report = Report {
+        length: sequence.len(),
+        ..Report::default()
+    };
+    for (i, base) in sequence.iter().enumerate() {
+        if bases(base).is_none() {
+            return Err(Error::new(
+                ErrorKind::Alphabet,
+                &format!("not a dna base: {} at position {}", *base as char, i + 1),
+            ));
+        }
+        if is_masked(base) {
+            report.masked += 1;
+        }
+        if is_ambiguous(base) {
+            report.ambiguous += 1;
+            *report
+                .codes
+                .entry(base.to_ascii_uppercase() as char)
+                .or_insert(0) += 1;
+        }
+    }
+    Ok(report)
+}
+
+/// Reverse complement a dna string
+///
+/// An error naming the 1-based position of an invalid base is returned.
+///
+pub fn reverse_complement(dna_string: &[u8]) -> Result<Vec<u8>> {
+    dna_string
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, base)| {
+            complement(base).map_err(|e| {
+                Error::new(
+                    ErrorKind::Alphabet,
+                    &format!("{} at position {}", e.message(), i + 1),
+                )
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_complement() {
+        struct TestCase<'a> {
+            name: &'a str,
+            base: u8,
+            expected: Result<u8>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should complement a base",
+                base: b'A',
+                expected: Ok(b'T'),
+            },
+            TestCase {
+                name: "Should complement an ambiguity code",
+                base: b'R',
+                expected: Ok(b'Y'),
+            },
+            TestCase {
+                name: "Should keep the case of a masked base",
+                base: b'b',
+                expected: Ok(b'v'),
+            },
+            TestCase {
+                name: "Should return an error on an invalid base",
+                base: b'X',
+                expected: Err(Error::new(ErrorKind::Alphabet, "not a dna base: X")),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                complement(&test_case.base),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        struct TestCase<'a> {
+            name: &'a str,
+            base1: u8,
+            base2: u8,
+            expected: bool,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should match equal bases regardless of case",
+                base1: b'A',
+                base2: b'a',
+                expected: true,
+            },
+            TestCase {
+                name: "Should match a base against a code that includes it",
+                base1: b'G',
+                base2: b'R',
+                expected: true,
+            },
+            TestCase {
+                name: "Should not match a base against a code that excludes it",
+                base1: b'C',
+                base2: b'R',
+                expected: false,
+            },
+            TestCase {
+                name: "Should not match an invalid base",
+                base1: b'X',
+                base2: b'N',
+                expected: false,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                matches(&test_case.base1, &test_case.base2),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            Ok(Report {
+                length: 8,
+                ambiguous: 3,
+                masked: 3,
+                codes: BTreeMap::from([('N', 2), ('R', 1)]),
+            }),
+            report(b"ACGTnnRa"),
+            "{}",
+            "Should count ambiguous and masked bases",
+        );
+    }
+
     #[test]
     fn test_reverse_complement() {
         struct TestCase<'a> {
@@ -0,0 +1,523 @@
+//! Indexed random access to fasta files
+//!
+//! The index follows the samtools `.fai` format, with one tab separated line per record:
+//!
+//! ```text
+//! NAME    LENGTH    OFFSET    LINEBASES    LINEWIDTH
+//! ```
+//!
+//! where OFFSET is the byte offset of the first base of the record, LINEBASES is the number of
+//! bases per line and LINEWIDTH is the number of bytes per line, including the line terminator.
+//!
+use crate::common::{fasta, gzip};
+use crate::errors::{Error, ErrorKind, Location, Result};
+use std::io::{BufRead, Read, Write};
+
+/// File extension of fasta index files
+pub const EXTENSION: &str = ".fai";
+const FIELDS: usize = 5;
+
+/// Index entry for a single fasta record
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub name: String,
+    pub length: u64,
+    pub offset: u64,
+    pub line_bases: u64,
+    pub line_width: u64,
+}
+
+impl Entry {
+    /// Byte offset of a 0-based position within the record
+    fn position(&self, position: u64) -> u64 {
+        if self.line_bases == 0 {
+            return self.offset;
+        }
+        self.offset + (position / self.line_bases) * self.line_width + position % self.line_bases
+    }
+}
+
+/// Index of a fasta file
+#[derive(Debug, Default, PartialEq)]
+pub struct Index {
+    entries: Vec<Entry>,
+}
+
+impl Index {
+    /// Build the index by scanning a fasta file
+    ///
+    /// Every line of a record except the last must have the same length, otherwise the record
+    /// cannot be indexed.  Compressed input is rejected since offsets would not point into the
+    /// decompressed sequence.
+    ///
+    pub fn build<R>(reader: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = std::io::BufReader::new(reader);
+        if gzip::is_gzip(reader.fill_buf()?) {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                "cannot index a compressed fasta file",
+            ));
+        }
+        let mut builder = Builder::default();
+        let mut line = String::new();
+        let mut offset = 0;
+        let mut line_number = 0;
+        loop {
+            line.clear();
+            let bytes = reader.read_line(&mut line)? as u64;
+            if bytes == 0 {
+                break;
+            }
+            offset += bytes;
+            line_number += 1;
+            builder
+                .push(&line, offset)
+                .map_err(|e| e.with_location(Location::new(line_number, 1, line.trim_end())))?;
+        }
+        builder.finish();
+        Ok(Self {
+            entries: builder.entries,
+        })
+    }
+
+    /// Read an existing `.fai` index
+    pub fn read<R>(reader: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut entries = Vec::new();
+        for (i, line) in std::io::BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let location = || Location::new(i + 1, 1, &line);
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < FIELDS {
+                return Err(Error::new(
+                    ErrorKind::Parse,
+                    &format!("invalid fai format: expected {} fields", FIELDS),
+                )
+                .with_location(location()));
+            }
+            let parse = |field: &str| {
+                field
+                    .parse::<u64>()
+                    .map_err(|e| Error::from(e).with_location(location()))
+            };
+            entries.push(Entry {
+                name: fields[0].into(),
+                length: parse(fields[1])?,
+                offset: parse(fields[2])?,
+                line_bases: parse(fields[3])?,
+                line_width: parse(fields[4])?,
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Write the index in the `.fai` format
+    pub fn write<W>(&self, writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut writer = std::io::BufWriter::new(writer);
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                entry.name, entry.length, entry.offset, entry.line_bases, entry.line_width
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the entries of the index
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Find the entry of a record by its name
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+}
+
+/// State of the index while scanning a fasta file
+#[derive(Default)]
+struct Builder {
+    entries: Vec<Entry>,
+    current: Option<Entry>,
+    /// Set once a line shorter than the line width is seen, which must be the last one
+    is_short: bool,
+}
+
+impl Builder {
+    /// Add a line ending at the byte offset
+    fn push(&mut self, line: &str, offset: u64) -> Result<()> {
+        if let Some(header) = line.strip_prefix('>') {
+            self.finish();
+            let name = header.split_whitespace().next().unwrap_or("");
+            self.current = Some(Entry {
+                name: name.into(),
+                length: 0,
+                offset,
+                line_bases: 0,
+                line_width: 0,
+            });
+            self.is_short = false;
+            return Ok(());
+        }
+        let entry = self.current.as_mut().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Parse,
+                "invalid fasta format: expected a line starting with '>'",
+            )
+        })?;
+        let bases = line.trim_end().len() as u64;
+        let width = line.len() as u64;
+        if bases == 0 {
+            self.is_short = true;
+            return Ok(());
+        }
+        if self.is_short {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                &format!("inconsistent line lengths in {}", entry.name),
+            ));
+        }
+        if entry.line_bases == 0 {
+            entry.line_bases = bases;
+            entry.line_width = width;
+        } else if bases > entry.line_bases || width - bases != entry.line_width - entry.line_bases {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                &format!("inconsistent line lengths in {}", entry.name),
+            ));
+        }
+        self.is_short = bases < entry.line_bases;
+        entry.length += bases;
+        Ok(())
+    }
+
+    /// Complete the current record
+    fn finish(&mut self) {
+        if let Some(entry) = self.current.take() {
+            self.entries.push(entry);
+        }
+    }
+}
+
+/// Region of a fasta record
+///
+/// Regions are written the samtools way, as `name`, `name:start` or `name:start-end`, with
+/// 1-based inclusive coordinates.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub name: String,
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+}
+
+impl std::str::FromStr for Region {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::new(ErrorKind::User, &format!("invalid region: {}", s));
+        let (name, range) = match s.rsplit_once(':') {
+            Some((name, range)) => (name, Some(range)),
+            None => (s, None),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let (start, end) = match range.map(|range| range.split_once('-')) {
+            None => (None, None),
+            Some(None) => (range, None),
+            Some(Some((start, end))) => (Some(start), Some(end)),
+        };
+        let parse = |position: Option<&str>| -> Result<Option<u64>> {
+            match position {
+                None => Ok(None),
+                Some(position) => match position.replace(',', "").parse::<u64>() {
+                    Ok(0) | Err(_) => Err(invalid()),
+                    Ok(position) => Ok(Some(position)),
+                },
+            }
+        };
+        let region = Self {
+            name: name.into(),
+            start: parse(start)?,
+            end: parse(end)?,
+        };
+        match (region.start, region.end) {
+            (Some(start), Some(end)) if start > end => Err(invalid()),
+            _ => Ok(region),
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)?;
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => write!(f, ":{}-{}", start, end),
+            (Some(start), None) => write!(f, ":{}", start),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Reader that seeks directly to records of an indexed fasta file
+pub struct IndexedReader<R>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    reader: R,
+    index: Index,
+}
+
+impl IndexedReader<std::fs::File> {
+    /// Open an indexed fasta file
+    ///
+    /// The index is read from `<path>.fai`, or built in memory if there is no index file.
+    ///
+    pub fn from_path(path: &str) -> Result<Self> {
+        let index = match std::fs::File::open(index_path(path)) {
+            Ok(file) => Index::read(file)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Index::build(std::fs::File::open(path)?)?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self::new(std::fs::File::open(path)?, index))
+    }
+}
+
+impl<R> IndexedReader<R>
+where
+    R: std::io::Read + std::io::Seek,
+{
+    /// Constructor for IndexedReader
+    pub fn new(reader: R, index: Index) -> Self {
+        Self { reader, index }
+    }
+
+    /// Returns the index
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Fetch the sequence of a region
+    ///
+    /// A region that extends past the end of the record is truncated to the end of the record.
+    ///
+    pub fn fetch(&mut self, region: &Region) -> Result<String> {
+        let entry = self.index.get(&region.name).cloned().ok_or_else(|| {
+            Error::new(
+                ErrorKind::Validation,
+                &format!("no record named {} in the index", region.name),
+            )
+        })?;
+        let start = region.start.map_or(0, |start| start - 1).min(entry.length);
+        let end = region.end.unwrap_or(entry.length).min(entry.length);
+        if start >= end {
+            return Ok(String::new());
+        }
+        let from = entry.position(start);
+        let to = entry.position(end - 1) + 1;
+        self.reader.seek(std::io::SeekFrom::Start(from))?;
+        let mut bytes = Vec::with_capacity((to - from) as usize);
+        (&mut self.reader).take(to - from).read_to_end(&mut bytes)?;
+        bytes.retain(|b| !b.is_ascii_whitespace());
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    /// Fetch a whole record by its name
+    pub fn record(&mut self, name: &str) -> Result<fasta::Record> {
+        let region = Region {
+            name: name.into(),
+            start: None,
+            end: None,
+        };
+        Ok(fasta::Record::with_sequence(name, &self.fetch(&region)?))
+    }
+
+    /// Write the regions as fasta records named after the region
+    pub fn write_regions<W>(&mut self, regions: &[Region], writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut writer = fasta::Writer::new(writer);
+        for region in regions {
+            let sequence = self.fetch(region)?;
+            writer.write(&fasta::Record::with_sequence(
+                &region.to_string(),
+                &sequence,
+            ))?;
+        }
+        writer.flush()
+    }
+}
+
+/// Build the index of a fasta file and save it next to the file
+pub fn write_index(path: &str) -> Result<()> {
+    let index = std::fs::File::open(path)
+        .map_err(Error::from)
+        .and_then(Index::build)
+        .map_err(|e| e.in_file(path))?;
+    let index_path = index_path(path);
+    std::fs::File::create(&index_path)
+        .map_err(Error::from)
+        .and_then(|file| index.write(file))
+        .map_err(|e| e.in_file(&index_path))
+}
+
+/// Return the path of the index of a fasta file
+pub fn index_path(path: &str) -> String {
+    format!("{}{}", path, EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FASTA: &[u8] = b">chr1 first\nACGT\nACGT\nAC\n>chr2\nGGGCC\nCAT\n";
+
+    #[test]
+    fn test_index_build() {
+        let expected = "chr1\t10\t12\t4\t5\nchr2\t8\t31\t5\t6\n";
+        let mut output = Vec::new();
+        Index::build(FASTA).unwrap().write(&mut output).unwrap();
+        assert_eq!(
+            expected,
+            String::from_utf8(output).unwrap(),
+            "{}",
+            "Should build a samtools compatible index",
+        );
+        assert_eq!(
+            Ok(Index::build(FASTA).unwrap()),
+            Index::read(expected.as_bytes()),
+            "{}",
+            "Should read back the index",
+        );
+    }
+
+    #[test]
+    fn test_index_build_invalid() {
+        let actual = Index::build(&b">chr1\nACG\nACGT\n"[..]);
+        assert_eq!(
+            Some(&ErrorKind::Validation),
+            actual.as_ref().err().map(|e| e.kind()),
+            "{}",
+            "Should return an error on inconsistent line lengths",
+        );
+    }
+
+    #[test]
+    fn test_indexed_reader_fetch() {
+        struct TestCase<'a> {
+            name: &'a str,
+            region: &'a str,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should fetch a whole record",
+                region: "chr1",
+                expected: "ACGTACGTAC",
+            },
+            TestCase {
+                name: "Should fetch a range across lines",
+                region: "chr1:3-6",
+                expected: "GTAC",
+            },
+            TestCase {
+                name: "Should fetch from a start position to the end",
+                region: "chr2:5",
+                expected: "CCAT",
+            },
+            TestCase {
+                name: "Should truncate a range past the end of the record",
+                region: "chr2:7-100",
+                expected: "AT",
+            },
+        ];
+        let index = Index::build(FASTA).unwrap();
+        let mut reader = IndexedReader::new(std::io::Cursor::new(FASTA), index);
+        for test_case in test_cases {
+            let region: Region = test_case.region.parse().unwrap();
+            assert_eq!(
+                Ok(test_case.expected.to_owned()),
+                reader.fetch(&region),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_indexed_reader_write_regions() {
+        let index = Index::build(FASTA).unwrap();
+        let mut reader = IndexedReader::new(std::io::Cursor::new(FASTA), index);
+        let regions: Vec<Region> = ["chr2:2-4", "chr1"]
+            .iter()
+            .map(|region| region.parse().unwrap())
+            .collect();
+        let mut output = Vec::new();
+        reader.write_regions(&regions, &mut output).unwrap();
+        assert_eq!(
+            ">chr2:2-4\nGGC\n>chr1\nACGTACGTAC\n",
+            String::from_utf8(output).unwrap(),
+            "{}",
+            "Should write the regions as fasta records"
+        );
+    }
+
+    #[test]
+    fn test_region_from_str() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            expected: Option<Region>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should parse a name",
+                s: "chr2",
+                expected: Some(Region {
+                    name: "chr2".into(),
+                    start: None,
+                    end: None,
+                }),
+            },
+            TestCase {
+                name: "Should parse a range",
+                s: "chr2:1,000-2,000",
+                expected: Some(Region {
+                    name: "chr2".into(),
+                    start: Some(1000),
+                    end: Some(2000),
+                }),
+            },
+            TestCase {
+                name: "Should return an error on a backwards range",
+                s: "chr2:20-10",
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                test_case.s.parse::<Region>().ok(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -1,16 +1,26 @@
-use crate::errors::{Error, ErrorKind, Result};
-use std::io::BufRead;
+use crate::common::{gzip, header};
+use crate::errors::{Error, ErrorKind, Location, Result};
+use std::io::{BufRead, Write};
 
 const NAME_PREFIX: &str = ">";
+/// Default number of sequence characters per line when writing
+pub const LINE_WIDTH: usize = 60;
 
 /// Reader for fasta files
+///
+/// Lines are read as bytes into a reused buffer.  `read_into` and `next_record` fill a
+/// `ByteRecord` without allocating per line, which is what large genomes should use, while `read`
+/// and `iter` are a convenience on top of them that return `String` backed records.
+///
 pub struct Reader<R>
 where
     R: std::io::Read,
 {
     reader: std::io::BufReader<R>,
-    line: String,
+    line: Vec<u8>,
+    line_number: usize,
     is_eof: bool,
+    record: ByteRecord,
 }
 
 impl<R> Reader<R>
@@ -21,31 +31,72 @@ where
     pub fn new(reader: R) -> Self {
         Self {
             reader: std::io::BufReader::new(reader),
-            line: String::new(),
+            line: Vec::new(),
+            line_number: 0,
             is_eof: false,
+            record: ByteRecord::new(),
         }
     }
 
     /// Read data into a record
     pub fn read(&mut self, record: &mut Record) -> Result<()> {
+        let mut bytes = std::mem::take(&mut self.record);
+        let result = self.read_into(&mut bytes);
+        self.record = bytes;
+        result?;
+        *record = self.record.to_record()?;
+        Ok(())
+    }
+
+    /// Read data into a byte record, reusing its buffers
+    pub fn read_into(&mut self, record: &mut ByteRecord) -> Result<()> {
         if self.line.is_empty() {
             self.read_line()?;
         }
         if !is_name(&self.line) {
-            return Err(Error::new(ErrorKind::IO, "invalid fasta format"));
+            return Err(Error::new(
+                ErrorKind::Parse,
+                &format!(
+                    "invalid fasta format: expected a line starting with '{}'",
+                    NAME_PREFIX
+                ),
+            )
+            .with_location(Location::new(
+                self.line_number,
+                1,
+                &String::from_utf8_lossy(self.line.trim_ascii_end()),
+            )));
         }
-        record.name = get_name(&self.line);
-        record.sequence = String::new();
+        record.clear();
+        let (name, description) = get_header(&self.line);
+        record.name.extend_from_slice(name);
+        record.description.extend_from_slice(description);
         loop {
             self.read_line()?;
             if is_name(&self.line) || self.is_eof {
                 break;
             }
-            record.sequence.push_str(&get_sequence(&self.line));
+            record.sequence.extend_from_slice(get_sequence(&self.line));
         }
         Ok(())
     }
 
+    /// Read the next record into a buffer owned by the reader and borrow it
+    ///
+    /// None is returned once the input is exhausted.  The buffers are reused from record to
+    /// record, so reading a whole file only allocates as much as its longest record.
+    ///
+    pub fn next_record(&mut self) -> Option<Result<&ByteRecord>> {
+        let mut record = std::mem::take(&mut self.record);
+        let result = self.read_into(&mut record);
+        self.record = record;
+        match result {
+            Ok(()) => Some(Ok(&self.record)),
+            _ if self.is_eof => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+
     /// Returns an iterator
     pub fn iter(self) -> Records<R> {
         Records { reader: self }
@@ -54,33 +105,77 @@ where
     /// Read line
     fn read_line(&mut self) -> Result<()> {
         self.line.clear();
-        let bytes = self.reader.read_line(&mut self.line)?;
+        let bytes = self.reader.read_until(b'\n', &mut self.line)?;
         if bytes == 0 {
             self.is_eof = true;
+        } else {
+            self.line_number += 1;
         }
         Ok(())
     }
 }
 
 /// Check to see if line is a name line
-fn is_name(line: &str) -> bool {
-    line.starts_with(NAME_PREFIX)
+fn is_name(line: &[u8]) -> bool {
+    line.starts_with(NAME_PREFIX.as_bytes())
 }
 
-/// Get name retrieves the name from a name line
-fn get_name(line: &str) -> String {
-    line.strip_prefix(NAME_PREFIX).unwrap_or("").trim().into()
+/// Get header splits a name line into the name and the description
+fn get_header(line: &[u8]) -> (&[u8], &[u8]) {
+    let header = line
+        .strip_prefix(NAME_PREFIX.as_bytes())
+        .unwrap_or(b"")
+        .trim_ascii();
+    match header.iter().position(u8::is_ascii_whitespace) {
+        Some(i) => (&header[..i], header[i..].trim_ascii()),
+        None => (header, b""),
+    }
 }
 
 /// Get sequence retrieves the sequence from the line
-fn get_sequence(line: &str) -> String {
-    line.trim().into()
+fn get_sequence(line: &[u8]) -> &[u8] {
+    line.trim_ascii()
+}
+
+/// ByteRecord is a fasta record backed by bytes
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ByteRecord {
+    pub name: Vec<u8>,
+    pub description: Vec<u8>,
+    pub sequence: Vec<u8>,
+}
+
+impl ByteRecord {
+    /// Construct a new, empty record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Empty the record while keeping its buffers
+    pub fn clear(&mut self) {
+        self.name.clear();
+        self.description.clear();
+        self.sequence.clear();
+    }
+
+    /// Convert the record into a `String` backed record
+    ///
+    /// An error is returned if any field is not valid UTF-8.
+    ///
+    pub fn to_record(&self) -> Result<Record> {
+        Ok(Record {
+            name: std::str::from_utf8(&self.name)?.into(),
+            description: std::str::from_utf8(&self.description)?.into(),
+            sequence: std::str::from_utf8(&self.sequence)?.into(),
+        })
+    }
 }
 
 /// Record is a fasta record
 #[derive(Debug, Default, PartialEq)]
 pub struct Record {
     pub name: String,
+    pub description: String,
     pub sequence: String,
 }
 
@@ -89,6 +184,30 @@ impl Record {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Construct a record from a name and a sequence
+    pub fn with_sequence(name: &str, sequence: &str) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            sequence: sequence.into(),
+        }
+    }
+
+    /// Parse the sequence into a validated sequence type such as `seq::DnaSeq`
+    pub fn parse_sequence<T>(&self) -> Result<T>
+    where
+        T: std::str::FromStr<Err = Error>,
+    {
+        self.sequence
+            .parse()
+            .map_err(|e: Error| Error::new(*e.kind(), &format!("{} in {}", e.message(), self.name)))
+    }
+
+    /// Parse the name and description of the record
+    pub fn header(&self) -> header::Header {
+        header::Header::parse(&self.name, &self.description)
+    }
 }
 
 /// Type for iterating records
@@ -115,6 +234,127 @@ where
     }
 }
 
+/// Writer for fasta files
+///
+/// Sequences are wrapped at `LINE_WIDTH` characters per line unless configured otherwise.  A line
+/// width of 0 writes every sequence on a single line.
+///
+pub struct Writer<W>
+where
+    W: std::io::Write,
+{
+    writer: std::io::BufWriter<W>,
+    line_width: usize,
+    descriptions: bool,
+}
+
+impl<W> Writer<W>
+where
+    W: std::io::Write,
+{
+    /// Constructor for fasta Writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: std::io::BufWriter::new(writer),
+            line_width: LINE_WIDTH,
+            descriptions: true,
+        }
+    }
+
+    /// Set the number of sequence characters per line
+    pub fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Set whether record descriptions are written after the name
+    pub fn descriptions(mut self, descriptions: bool) -> Self {
+        self.descriptions = descriptions;
+        self
+    }
+
+    /// Write a record
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        write!(self.writer, "{}{}", NAME_PREFIX, record.name)?;
+        if self.descriptions && !record.description.is_empty() {
+            write!(self.writer, " {}", record.description)?;
+        }
+        writeln!(self.writer)?;
+        if self.line_width == 0 {
+            writeln!(self.writer, "{}", record.sequence)?;
+            return Ok(());
+        }
+        for line in record.sequence.as_bytes().chunks(self.line_width) {
+            self.writer.write_all(line)?;
+            writeln!(self.writer)?;
+        }
+        Ok(())
+    }
+
+    /// Flush buffered records to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush buffered records and return the underlying writer
+    pub fn into_inner(self) -> Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|e| Error::from(e.into_error()))
+    }
+}
+
+impl<W> Writer<flate2::write::GzEncoder<W>>
+where
+    W: std::io::Write,
+{
+    /// Constructor for a fasta Writer that gzip compresses its output
+    pub fn gzip(writer: W) -> Self {
+        Self::new(gzip::encode(writer))
+    }
+
+    /// Flush buffered records, complete the gzip stream and return the underlying writer
+    pub fn finish(self) -> Result<W> {
+        Ok(self.into_inner()?.finish()?)
+    }
+}
+
+/// Render records as fasta text
+pub fn to_string(records: &[Record], line_width: usize) -> Result<String> {
+    let mut output = Vec::new();
+    {
+        let mut writer = Writer::new(&mut output).line_width(line_width);
+        for record in records {
+            writer.write(record)?;
+        }
+        writer.flush()?;
+    }
+    Ok(String::from_utf8(output)?)
+}
+
+/// Write records to a fasta file
+///
+/// The file is gzip compressed if the path ends in `.gz`.
+///
+pub fn write_file(path: &str, records: &[Record], line_width: usize) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    if gzip::is_gzip_path(path) {
+        let mut writer = Writer::gzip(file).line_width(line_width);
+        for record in records {
+            writer.write(record)?;
+        }
+        writer.finish()?;
+    } else {
+        let mut writer = Writer::new(file).line_width(line_width);
+        for record in records {
+            writer.write(record)?;
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,42 +403,158 @@ mod tests {
     }
 
     #[test]
-    fn test_is_name() {
+    fn test_reader_next_record() {
+        let data: &[u8] = b">name1 desc\nACGT\nAAAA\n\n>name2\nAAGGTT\n";
+        let mut reader = Reader::new(data);
+        let mut names = Vec::new();
+        let mut sequences = Vec::new();
+        while let Some(record) = reader.next_record() {
+            let record = record.unwrap();
+            names.push(record.name.clone());
+            sequences.push(record.sequence.clone());
+        }
         assert_eq!(
-            false,
-            is_name("abcdefg"),
+            vec![b"name1".to_vec(), b"name2".to_vec()],
+            names,
             "{}",
-            "Should return false if line is not a name line"
+            "Should read the names correctly",
+        );
+        assert_eq!(
+            vec![b"ACGTAAAA".to_vec(), b"AAGGTT".to_vec()],
+            sequences,
+            "{}",
+            "Should read the sequences correctly",
+        );
+    }
+
+    #[test]
+    fn test_reader_read_invalid() {
+        let mut record = Record::new();
+        let data: &[u8] = b"ACGT\n>name\n";
+        let mut reader = Reader::new(data);
+        assert_eq!(
+            Err(Error::new(
+                ErrorKind::Parse,
+                "invalid fasta format: expected a line starting with '>'"
+            )
+            .with_location(Location::new(1, 1, "ACGT"))),
+            reader.read(&mut record),
+            "{}",
+            "Should return the location of the invalid line",
         );
+    }
+
+    #[test]
+    fn test_writer_gzip() {
+        let mut writer = Writer::gzip(Vec::new());
+        writer
+            .write(&Record::with_sequence("name", "ACGT"))
+            .unwrap();
+        let compressed = writer.finish().unwrap();
+        let records: Result<Vec<Record>> =
+            Reader::new(gzip::decode(std::io::Cursor::new(compressed)).unwrap())
+                .iter()
+                .collect();
         assert_eq!(
-            true,
-            is_name(">abcdef"),
+            Ok(vec![Record::with_sequence("name", "ACGT")]),
+            records,
+            "{}",
+            "Should read back gzip compressed records",
+        );
+    }
+
+    #[test]
+    fn test_is_name() {
+        assert!(
+            !is_name(b"abcdefg"),
+            "{}",
+            "Should return false if line is not a name line"
+        );
+        assert!(
+            is_name(b">abcdef"),
             "{}",
             "Should return true if line is a name line"
         );
     }
 
     #[test]
-    fn test_get_name() {
+    fn test_get_header() {
         assert_eq!(
-            String::from("abc"),
-            get_name(">abc"),
+            (&b"abc"[..], &b""[..]),
+            get_header(b">abc"),
             "{}",
             "Should parse name from name line",
         );
         assert_eq!(
-            String::new(),
-            get_name("abc"),
+            (&b"abc"[..], &b"some description"[..]),
+            get_header(b">abc  some description\n"),
+            "{}",
+            "Should split the description from the name",
+        );
+        assert_eq!(
+            (&b""[..], &b""[..]),
+            get_header(b"abc"),
             "{}",
-            "Should return an empty string if not a name line",
+            "Should return empty strings if not a name line",
         );
     }
 
+    #[test]
+    fn test_writer_write() {
+        struct TestCase<'a> {
+            name: &'a str,
+            line_width: usize,
+            descriptions: bool,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should wrap the sequence",
+                line_width: 4,
+                descriptions: true,
+                expected: ">seq1 first sequence\nACGT\nACGT\nAC\n",
+            },
+            TestCase {
+                name: "Should not wrap the sequence with a line width of 0",
+                line_width: 0,
+                descriptions: true,
+                expected: ">seq1 first sequence\nACGTACGTAC\n",
+            },
+            TestCase {
+                name: "Should leave out the description",
+                line_width: 5,
+                descriptions: false,
+                expected: ">seq1\nACGTA\nCGTAC\n",
+            },
+        ];
+        let record = Record {
+            name: "seq1".into(),
+            description: "first sequence".into(),
+            sequence: "ACGTACGTAC".into(),
+        };
+        for test_case in test_cases {
+            let mut output = Vec::new();
+            {
+                let mut writer = Writer::new(&mut output)
+                    .line_width(test_case.line_width)
+                    .descriptions(test_case.descriptions);
+                writer.write(&record).unwrap();
+                writer.flush().unwrap();
+            }
+            assert_eq!(
+                test_case.expected,
+                String::from_utf8(output).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
     #[test]
     fn test_get_sequence() {
         assert_eq!(
-            String::from("ACGT"),
-            get_sequence("ACGT\n"),
+            &b"ACGT"[..],
+            get_sequence(b"ACGT\n"),
             "{}",
             "Should prase sequence from sequence line",
         );
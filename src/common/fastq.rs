@@ -0,0 +1,432 @@
+use crate::errors::{Error, ErrorKind, Location, Result};
+use std::io::{BufRead, Write};
+
+const NAME_PREFIX: &str = "@";
+const SEPARATOR_PREFIX: &str = "+";
+
+/// Reader for fastq files
+///
+/// Sequences and qualities may be wrapped over multiple lines.  The quality of a record is read
+/// until it is as long as the sequence, so quality lines starting with '@' are handled.
+///
+pub struct Reader<R>
+where
+    R: std::io::Read,
+{
+    reader: std::io::BufReader<R>,
+    line: String,
+    line_number: usize,
+    is_eof: bool,
+}
+
+impl<R> Reader<R>
+where
+    R: std::io::Read,
+{
+    /// Constructor for fastq Reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: std::io::BufReader::new(reader),
+            line: String::new(),
+            line_number: 0,
+            is_eof: false,
+        }
+    }
+
+    /// Read data into a record
+    pub fn read(&mut self, record: &mut Record) -> Result<()> {
+        if self.is_finished()? {
+            return Err(Error::new(
+                ErrorKind::Parse,
+                "unexpected end of fastq input",
+            ));
+        }
+        if !self.line.starts_with(NAME_PREFIX) {
+            return Err(self.parse_err(&format!(
+                "invalid fastq format: expected a line starting with '{}'",
+                NAME_PREFIX
+            )));
+        }
+        let (name, description) = get_header(&self.line);
+        record.name = name;
+        record.description = description;
+
+        record.sequence.clear();
+        loop {
+            self.read_line()?;
+            if self.is_eof {
+                return Err(self.parse_err("invalid fastq format: missing '+' separator line"));
+            }
+            if self.line.starts_with(SEPARATOR_PREFIX) {
+                break;
+            }
+            record.sequence.push_str(self.line.trim());
+        }
+
+        record.quality.clear();
+        while record.quality.len() < record.sequence.len() {
+            self.read_line()?;
+            if self.is_eof {
+                break;
+            }
+            record.quality.push_str(self.line.trim());
+        }
+        if record.quality.len() != record.sequence.len() {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                &format!(
+                    "quality length {} does not match sequence length {} for {}",
+                    record.quality.len(),
+                    record.sequence.len(),
+                    record.name
+                ),
+            )
+            .with_location(Location::new(self.line_number, 1, self.line.trim_end())));
+        }
+        self.line.clear();
+        Ok(())
+    }
+
+    /// Returns an iterator
+    pub fn iter(self) -> Records<R> {
+        Records { reader: self }
+    }
+
+    /// Skip blank lines and return true if there are no more records
+    fn is_finished(&mut self) -> Result<bool> {
+        while self.line.trim().is_empty() {
+            if self.is_eof {
+                return Ok(true);
+            }
+            self.read_line()?;
+        }
+        Ok(false)
+    }
+
+    /// Read line
+    fn read_line(&mut self) -> Result<()> {
+        self.line.clear();
+        let bytes = self.reader.read_line(&mut self.line)?;
+        if bytes == 0 {
+            self.is_eof = true;
+        } else {
+            self.line_number += 1;
+        }
+        Ok(())
+    }
+
+    /// Return a parse error located at the current line
+    fn parse_err(&self, message: &str) -> Error {
+        Error::new(ErrorKind::Parse, message).with_location(Location::new(
+            self.line_number,
+            1,
+            self.line.trim_end(),
+        ))
+    }
+}
+
+/// Split a header line into the name and the description
+fn get_header(line: &str) -> (String, String) {
+    let header = line.strip_prefix(NAME_PREFIX).unwrap_or("").trim();
+    match header.split_once(char::is_whitespace) {
+        Some((name, description)) => (name.into(), description.trim().into()),
+        None => (header.into(), String::new()),
+    }
+}
+
+/// Record is a fastq record
+#[derive(Debug, Default, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub description: String,
+    pub sequence: String,
+    pub quality: String,
+}
+
+impl Record {
+    /// Construct a new, empty record
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode the quality string into phred scores
+    pub fn phred(&self, encoding: Encoding) -> Result<Vec<u8>> {
+        encoding.decode(&self.quality)
+    }
+}
+
+/// Type for iterating records
+pub struct Records<R>
+where
+    R: std::io::Read,
+{
+    reader: Reader<R>,
+}
+
+impl<R> Iterator for Records<R>
+where
+    R: std::io::Read,
+{
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.is_finished() {
+            Ok(true) => return None,
+            Err(err) => return Some(Err(err)),
+            Ok(false) => (),
+        }
+        let mut record = Record::new();
+        match self.reader.read(&mut record) {
+            Ok(()) => Some(Ok(record)),
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Encoding of phred quality scores as ASCII characters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// Sanger and Illumina 1.8+, where a score of 0 is '!'
+    Phred33,
+    /// Illumina 1.3 to 1.7, where a score of 0 is '@'
+    Phred64,
+}
+
+impl Encoding {
+    /// ASCII value of a score of 0
+    pub fn offset(&self) -> u8 {
+        match self {
+            Self::Phred33 => 33,
+            Self::Phred64 => 64,
+        }
+    }
+
+    /// Decode a quality string into phred scores
+    ///
+    /// An error is returned if a character falls outside of the printable range of the encoding.
+    ///
+    pub fn decode(&self, quality: &str) -> Result<Vec<u8>> {
+        quality
+            .bytes()
+            .enumerate()
+            .map(|(i, b)| {
+                if b < self.offset() || b > b'~' {
+                    return Err(Error::new(
+                        ErrorKind::Alphabet,
+                        &format!(
+                            "invalid {:?} quality character {} at position {}",
+                            self,
+                            b as char,
+                            i + 1
+                        ),
+                    ));
+                }
+                Ok(b - self.offset())
+            })
+            .collect()
+    }
+
+    /// Encode phred scores into a quality string
+    ///
+    /// An error is returned if a score cannot be represented in the encoding.
+    ///
+    pub fn encode(&self, scores: &[u8]) -> Result<String> {
+        scores
+            .iter()
+            .map(|score| {
+                score
+                    .checked_add(self.offset())
+                    .filter(|b| *b <= b'~')
+                    .map(char::from)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::Overflow,
+                            &format!("phred score {} cannot be encoded as {:?}", score, self),
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Writer for fastq files
+pub struct Writer<W>
+where
+    W: std::io::Write,
+{
+    writer: std::io::BufWriter<W>,
+}
+
+impl<W> Writer<W>
+where
+    W: std::io::Write,
+{
+    /// Constructor for fastq Writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: std::io::BufWriter::new(writer),
+        }
+    }
+
+    /// Write a record
+    ///
+    /// An error is returned if the sequence and quality lengths differ.
+    ///
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        if record.sequence.len() != record.quality.len() {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                &format!(
+                    "quality length {} does not match sequence length {} for {}",
+                    record.quality.len(),
+                    record.sequence.len(),
+                    record.name
+                ),
+            ));
+        }
+        write!(self.writer, "{}{}", NAME_PREFIX, record.name)?;
+        if !record.description.is_empty() {
+            write!(self.writer, " {}", record.description)?;
+        }
+        writeln!(self.writer)?;
+        writeln!(self.writer, "{}", record.sequence)?;
+        writeln!(self.writer, "{}", SEPARATOR_PREFIX)?;
+        writeln!(self.writer, "{}", record.quality)?;
+        Ok(())
+    }
+
+    /// Flush buffered records to the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reader_iter() {
+        let data: &[u8] = b"@read1 first read\nACGT\nAC\n+\nII@\n#!!\n\n@read2\nGG\n+read2\n!!\n";
+        let records: Result<Vec<Record>> = Reader::new(data).iter().collect();
+        assert_eq!(
+            Ok(vec![
+                Record {
+                    name: "read1".into(),
+                    description: "first read".into(),
+                    sequence: "ACGTAC".into(),
+                    quality: "II@#!!".into(),
+                },
+                Record {
+                    name: "read2".into(),
+                    description: String::new(),
+                    sequence: "GG".into(),
+                    quality: "!!".into(),
+                },
+            ]),
+            records,
+            "{}",
+            "Should read multi-line records and quality lines starting with '@'",
+        );
+    }
+
+    #[test]
+    fn test_reader_read_invalid() {
+        struct TestCase<'a> {
+            name: &'a str,
+            data: &'a [u8],
+            expected: ErrorKind,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should return a parse error on a missing header",
+                data: b"ACGT\n+\nIIII\n",
+                expected: ErrorKind::Parse,
+            },
+            TestCase {
+                name: "Should return a parse error on a missing separator",
+                data: b"@read\nACGT\n",
+                expected: ErrorKind::Parse,
+            },
+            TestCase {
+                name: "Should return a validation error on mismatched lengths",
+                data: b"@read\nACGT\n+\nII\n",
+                expected: ErrorKind::Validation,
+            },
+        ];
+        for test_case in test_cases {
+            let mut record = Record::new();
+            let actual = Reader::new(test_case.data).read(&mut record);
+            assert_eq!(
+                Some(&test_case.expected),
+                actual.as_ref().err().map(|e| e.kind()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_encoding_decode() {
+        struct TestCase<'a> {
+            name: &'a str,
+            encoding: Encoding,
+            quality: &'a str,
+            expected: Result<Vec<u8>>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should decode phred+33",
+                encoding: Encoding::Phred33,
+                quality: "!5I",
+                expected: Ok(vec![0, 20, 40]),
+            },
+            TestCase {
+                name: "Should decode phred+64",
+                encoding: Encoding::Phred64,
+                quality: "@Th",
+                expected: Ok(vec![0, 20, 40]),
+            },
+            TestCase {
+                name: "Should return an error on characters below the offset",
+                encoding: Encoding::Phred64,
+                quality: "@5",
+                expected: Err(Error::new(
+                    ErrorKind::Alphabet,
+                    "invalid Phred64 quality character 5 at position 2",
+                )),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                test_case.encoding.decode(test_case.quality),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_writer_write() {
+        let mut output = Vec::new();
+        {
+            let mut writer = Writer::new(&mut output);
+            let record = Record {
+                name: "read1".into(),
+                description: "first read".into(),
+                sequence: "ACGT".into(),
+                quality: Encoding::Phred33.encode(&[40, 40, 20, 0]).unwrap(),
+            };
+            assert!(writer.write(&record).is_ok(), "{}", "Should write record");
+            assert!(writer.flush().is_ok(), "{}", "Should flush records");
+        }
+        assert_eq!(
+            "@read1 first read\nACGT\n+\nII5!\n",
+            String::from_utf8(output).unwrap(),
+            "{}",
+            "Should write a fastq record",
+        );
+    }
+}
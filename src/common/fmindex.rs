@@ -0,0 +1,941 @@
+//! Burrows-Wheeler transform and FM-index
+//!
+//! The FM-index answers substring queries over a reference by backward search on its
+//! Burrows-Wheeler transform.  Symbol counts are checkpointed every `INTERVAL` rows, and the text
+//! positions of rows are recovered by walking the transform back to a sampled entry of the suffix
+//! array.  The records of a reference are joined by a separator that no query may match, so no hit
+//! runs across the end of a record.
+//!
+//! Indexes are saved in a little-endian binary format:
+//!
+//! ```text
+//! MAGIC  SIZE  MODIFIED  LENGTH  BWT  RECORDS  (NAME_LENGTH  NAME  START)*  SAMPLES  (ROW  POSITION)*
+//! ```
+//!
+//! where every number is a u64.  SIZE and MODIFIED stamp the reference file the index was built
+//! from, so that an index left behind by an edited reference is rebuilt instead of trusted.
+//! Checkpoints are rebuilt when an index is read.
+//!
+use crate::common::input::Input;
+use crate::common::{fasta, gzip, index};
+use crate::errors::{Error, ErrorKind, Result};
+use std::io::{Read, Write};
+use std::ops::Range;
+
+/// File extension of FM-index files
+pub const EXTENSION: &str = ".fmi";
+/// Marker of the end of the text, smaller than every symbol of a sequence
+pub const SENTINEL: u8 = b'$';
+/// Marker between the records of a reference
+const SEPARATOR: u8 = b'|';
+const MAGIC: &[u8; 4] = b"FMI2";
+/// Number of rows between checkpoints of the symbol counts
+const INTERVAL: usize = 64;
+/// Text positions that are a multiple of the rate have their row sampled
+const SAMPLE_RATE: usize = 32;
+/// Rank of a symbol that does not occur in the transform
+const NONE: usize = usize::MAX;
+/// Header of the tab separated table of hits
+pub const TABLE_HEADER: &str = "query\tsequence\tstart\tend\tmismatches";
+
+/// Return the Burrows-Wheeler transform of the text
+///
+/// The text should end with a sentinel that occurs nowhere else and is smaller than every other
+/// symbol, such as '$', so that sorting its suffixes sorts its rotations.
+///
+pub fn bwt(text: &[u8]) -> Vec<u8> {
+    index::suffix_array(text)
+        .iter()
+        .map(|start| text[(start + text.len() - 1) % text.len()])
+        .collect()
+}
+
+/// Occurrence of a query in the reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hit {
+    /// Index of the record
+    pub record: usize,
+    /// 0-based start within the record
+    pub start: usize,
+    pub mismatches: usize,
+}
+
+impl Hit {
+    /// Return the hit of a query of the given length as a row of the tab separated table
+    ///
+    /// Positions are 1-based and inclusive.
+    ///
+    pub fn to_row(&self, query: &str, sequence: &str, length: usize) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            query,
+            sequence,
+            self.start + 1,
+            self.start + length,
+            self.mismatches
+        )
+    }
+}
+
+/// Size and modification time of the reference file an index was built from
+///
+/// Indexes that were not built from a file have the zero stamp.
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Stamp {
+    size: u64,
+    /// Nanoseconds since the unix epoch
+    modified: u64,
+}
+
+impl Stamp {
+    /// Return the stamp of the file at the path
+    fn of(path: &str) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+        Ok(Self {
+            size: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// FM-index of a collection of sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct FmIndex {
+    /// Stamp of the reference file, checked when the index is loaded from a path
+    stamp: Stamp,
+    bwt: Vec<u8>,
+    names: Vec<String>,
+    /// Start of every record in the text
+    starts: Vec<usize>,
+    /// Rows and text positions of the sampled suffixes, sorted by row
+    samples: Vec<(usize, usize)>,
+    /// Symbols of the transform in sorted order
+    alphabet: Vec<u8>,
+    /// Rank of every byte in the alphabet
+    ranks: [usize; 256],
+    /// First row of every symbol of the alphabet in the sorted rotations
+    first: Vec<usize>,
+    /// Counts of every symbol of the alphabet before each checkpoint
+    checkpoints: Vec<usize>,
+}
+
+impl FmIndex {
+    /// Build the index of unnamed sequences
+    ///
+    /// An error is returned if a sequence contains the sentinel or the separator.
+    ///
+    pub fn new<S>(sequences: &[S]) -> Result<Self>
+    where
+        S: AsRef<[u8]>,
+    {
+        let names = vec![String::new(); sequences.len()];
+        Self::build(names, sequences)
+    }
+
+    /// Build the index of the records of a fasta file
+    ///
+    /// Sequences are upper cased so that soft-masked bases are found too.  An error is returned if
+    /// a sequence contains the sentinel or the separator.
+    ///
+    pub fn from_fasta<R>(reader: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut names = Vec::new();
+        let mut sequences = Vec::new();
+        let mut reader = fasta::Reader::new(reader);
+        while let Some(record) = reader.next_record() {
+            let record = record?;
+            names.push(String::from_utf8_lossy(&record.name).into_owned());
+            sequences.push(record.sequence.to_ascii_uppercase());
+        }
+        Self::build(names, &sequences)
+    }
+
+    /// Read the index saved next to a fasta file, or build it when there is none
+    ///
+    /// A saved index whose stamp does not match the size and modification time of the fasta file
+    /// is stale and the index is rebuilt.  The fasta file may be gzip compressed.
+    ///
+    pub fn from_path(path: &str) -> Result<Self> {
+        let stamp = Stamp::of(path)?;
+        match std::fs::File::open(index_path(path)) {
+            Ok(file) => {
+                let index = Self::read(file)?;
+                if index.stamp == stamp {
+                    return Ok(index);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let mut index = Self::from_fasta(gzip::decode(std::fs::File::open(path)?)?)?;
+        index.stamp = stamp;
+        Ok(index)
+    }
+
+    /// Read a saved index
+    pub fn read<R>(reader: R) -> Result<Self>
+    where
+        R: std::io::Read,
+    {
+        let mut reader = std::io::BufReader::new(reader);
+        let mut magic = [0; MAGIC.len()];
+        read_exact(&mut reader, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_format("unknown file signature"));
+        }
+        let stamp = Stamp {
+            size: read_u64(&mut reader)?,
+            modified: read_u64(&mut reader)?,
+        };
+        let length = read_usize(&mut reader)?;
+        let bwt = read_bytes(&mut reader, length)?;
+        if bwt.iter().filter(|symbol| **symbol == SENTINEL).count() != 1 {
+            return Err(invalid_format("expected a single sentinel"));
+        }
+        let count = read_usize(&mut reader)?;
+        let mut names = Vec::new();
+        let mut starts = Vec::new();
+        for _ in 0..count {
+            let length = read_usize(&mut reader)?;
+            names.push(String::from_utf8(read_bytes(&mut reader, length)?)?);
+            starts.push(read_usize(&mut reader)?);
+        }
+        if starts.first().is_some_and(|start| *start != 0)
+            || !starts.windows(2).all(|pair| pair[0] < pair[1])
+            || starts.last() >= Some(&length)
+        {
+            return Err(invalid_format("record starts out of order"));
+        }
+        let count = read_usize(&mut reader)?;
+        let mut samples = Vec::new();
+        for _ in 0..count {
+            samples.push((read_usize(&mut reader)?, read_usize(&mut reader)?));
+        }
+        if !samples.windows(2).all(|pair| pair[0].0 < pair[1].0)
+            || samples.last().is_some_and(|(row, _)| *row >= length)
+        {
+            return Err(invalid_format("sampled rows out of order"));
+        }
+        let mut index = Self::from_parts(bwt, names, starts, samples);
+        index.stamp = stamp;
+        index.check_walk()?;
+        Ok(index)
+    }
+
+    /// Save the index
+    pub fn write<W>(&self, writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut writer = std::io::BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.stamp.size.to_le_bytes())?;
+        writer.write_all(&self.stamp.modified.to_le_bytes())?;
+        write_usize(&mut writer, self.bwt.len())?;
+        writer.write_all(&self.bwt)?;
+        write_usize(&mut writer, self.names.len())?;
+        for (name, start) in self.names.iter().zip(&self.starts) {
+            write_usize(&mut writer, name.len())?;
+            writer.write_all(name.as_bytes())?;
+            write_usize(&mut writer, *start)?;
+        }
+        write_usize(&mut writer, self.samples.len())?;
+        for (row, position) in &self.samples {
+            write_usize(&mut writer, *row)?;
+            write_usize(&mut writer, *position)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Save the index next to a fasta file, where `from_path` reads it
+    pub fn save(&self, path: &str) -> Result<()> {
+        let index_path = index_path(path);
+        std::fs::File::create(&index_path)
+            .map_err(Error::from)
+            .and_then(|file| self.write(file))
+            .map_err(|e| e.in_file(&index_path))
+    }
+
+    /// Write the occurrences of the sequence of every fasta or fastq record of the input as a
+    /// table
+    ///
+    /// Queries are matched in upper case, with at most the given mismatches.
+    ///
+    pub fn write_search<W>(&self, input: &Input, mismatches: usize, mut writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(writer, "{}", TABLE_HEADER)?;
+        input.for_each_sequence(|name, sequence| {
+            let query = sequence.to_ascii_uppercase();
+            for hit in self.locate_approximate(&query, mismatches) {
+                let sequence = &self.names[hit.record];
+                writeln!(writer, "{}", hit.to_row(name, sequence, query.len()))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns the Burrows-Wheeler transform of the joined records
+    pub fn bwt(&self) -> &[u8] {
+        &self.bwt
+    }
+
+    /// Returns the names of the records
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Return the number of occurrences of the pattern
+    pub fn count(&self, pattern: &[u8]) -> usize {
+        self.count_approximate(pattern, 0)
+    }
+
+    /// Return the occurrences of the pattern, in order
+    pub fn locate(&self, pattern: &[u8]) -> Vec<Hit> {
+        self.locate_approximate(pattern, 0)
+    }
+
+    /// Return the number of occurrences of the pattern with at most the given mismatches
+    pub fn count_approximate(&self, pattern: &[u8], mismatches: usize) -> usize {
+        self.search(pattern, mismatches)
+            .iter()
+            .map(|(rows, _)| rows.len())
+            .sum()
+    }
+
+    /// Return the occurrences of the pattern with at most the given mismatches, in order
+    ///
+    /// Every start is reported once, with the number of mismatches of the substring there.  An
+    /// empty pattern has no occurrences.
+    ///
+    pub fn locate_approximate(&self, pattern: &[u8], mismatches: usize) -> Vec<Hit> {
+        let mut hits: Vec<Hit> = self
+            .search(pattern, mismatches)
+            .into_iter()
+            .flat_map(|(rows, mismatches)| {
+                rows.map(move |row| {
+                    let position = self.position(row);
+                    let record = self.starts.partition_point(|start| *start <= position) - 1;
+                    Hit {
+                        record,
+                        start: position - self.starts[record],
+                        mismatches,
+                    }
+                })
+            })
+            .collect();
+        hits.sort_unstable();
+        hits
+    }
+
+    /// Build the index of named sequences
+    fn build<S>(names: Vec<String>, sequences: &[S]) -> Result<Self>
+    where
+        S: AsRef<[u8]>,
+    {
+        for (k, sequence) in sequences.iter().enumerate() {
+            let sequence = sequence.as_ref();
+            if let Some(i) = sequence
+                .iter()
+                .position(|symbol| *symbol == SENTINEL || *symbol == SEPARATOR)
+            {
+                return Err(Error::new(
+                    ErrorKind::Alphabet,
+                    &format!(
+                        "reserved symbol {} at position {} of sequence {}",
+                        sequence[i] as char,
+                        i + 1,
+                        k + 1
+                    ),
+                ));
+            }
+        }
+        let length = sequences
+            .iter()
+            .map(|sequence| sequence.as_ref().len() + 1)
+            .sum::<usize>();
+        let mut text = Vec::with_capacity(length.max(1));
+        let mut starts = Vec::with_capacity(sequences.len());
+        for (k, sequence) in sequences.iter().enumerate() {
+            if k > 0 {
+                text.push(SEPARATOR);
+            }
+            starts.push(text.len());
+            text.extend_from_slice(sequence.as_ref());
+        }
+        text.push(SENTINEL);
+        let suffix_array = index::suffix_array(&text);
+        let bwt = suffix_array
+            .iter()
+            .map(|start| text[(start + text.len() - 1) % text.len()])
+            .collect();
+        let samples = suffix_array
+            .into_iter()
+            .enumerate()
+            .filter(|(_, position)| position.is_multiple_of(SAMPLE_RATE))
+            .collect();
+        Ok(Self::from_parts(bwt, names, starts, samples))
+    }
+
+    /// Assemble the index and its checkpoints
+    fn from_parts(
+        bwt: Vec<u8>,
+        names: Vec<String>,
+        starts: Vec<usize>,
+        samples: Vec<(usize, usize)>,
+    ) -> Self {
+        let mut counts = [0; 256];
+        for symbol in &bwt {
+            counts[*symbol as usize] += 1;
+        }
+        let alphabet: Vec<u8> = (0..=u8::MAX)
+            .filter(|symbol| counts[*symbol as usize] > 0)
+            .collect();
+        let mut ranks = [NONE; 256];
+        let mut first = Vec::with_capacity(alphabet.len());
+        let mut total = 0;
+        for (rank, symbol) in alphabet.iter().enumerate() {
+            ranks[*symbol as usize] = rank;
+            first.push(total);
+            total += counts[*symbol as usize];
+        }
+        let mut checkpoints = Vec::with_capacity((bwt.len() / INTERVAL + 1) * alphabet.len());
+        let mut running = vec![0; alphabet.len()];
+        for (row, symbol) in bwt.iter().enumerate() {
+            if row.is_multiple_of(INTERVAL) {
+                checkpoints.extend_from_slice(&running);
+            }
+            running[ranks[*symbol as usize]] += 1;
+        }
+        if bwt.len().is_multiple_of(INTERVAL) {
+            checkpoints.extend_from_slice(&running);
+        }
+        Self {
+            stamp: Stamp::default(),
+            bwt,
+            names,
+            starts,
+            samples,
+            alphabet,
+            ranks,
+            first,
+            checkpoints,
+        }
+    }
+
+    /// Return the number of times the symbol of the given rank occurs before the row
+    fn occurrences(&self, rank: usize, row: usize) -> usize {
+        let checkpoint = row / INTERVAL;
+        let symbol = self.alphabet[rank];
+        self.checkpoints[checkpoint * self.alphabet.len() + rank]
+            + self.bwt[checkpoint * INTERVAL..row]
+                .iter()
+                .filter(|other| **other == symbol)
+                .count()
+    }
+
+    /// Return the rows of the rotations starting with the symbol followed by the given rows
+    fn extend(&self, symbol: u8, rows: &Range<usize>) -> Option<Range<usize>> {
+        let rank = self.ranks[symbol as usize];
+        if rank == NONE {
+            return None;
+        }
+        let start = self.first[rank] + self.occurrences(rank, rows.start);
+        let end = self.first[rank] + self.occurrences(rank, rows.end);
+        (start < end).then_some(start..end)
+    }
+
+    /// Return the row of the rotation starting one symbol before the rotation at the row
+    fn previous(&self, row: usize) -> usize {
+        let rank = self.ranks[self.bwt[row] as usize];
+        self.first[rank] + self.occurrences(rank, row)
+    }
+
+    /// Return the text position of the suffix at the row
+    ///
+    /// Position 0 is always sampled, so a sampled row is reached within a walk of the whole text.
+    ///
+    fn position(&self, row: usize) -> usize {
+        let mut row = row;
+        for steps in 0..self.bwt.len() {
+            if let Ok(i) = self.samples.binary_search_by_key(&row, |(row, _)| *row) {
+                return self.samples[i].1 + steps;
+            }
+            row = self.previous(row);
+        }
+        unreachable!("no sampled row within a walk of the text, which reading the index checks")
+    }
+
+    /// Check that walking back from the sentinel visits every row once, and reaches every sampled
+    /// row at its position, including the row of position 0
+    fn check_walk(&self) -> Result<()> {
+        let length = self.bwt.len();
+        let mut positions = vec![NONE; length];
+        // The rotation starting with the sentinel sorts first
+        let mut row = 0;
+        for position in (0..length).rev() {
+            if positions[row] != NONE {
+                return Err(invalid_format(
+                    "transform is not a single rotation of a text",
+                ));
+            }
+            positions[row] = position;
+            row = self.previous(row);
+        }
+        if !self
+            .samples
+            .iter()
+            .all(|(row, position)| positions[*row] == *position)
+        {
+            return Err(invalid_format(
+                "sampled positions do not match the transform",
+            ));
+        }
+        if !self.samples.iter().any(|(_, position)| *position == 0) {
+            return Err(invalid_format("missing the sample of position 0"));
+        }
+        Ok(())
+    }
+
+    /// Return the row ranges of the substrings within the mismatches of the pattern, with their
+    /// number of mismatches
+    fn search(&self, pattern: &[u8], mismatches: usize) -> Vec<(Range<usize>, usize)> {
+        let mut found = Vec::new();
+        if !pattern.is_empty() {
+            self.backtrack(pattern, mismatches, 0, 0..self.bwt.len(), &mut found);
+        }
+        found
+    }
+
+    /// Extend the rows by the last symbol of the pattern, substituting other symbols while the
+    /// budget of mismatches lasts
+    fn backtrack(
+        &self,
+        pattern: &[u8],
+        budget: usize,
+        mismatches: usize,
+        rows: Range<usize>,
+        found: &mut Vec<(Range<usize>, usize)>,
+    ) {
+        let Some((symbol, prefix)) = pattern.split_last() else {
+            found.push((rows, mismatches));
+            return;
+        };
+        let candidates: &[u8] = if mismatches < budget {
+            &self.alphabet
+        } else {
+            std::slice::from_ref(symbol)
+        };
+        for candidate in candidates {
+            if *candidate == SENTINEL || *candidate == SEPARATOR {
+                continue;
+            }
+            if let Some(rows) = self.extend(*candidate, &rows) {
+                let mismatches = mismatches + usize::from(candidate != symbol);
+                self.backtrack(prefix, budget, mismatches, rows, found);
+            }
+        }
+    }
+}
+
+/// Return the path of the FM-index of a fasta file
+pub fn index_path(path: &str) -> String {
+    format!("{}{}", path, EXTENSION)
+}
+
+/// Error for a malformed index file
+fn invalid_format(message: &str) -> Error {
+    Error::new(
+        ErrorKind::Parse,
+        &format!("invalid fmi format: {}", message),
+    )
+}
+
+/// Fill the buffer, treating a short read as a truncated index
+fn read_exact<R>(reader: &mut R, buffer: &mut [u8]) -> Result<()>
+where
+    R: Read,
+{
+    reader.read_exact(buffer).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => invalid_format("truncated file"),
+        _ => e.into(),
+    })
+}
+
+/// Read the given number of bytes, treating a short read as a truncated index
+///
+/// The buffer grows with the bytes actually read, so a corrupt length cannot exhaust memory.
+///
+fn read_bytes<R>(reader: &mut R, length: usize) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < length {
+        return Err(invalid_format("truncated file"));
+    }
+    Ok(bytes)
+}
+
+/// Read a little-endian u64
+fn read_u64<R>(reader: &mut R) -> Result<u64>
+where
+    R: Read,
+{
+    let mut bytes = [0; 8];
+    read_exact(reader, &mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Read a little-endian u64 that must fit in a usize
+fn read_usize<R>(reader: &mut R) -> Result<usize>
+where
+    R: Read,
+{
+    usize::try_from(read_u64(reader)?).map_err(|e| invalid_format(&e.to_string()))
+}
+
+/// Write a little-endian u64
+fn write_usize<W>(writer: &mut W, value: usize) -> Result<()>
+where
+    W: Write,
+{
+    writer.write_all(&(value as u64).to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the sorted starts of the windows of the text within the mismatches of the pattern
+    fn naive(text: &[u8], pattern: &[u8], mismatches: usize) -> Vec<(usize, usize)> {
+        if pattern.is_empty() || pattern.len() > text.len() {
+            return Vec::new();
+        }
+        text.windows(pattern.len())
+            .enumerate()
+            .map(|(start, window)| {
+                let distance = window.iter().zip(pattern).filter(|(a, b)| a != b).count();
+                (start, distance)
+            })
+            .filter(|(_, distance)| *distance <= mismatches)
+            .collect()
+    }
+
+    #[test]
+    fn test_bwt() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should transform the text",
+                text: "GCGTGCCTGGTCA$",
+                expected: "ACTGGCT$TGCGGC",
+            },
+            TestCase {
+                name: "Should transform a repetitive text",
+                text: "banana$",
+                expected: "annb$aa",
+            },
+            TestCase {
+                name: "Should transform the sentinel alone",
+                text: "$",
+                expected: "$",
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected.as_bytes(),
+                bwt(test_case.text.as_bytes()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            pattern: &'a str,
+            mismatches: usize,
+            expected: Vec<(usize, usize, usize)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should locate exact occurrences",
+                sequences: vec!["AATCGGGTTCAATCGGGGT"],
+                pattern: "ATCG",
+                mismatches: 0,
+                expected: vec![(0, 1, 0), (0, 11, 0)],
+            },
+            TestCase {
+                name: "Should locate occurrences with mismatches",
+                sequences: vec!["ACATGCTACTTT"],
+                pattern: "ATT",
+                mismatches: 1,
+                expected: vec![(0, 2, 1), (0, 7, 1), (0, 8, 1), (0, 9, 1)],
+            },
+            TestCase {
+                name: "Should locate occurrences in every record",
+                sequences: vec!["ACGT", "TTACG", "CG"],
+                pattern: "CG",
+                mismatches: 0,
+                expected: vec![(0, 1, 0), (1, 3, 0), (2, 0, 0)],
+            },
+            TestCase {
+                name: "Should not match across records",
+                sequences: vec!["AAC", "GTT"],
+                pattern: "ACG",
+                mismatches: 0,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Should not substitute the separator",
+                sequences: vec!["AA", "AA"],
+                pattern: "AAA",
+                mismatches: 1,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Should substitute symbols missing from the reference",
+                sequences: vec!["ACGT"],
+                pattern: "CNT",
+                mismatches: 1,
+                expected: vec![(0, 1, 1)],
+            },
+            TestCase {
+                name: "Should locate nothing for an empty pattern",
+                sequences: vec!["ACGT"],
+                pattern: "",
+                mismatches: 1,
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let index = FmIndex::new(&test_case.sequences).unwrap();
+            let hits: Vec<(usize, usize, usize)> = index
+                .locate_approximate(test_case.pattern.as_bytes(), test_case.mismatches)
+                .iter()
+                .map(|hit| (hit.record, hit.start, hit.mismatches))
+                .collect();
+            assert_eq!(test_case.expected, hits, "{}", test_case.name);
+            assert_eq!(
+                test_case.expected.len(),
+                index.count_approximate(test_case.pattern.as_bytes(), test_case.mismatches),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_locate_naive() {
+        // Pseudo-random text long enough to need checkpoints and walks to sampled rows
+        let mut state: u32 = 7;
+        let text: Vec<u8> = (0..1000)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                b"ACGT"[(state >> 16) as usize % 4]
+            })
+            .collect();
+        let index = FmIndex::new(&[&text]).unwrap();
+        for start in (0..990).step_by(37) {
+            for length in [1, 4, 9] {
+                let pattern = &text[start..start + length];
+                for mismatches in 0..3 {
+                    let hits: Vec<(usize, usize)> = index
+                        .locate_approximate(pattern, mismatches)
+                        .iter()
+                        .map(|hit| (hit.start, hit.mismatches))
+                        .collect();
+                    assert_eq!(
+                        naive(&text, pattern, mismatches),
+                        hits,
+                        "Should match a naive scan for {:?} with {} mismatches",
+                        std::str::from_utf8(pattern),
+                        mismatches
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_read() {
+        let fasta = b">chr1\nacgtACGT\nAC\n>chr2\nGGGCCCAT\n";
+        let index = FmIndex::from_fasta(&fasta[..]).unwrap();
+        assert_eq!(["chr1", "chr2"], index.names());
+        let mut bytes = Vec::new();
+        index.write(&mut bytes).unwrap();
+        let read = FmIndex::read(&bytes[..]);
+        assert_eq!(Ok(&index), read.as_ref(), "Should read back the same index");
+        assert_eq!(
+            vec![
+                Hit {
+                    record: 0,
+                    start: 2,
+                    mismatches: 0
+                },
+                Hit {
+                    record: 0,
+                    start: 6,
+                    mismatches: 0
+                },
+            ],
+            read.unwrap().locate(b"GTAC"),
+            "Should locate upper cased sequences in the read index"
+        );
+        let truncated = FmIndex::read(&bytes[..bytes.len() - 1]).map_err(|e| *e.kind());
+        assert_eq!(
+            Err(ErrorKind::Parse),
+            truncated,
+            "Should reject a truncated index"
+        );
+        let unknown = FmIndex::read(&b"FAI1"[..]).map_err(|e| *e.kind());
+        assert_eq!(
+            Err(ErrorKind::Parse),
+            unknown,
+            "Should reject an unknown signature"
+        );
+    }
+
+    #[test]
+    fn test_read_corrupt() {
+        let index = FmIndex::new(&["ACGTTGCA", "GGATCC"]).unwrap();
+        let bytes = |index: &FmIndex| {
+            let mut bytes = Vec::new();
+            index.write(&mut bytes).unwrap();
+            bytes
+        };
+        let valid = bytes(&index);
+        // Offsets of the length and of the transform after the signature and the stamp
+        let (length, bwt) = (MAGIC.len() + 16, MAGIC.len() + 24);
+        let mut huge_length = valid.clone();
+        huge_length[length..bwt].copy_from_slice(&(u32::MAX as u64).to_le_bytes());
+        let mut swapped = valid.clone();
+        let a = bwt + index.bwt.iter().position(|symbol| *symbol == b'A').unwrap();
+        let c = bwt + index.bwt.iter().position(|symbol| *symbol == b'C').unwrap();
+        swapped.swap(a, c);
+        let mut unsampled = index.clone();
+        unsampled.samples.clear();
+        let mut shifted = index.clone();
+        shifted.samples[0].1 += 1;
+        struct TestCase<'a> {
+            name: &'a str,
+            bytes: Vec<u8>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should reject a length longer than the file",
+                bytes: huge_length,
+            },
+            TestCase {
+                name: "Should reject a transform that is not a rotation of a text",
+                bytes: swapped,
+            },
+            TestCase {
+                name: "Should reject an index without samples",
+                bytes: bytes(&unsampled),
+            },
+            TestCase {
+                name: "Should reject samples that do not match the transform",
+                bytes: bytes(&shifted),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                Err(ErrorKind::Parse),
+                FmIndex::read(&test_case.bytes[..]).map_err(|e| *e.kind()),
+                "{}",
+                test_case.name
+            );
+        }
+        assert_eq!(
+            Ok(&index),
+            FmIndex::read(&valid[..]).as_ref(),
+            "Should read back the valid index"
+        );
+    }
+
+    #[test]
+    fn test_new_reserved() {
+        for sequence in ["AC$GT", "AC|GT"] {
+            assert_eq!(
+                Err(ErrorKind::Alphabet),
+                FmIndex::new(&["ACGT", sequence]).map_err(|e| *e.kind()),
+                "Should reject the reserved symbol in {}",
+                sequence
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_path() {
+        let path = std::env::temp_dir().join(format!("fmindex-{}.fa.gz", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut writer = gzip::encode(std::fs::File::create(path).unwrap());
+        writer.write_all(b">chr1\nACGTACGT\n").unwrap();
+        writer.finish().unwrap();
+        let index = FmIndex::from_path(path).unwrap();
+        assert_eq!(
+            1,
+            index.count(b"GTAC"),
+            "Should build the index of a gzip compressed reference"
+        );
+        index.save(path).unwrap();
+        assert_eq!(
+            Ok(&index),
+            FmIndex::from_path(path).as_ref(),
+            "Should read the saved index of an unchanged reference"
+        );
+        let mut writer = gzip::encode(std::fs::File::create(path).unwrap());
+        writer.write_all(b">chr1\nTTTTTTTTTTTT\n").unwrap();
+        writer.finish().unwrap();
+        let rebuilt = FmIndex::from_path(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(index_path(path)).unwrap();
+        assert_eq!(
+            (0, 1),
+            (rebuilt.count(b"GTAC"), rebuilt.count(b"TTTTTTTTTTTT")),
+            "Should rebuild the index of a changed reference"
+        );
+    }
+
+    #[test]
+    fn test_write_search() {
+        let index = FmIndex::from_fasta(&b">chr1\nACGTACGT\n>chr2\nTTACGA\n"[..]).unwrap();
+        let mut output = Vec::new();
+        index
+            .write_search(
+                &Input::Text(">q1\nacg\n>q2\nGGG\n".to_owned()),
+                0,
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(
+            "query\tsequence\tstart\tend\tmismatches\n\
+             q1\tchr1\t1\t3\t0\n\
+             q1\tchr1\t5\t7\t0\n\
+             q1\tchr2\t3\t5\t0\n",
+            String::from_utf8(output).unwrap(),
+            "{}",
+            "Should write the occurrences of every query in upper case"
+        );
+    }
+}
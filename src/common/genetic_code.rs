@@ -0,0 +1,434 @@
+//! NCBI genetic codes (https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi)
+//!
+//! Each table lists the amino acid of every codon, and which codons can start translation, in the
+//! NCBI order where the bases of each position run through T, C, A, G.  Stop codons are '*'.
+//!
+//! Tables 27, 28 and 31 have codons that are read as a stop only at the end of a gene, which the
+//! NCBI marks with '*' among the starts.  These codons are translated as their amino acid, but
+//! they also count as stops, so translation and open reading frames may end at them.
+//!
+use crate::errors::{Error, ErrorKind, Result};
+
+/// Order of the bases in a table
+const BASE_ORDER: &[u8] = b"TCAG";
+/// Symbol of a stop codon
+pub const STOP: u8 = b'*';
+const START: u8 = b'M';
+/// ID of the standard code
+pub const STANDARD: u8 = 1;
+
+/// Genetic code translating codons into amino acids
+#[derive(Debug, PartialEq)]
+pub struct GeneticCode {
+    id: u8,
+    name: &'static str,
+    amino_acids: [u8; 64],
+    starts: [u8; 64],
+}
+
+/// Every NCBI translation table
+static TABLES: &[GeneticCode] = &[
+    GeneticCode {
+        id: 1,
+        name: "Standard",
+        amino_acids: *b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"---M------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 2,
+        name: "Vertebrate Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG",
+        starts: *b"----------**--------------------MMMM----------**---M------------",
+    },
+    GeneticCode {
+        id: 3,
+        name: "Yeast Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWTTTTPPPPHHQQRRRRIIMMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**----------------------MM----------------------------",
+    },
+    GeneticCode {
+        id: 4,
+        name: "Mold, Protozoan, and Coelenterate Mitochondrial and Mycoplasma/Spiroplasma",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--MM------**-------M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 5,
+        name: "Invertebrate Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG",
+        starts: *b"---M------**--------------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 6,
+        name: "Ciliate, Dasycladacean and Hexamita Nuclear",
+        amino_acids: *b"FFLLSSSSYYQQCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 9,
+        name: "Echinoderm and Flatworm Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: *b"----------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 10,
+        name: "Euplotid Nuclear",
+        amino_acids: *b"FFLLSSSSYY**CCCWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 11,
+        name: "Bacterial, Archaeal and Plant Plastid",
+        amino_acids: *b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"---M------**--*----M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 12,
+        name: "Alternative Yeast Nuclear",
+        amino_acids: *b"FFLLSSSSYY**CC*WLLLSPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 13,
+        name: "Ascidian Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSGGVVVVAAAADDEEGGGG",
+        starts: *b"---M------**----------------------MM---------------M------------",
+    },
+    GeneticCode {
+        id: 14,
+        name: "Alternative Flatworm Mitochondrial",
+        amino_acids: *b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: *b"-----------*-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 15,
+        name: "Blepharisma Nuclear",
+        amino_acids: *b"FFLLSSSSYY*QCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------*---*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 16,
+        name: "Chlorophycean Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------*---*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 21,
+        name: "Trematode Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNNKSSSSVVVVAAAADDEEGGGG",
+        starts: *b"----------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 22,
+        name: "Scenedesmus obliquus Mitochondrial",
+        amino_acids: *b"FFLLSS*SYY*LCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"------*---*---*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 23,
+        name: "Thraustochytrium Mitochondrial",
+        amino_acids: *b"FF*LSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--*-------**--*-----------------M--M---------------M------------",
+    },
+    GeneticCode {
+        id: 24,
+        name: "Rhabdopleuridae Mitochondrial",
+        amino_acids: *b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: *b"---M------**-------M---------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 25,
+        name: "Candidate Division SR1 and Gracilibacteria",
+        amino_acids: *b"FFLLSSSSYY**CCGWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"---M------**-----------------------M---------------M------------",
+    },
+    GeneticCode {
+        id: 26,
+        name: "Pachysolen tannophilus Nuclear",
+        amino_acids: *b"FFLLSSSSYY**CC*WLLLAPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**--*----M---------------M----------------------------",
+    },
+    GeneticCode {
+        id: 27,
+        name: "Karyorelict Nuclear",
+        amino_acids: *b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 28,
+        name: "Condylostoma Nuclear",
+        amino_acids: *b"FFLLSSSSYYQQCCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**--*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 29,
+        name: "Mesodinium Nuclear",
+        amino_acids: *b"FFLLSSSSYYYYCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 30,
+        name: "Peritrich Nuclear",
+        amino_acids: *b"FFLLSSSSYYEECC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"--------------*--------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 31,
+        name: "Blastocrithidia Nuclear",
+        amino_acids: *b"FFLLSSSSYYEECCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"----------**-----------------------M----------------------------",
+    },
+    GeneticCode {
+        id: 32,
+        name: "Balanophoraceae Plastid",
+        amino_acids: *b"FFLLSSSSYY*WCC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG",
+        starts: *b"---M------*---*----M------------MMMM---------------M------------",
+    },
+    GeneticCode {
+        id: 33,
+        name: "Cephalodiscidae Mitochondrial",
+        amino_acids: *b"FFLLSSSSYYY*CCWWLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSSKVVVVAAAADDEEGGGG",
+        starts: *b"---M-------*-------M---------------M---------------M------------",
+    },
+];
+
+impl GeneticCode {
+    /// Return the standard code
+    pub fn standard() -> &'static Self {
+        &TABLES[0]
+    }
+
+    /// Find a code by its NCBI table ID
+    pub fn from_id(id: u8) -> Result<&'static Self> {
+        TABLES.iter().find(|code| code.id == id).ok_or_else(|| {
+            Error::new(
+                ErrorKind::User,
+                &format!("unknown genetic code table: {}", id),
+            )
+        })
+    }
+
+    /// Return every code
+    pub fn all() -> &'static [Self] {
+        TABLES
+    }
+
+    /// Returns the NCBI table ID
+    pub fn id(&self) -> u8 {
+        self.id
+    }
+
+    /// Returns the name of the code
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Translate a DNA or RNA codon
+    ///
+    /// None is returned for a stop codon, and an error is returned if the codon is not made of
+    /// three bases.
+    ///
+    pub fn translate(&self, codon: &[u8]) -> Result<Option<u8>> {
+        let amino_acid = self.amino_acids[index(codon)?];
+        if amino_acid == STOP {
+            return Ok(None);
+        }
+        Ok(Some(amino_acid))
+    }
+
+    /// Check to see if the codon can start translation, including alternative start codons
+    pub fn is_start(&self, codon: &[u8]) -> bool {
+        index(codon).is_ok_and(|i| self.starts[i] == START)
+    }
+
+    /// Check to see if the codon can stop translation, including codons that stop it only at the
+    /// end of a gene
+    pub fn is_stop(&self, codon: &[u8]) -> bool {
+        index(codon).is_ok_and(|i| self.amino_acids[i] == STOP || self.starts[i] == STOP)
+    }
+
+    /// Return the number of codons that translate into the amino acid, or that can stop
+    /// translation for '*'
+    pub fn codon_count(&self, amino_acid: u8) -> usize {
+        self.amino_acids
+            .iter()
+            .zip(&self.starts)
+            .filter(|(symbol, start)| {
+                **symbol == amino_acid || amino_acid == STOP && **start == STOP
+            })
+            .count()
+    }
+}
+
+/// Return the index of a codon in a table
+fn index(codon: &[u8]) -> Result<usize> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::Alphabet,
+            &format!("unrecognized codon: {}", String::from_utf8_lossy(codon)),
+        )
+    };
+    if codon.len() != 3 {
+        return Err(invalid());
+    }
+    codon.iter().try_fold(0, |index, base| {
+        let base = match base.to_ascii_uppercase() {
+            b'U' => b'T',
+            base => base,
+        };
+        let position = BASE_ORDER
+            .iter()
+            .position(|b| *b == base)
+            .ok_or_else(invalid)?;
+        Ok(index * BASE_ORDER.len() + position)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genetic_code_translate() {
+        struct TestCase<'a> {
+            name: &'a str,
+            id: u8,
+            codon: &'a [u8],
+            expected: Result<Option<u8>>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should translate a dna codon",
+                id: 1,
+                codon: b"ATG",
+                expected: Ok(Some(b'M')),
+            },
+            TestCase {
+                name: "Should translate an rna codon",
+                id: 1,
+                codon: b"UGG",
+                expected: Ok(Some(b'W')),
+            },
+            TestCase {
+                name: "Should translate a stop codon",
+                id: 1,
+                codon: b"TGA",
+                expected: Ok(None),
+            },
+            TestCase {
+                name: "Should use the vertebrate mitochondrial code",
+                id: 2,
+                codon: b"TGA",
+                expected: Ok(Some(b'W')),
+            },
+            TestCase {
+                name: "Should return an error on an invalid codon",
+                id: 1,
+                codon: b"AXG",
+                expected: Err(Error::new(ErrorKind::Alphabet, "unrecognized codon: AXG")),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                GeneticCode::from_id(test_case.id)
+                    .unwrap()
+                    .translate(test_case.codon),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_genetic_code_from_id() {
+        let ids = (1..=6).chain(9..=16).chain(21..=33);
+        for id in ids.clone() {
+            assert_eq!(
+                Ok(id),
+                GeneticCode::from_id(id).map(|code| code.id()),
+                "Should find NCBI table {}",
+                id
+            );
+        }
+        assert_eq!(
+            ids.collect::<Vec<u8>>(),
+            GeneticCode::all()
+                .iter()
+                .map(|code| code.id())
+                .collect::<Vec<u8>>(),
+            "Should list every NCBI table in order"
+        );
+        assert!(
+            GeneticCode::from_id(7).is_err(),
+            "Should reject a retired table"
+        );
+    }
+
+    #[test]
+    fn test_genetic_code_is_start() {
+        let code = GeneticCode::from_id(11).unwrap();
+        assert!(
+            code.is_start(b"GTG"),
+            "{}",
+            "Should accept an alternative start codon"
+        );
+        assert!(
+            !GeneticCode::standard().is_start(b"GTG"),
+            "{}",
+            "Should reject a codon that is not a start in the standard code"
+        );
+    }
+
+    #[test]
+    fn test_genetic_code_codon_count() {
+        struct TestCase<'a> {
+            name: &'a str,
+            id: u8,
+            amino_acid: u8,
+            expected: usize,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should count the codons of an amino acid",
+                id: 1,
+                amino_acid: b'L',
+                expected: 6,
+            },
+            TestCase {
+                name: "Should count the stop codons",
+                id: 1,
+                amino_acid: STOP,
+                expected: 3,
+            },
+            TestCase {
+                name: "Should count the stop codons of the vertebrate mitochondrial code",
+                id: 2,
+                amino_acid: STOP,
+                expected: 4,
+            },
+            TestCase {
+                name: "Should count codons that are stops only at the end of a gene",
+                id: 28,
+                amino_acid: STOP,
+                expected: 3,
+            },
+            TestCase {
+                name: "Should count codons that are stops only at the end of a gene as amino acids",
+                id: 28,
+                amino_acid: b'Q',
+                expected: 4,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                GeneticCode::from_id(test_case.id)
+                    .unwrap()
+                    .codon_count(test_case.amino_acid),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
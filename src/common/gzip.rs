@@ -0,0 +1,95 @@
+//! Transparent gzip compression
+//!
+//! Inputs are sniffed for the gzip magic bytes and decompressed on the fly.  Every member of a
+//! multi-member stream is read, so BGZF files (as written by `bgzip`) are decompressed in full.
+//!
+use crate::errors::Result;
+use std::io::BufRead;
+
+/// Magic bytes at the start of every gzip member
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// File extension of gzip compressed files
+pub const EXTENSION: &str = ".gz";
+
+/// Return a reader that decompresses the input if it is gzip compressed
+///
+/// Input that does not start with the gzip magic bytes is passed through unchanged.
+///
+pub fn decode<R>(reader: R) -> Result<Box<dyn std::io::Read>>
+where
+    R: std::io::Read + 'static,
+{
+    let mut reader = std::io::BufReader::new(reader);
+    if is_gzip(reader.fill_buf()?) {
+        return Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader)));
+    }
+    Ok(Box::new(reader))
+}
+
+/// Return a writer that compresses everything written to it
+///
+/// The stream is only complete once the encoder is finished, either explicitly with `finish` or
+/// when it is dropped.
+///
+pub fn encode<W>(writer: W) -> flate2::write::GzEncoder<W>
+where
+    W: std::io::Write,
+{
+    flate2::write::GzEncoder::new(writer, flate2::Compression::default())
+}
+
+/// Check to see if the bytes start with the gzip magic bytes
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Check to see if the path names a gzip compressed file
+pub fn is_gzip_path(path: &str) -> bool {
+    path.ends_with(EXTENSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = encode(Vec::new());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decode() {
+        struct TestCase<'a> {
+            name: &'a str,
+            data: Vec<u8>,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should pass plain text through",
+                data: b">name\nACGT\n".to_vec(),
+                expected: ">name\nACGT\n",
+            },
+            TestCase {
+                name: "Should decompress gzip",
+                data: compress(b">name\nACGT\n"),
+                expected: ">name\nACGT\n",
+            },
+            TestCase {
+                name: "Should decompress every member of a multi-member stream",
+                data: [compress(b">name\nAC"), compress(b"GT\n")].concat(),
+                expected: ">name\nACGT\n",
+            },
+        ];
+        for test_case in test_cases {
+            let mut actual = String::new();
+            decode(std::io::Cursor::new(test_case.data))
+                .unwrap()
+                .read_to_string(&mut actual)
+                .unwrap();
+            assert_eq!(test_case.expected, actual, "{}", test_case.name);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Parsing of fasta headers
+//!
+//! The name of a record is parsed into an identifier, which understands the UniProt style
+//! `db|accession|entry` and the NCBI style `db|value|db|value` names.  The description is parsed
+//! into a title followed by attributes, which may be given UniProt style as `KEY=value` or NCBI
+//! style as `[key=value]`.  For example:
+//!
+//! ```text
+//! >sp|P07204|TRBM_HUMAN Thrombomodulin OS=Homo sapiens OX=9606 GN=THBD PE=1 SV=2
+//! ```
+//!
+//! has the accession P07204, the title Thrombomodulin, and the attributes OS, OX, GN, PE and SV.
+//!
+const ID_DELIMITER: char = '|';
+const ATTRIBUTE_DELIMITER: char = '=';
+const UNIPROT_DATABASES: [&str; 2] = ["sp", "tr"];
+const ORGANISM_KEYS: [&str; 2] = ["OS", "organism"];
+const GENE_KEYS: [&str; 2] = ["GN", "gene"];
+
+/// Identifier parsed from the name of a record
+#[derive(Debug, Clone, PartialEq)]
+pub enum Identifier {
+    /// UniProt name such as `sp|P07204|TRBM_HUMAN`
+    UniProt {
+        database: String,
+        accession: String,
+        entry: String,
+    },
+    /// NCBI name made of database and value pairs such as `gi|12345|ref|NP_000001.1|`
+    Ncbi(Vec<(String, String)>),
+    /// Name without any structure
+    Plain(String),
+}
+
+impl Identifier {
+    /// Parse the name of a record
+    pub fn parse(name: &str) -> Self {
+        let fields: Vec<&str> = name.split(ID_DELIMITER).collect();
+        match fields.as_slice() {
+            [database, accession, entry] if UNIPROT_DATABASES.contains(database) => Self::UniProt {
+                database: database.to_string(),
+                accession: accession.to_string(),
+                entry: entry.to_string(),
+            },
+            [_] => Self::Plain(name.into()),
+            _ => Self::Ncbi(
+                fields
+                    .chunks(2)
+                    .filter(|pair| !pair[0].is_empty())
+                    .map(|pair| (pair[0].to_string(), pair.get(1).unwrap_or(&"").to_string()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Return the accession of the record
+    ///
+    /// For NCBI names, the value of the last database other than `gi` is preferred since it is
+    /// usually the versioned accession.
+    ///
+    pub fn accession(&self) -> &str {
+        match self {
+            Self::UniProt { accession, .. } => accession,
+            Self::Ncbi(pairs) => pairs
+                .iter()
+                .rev()
+                .find(|(database, value)| database != "gi" && !value.is_empty())
+                .or_else(|| pairs.first())
+                .map_or("", |(_, value)| value),
+            Self::Plain(name) => name,
+        }
+    }
+}
+
+/// Header of a fasta record
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub identifier: Identifier,
+    /// Free text of the description outside of the attributes
+    pub title: String,
+    /// Attributes of the description in the order they are given
+    pub attributes: Vec<(String, String)>,
+}
+
+impl Header {
+    /// Parse the name and description of a record
+    pub fn parse(name: &str, description: &str) -> Self {
+        let (title, attributes) = parse_description(description);
+        Self {
+            identifier: Identifier::parse(name),
+            title,
+            attributes,
+        }
+    }
+
+    /// Return the accession of the record
+    pub fn accession(&self) -> &str {
+        self.identifier.accession()
+    }
+
+    /// Return the value of an attribute
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Return the organism, given as OS or organism
+    pub fn organism(&self) -> Option<&str> {
+        ORGANISM_KEYS.iter().find_map(|key| self.get(key))
+    }
+
+    /// Return the gene name, given as GN or gene
+    pub fn gene(&self) -> Option<&str> {
+        GENE_KEYS.iter().find_map(|key| self.get(key))
+    }
+}
+
+/// Parse a description into the title and the attributes
+///
+/// A `KEY=value` attribute runs until the next word that is itself a `KEY=`, so values may contain
+/// spaces as in `OS=Homo sapiens`.  A `[key=value]` attribute runs until the closing bracket.
+///
+fn parse_description(description: &str) -> (String, Vec<(String, String)>) {
+    let mut title = Vec::new();
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    let mut words = description.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        if let Some(bracketed) = word.strip_prefix('[') {
+            let mut attribute = bracketed.to_string();
+            while !attribute.ends_with(']') {
+                match words.next() {
+                    Some(word) => {
+                        attribute.push(' ');
+                        attribute.push_str(word);
+                    }
+                    None => break,
+                }
+            }
+            if let Some((key, value)) = attribute
+                .trim_end_matches(']')
+                .split_once(ATTRIBUTE_DELIMITER)
+            {
+                attributes.push((key.into(), value.into()));
+                continue;
+            }
+            title.push(format!("[{}", attribute));
+            continue;
+        }
+        match split_attribute(word) {
+            Some((key, value)) => {
+                let mut value = value.to_string();
+                while let Some(next) = words.peek() {
+                    if split_attribute(next).is_some() || next.starts_with('[') {
+                        break;
+                    }
+                    value.push(' ');
+                    value.push_str(next);
+                    words.next();
+                }
+                attributes.push((key.into(), value));
+            }
+            None => title.push(word.to_string()),
+        }
+    }
+    (title.join(" "), attributes)
+}
+
+/// Split a word into a key and a value if it starts with `KEY=`
+fn split_attribute(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once(ATTRIBUTE_DELIMITER)?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fasta;
+
+    #[test]
+    fn test_identifier_parse() {
+        struct TestCase<'a> {
+            name: &'a str,
+            id: &'a str,
+            expected: Identifier,
+            accession: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should parse a UniProt name",
+                id: "sp|P07204|TRBM_HUMAN",
+                expected: Identifier::UniProt {
+                    database: "sp".into(),
+                    accession: "P07204".into(),
+                    entry: "TRBM_HUMAN".into(),
+                },
+                accession: "P07204",
+            },
+            TestCase {
+                name: "Should parse an NCBI name",
+                id: "gi|12345|ref|NP_000001.1|",
+                expected: Identifier::Ncbi(vec![
+                    ("gi".into(), "12345".into()),
+                    ("ref".into(), "NP_000001.1".into()),
+                ]),
+                accession: "NP_000001.1",
+            },
+            TestCase {
+                name: "Should keep a plain name",
+                id: "Rosalind_0498",
+                expected: Identifier::Plain("Rosalind_0498".into()),
+                accession: "Rosalind_0498",
+            },
+        ];
+        for test_case in test_cases {
+            let identifier = Identifier::parse(test_case.id);
+            assert_eq!(test_case.expected, identifier, "{}", test_case.name);
+            assert_eq!(
+                test_case.accession,
+                identifier.accession(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_description() {
+        struct TestCase<'a> {
+            name: &'a str,
+            description: &'a str,
+            expected: (&'a str, Vec<(&'a str, &'a str)>),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should parse UniProt attributes",
+                description: "Thrombomodulin OS=Homo sapiens OX=9606 GN=THBD PE=1 SV=2",
+                expected: (
+                    "Thrombomodulin",
+                    vec![
+                        ("OS", "Homo sapiens"),
+                        ("OX", "9606"),
+                        ("GN", "THBD"),
+                        ("PE", "1"),
+                        ("SV", "2"),
+                    ],
+                ),
+            },
+            TestCase {
+                name: "Should parse NCBI attributes",
+                description: "thrombomodulin [organism=Homo sapiens] [gene=THBD]",
+                expected: (
+                    "thrombomodulin",
+                    vec![("organism", "Homo sapiens"), ("gene", "THBD")],
+                ),
+            },
+            TestCase {
+                name: "Should keep a description without attributes as the title",
+                description: "chromosome 2, complete sequence",
+                expected: ("chromosome 2, complete sequence", vec![]),
+            },
+        ];
+        for test_case in test_cases {
+            let (title, attributes) = parse_description(test_case.description);
+            let expected_attributes: Vec<(String, String)> = test_case
+                .expected
+                .1
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            assert_eq!(
+                (test_case.expected.0.to_string(), expected_attributes),
+                (title, attributes),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_header() {
+        let record = fasta::Record {
+            name: "sp|P07204|TRBM_HUMAN".into(),
+            description: "Thrombomodulin OS=Homo sapiens OX=9606 GN=THBD PE=1 SV=2".into(),
+            sequence: String::new(),
+        };
+        let header = record.header();
+        assert_eq!(
+            ("P07204", Some("Homo sapiens"), Some("THBD")),
+            (header.accession(), header.organism(), header.gene()),
+            "{}",
+            "Should report the accession, organism and gene",
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Suffix arrays and longest common prefix arrays
+//!
+//! Suffix arrays are built in linear time with SA-IS (Nong, Zhang and Chan) and longest common
+//! prefix arrays with Kasai's algorithm.  A generalized suffix array indexes many sequences at
+//! once by joining them with separators that are unique and smaller than every symbol, so that no
+//! common prefix runs across the end of a sequence.
+//!
+use std::collections::VecDeque;
+
+/// Marker of an empty slot while inducing
+const NONE: usize = usize::MAX;
+
+/// Return the suffix array of the text, the starts of its suffixes in lexicographic order
+pub fn suffix_array(text: &[u8]) -> Vec<usize> {
+    let symbols: Vec<usize> = text.iter().map(|symbol| *symbol as usize).collect();
+    sa_is(&symbols, u8::MAX as usize)
+}
+
+/// Return the longest common prefix array of the text and its suffix array
+///
+/// Entry i is the length of the longest common prefix of the suffixes at ranks i - 1 and i, and
+/// entry 0 is 0.
+///
+pub fn lcp_array<T>(text: &[T], suffix_array: &[usize]) -> Vec<usize>
+where
+    T: PartialEq,
+{
+    let n = text.len();
+    let mut rank = vec![0; n];
+    for (i, start) in suffix_array.iter().enumerate() {
+        rank[*start] = i;
+    }
+    let mut lcp = vec![0; n];
+    let mut length = 0;
+    for i in 0..n {
+        if rank[i] == 0 {
+            length = 0;
+            continue;
+        }
+        let j = suffix_array[rank[i] - 1];
+        while i + length < n && j + length < n && text[i + length] == text[j + length] {
+            length += 1;
+        }
+        lcp[rank[i]] = length;
+        length = length.saturating_sub(1);
+    }
+    lcp
+}
+
+/// Suffix array over a collection of sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralizedSuffixArray {
+    /// Sequences joined by separators, where the separator after sequence k is k and symbols are
+    /// shifted above the separators
+    text: Vec<usize>,
+    /// Index of the sequence of every position of the text
+    sequences: Vec<usize>,
+    /// Start of every sequence in the text
+    starts: Vec<usize>,
+    suffix_array: Vec<usize>,
+    lcp: Vec<usize>,
+}
+
+impl GeneralizedSuffixArray {
+    /// Index the sequences
+    pub fn new<S>(sequences: &[S]) -> Self
+    where
+        S: AsRef<[u8]>,
+    {
+        let count = sequences.len();
+        let length = sequences.iter().map(|s| s.as_ref().len() + 1).sum();
+        let mut text = Vec::with_capacity(length);
+        let mut indices = Vec::with_capacity(length);
+        let mut starts = Vec::with_capacity(count);
+        for (k, sequence) in sequences.iter().enumerate() {
+            starts.push(text.len());
+            text.extend(
+                sequence
+                    .as_ref()
+                    .iter()
+                    .map(|symbol| *symbol as usize + count),
+            );
+            text.push(k);
+            indices.resize(text.len(), k);
+        }
+        let suffix_array = sa_is(&text, u8::MAX as usize + count);
+        let lcp = lcp_array(&text, &suffix_array);
+        Self {
+            text,
+            sequences: indices,
+            starts,
+            suffix_array,
+            lcp,
+        }
+    }
+
+    /// Returns the number of indexed sequences
+    pub fn count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Returns the suffix array of the joined sequences, whose first entries are the separators
+    pub fn suffix_array(&self) -> &[usize] {
+        &self.suffix_array
+    }
+
+    /// Returns the longest common prefix array of the joined sequences
+    pub fn lcp(&self) -> &[usize] {
+        &self.lcp
+    }
+
+    /// Return the index of the sequence and the offset in it of a position of the joined sequences
+    pub fn locate(&self, position: usize) -> (usize, usize) {
+        let sequence = self.sequences[position];
+        (sequence, position - self.starts[sequence])
+    }
+
+    /// Return a longest substring shared by all sequences as the index of a sequence, an offset in
+    /// it and a length, or None if there are no sequences
+    ///
+    /// Among the longest substrings, the lexicographically smallest is returned.  When nothing is
+    /// shared, as when a sequence is empty, the empty substring at the start of the first sequence
+    /// is returned.
+    ///
+    pub fn longest_common_substring(&self) -> Option<(usize, usize, usize)> {
+        let count = self.count();
+        match count {
+            0 => return None,
+            1 => return Some((0, 0, self.text.len() - 1)),
+            _ => (),
+        }
+        // Suffixes starting at separators sort first and are skipped
+        let ranks = count..self.suffix_array.len();
+        let mut covered = vec![0; count];
+        let mut distinct = 0;
+        // Ranks of the window whose common prefix lengths are increasing
+        let mut minimums: VecDeque<usize> = VecDeque::new();
+        let mut best: Option<(usize, usize)> = None;
+        let mut left = ranks.start;
+        for right in ranks {
+            let sequence = self.sequences[self.suffix_array[right]];
+            covered[sequence] += 1;
+            if covered[sequence] == 1 {
+                distinct += 1;
+            }
+            if right > left {
+                while minimums
+                    .back()
+                    .is_some_and(|rank| self.lcp[*rank] >= self.lcp[right])
+                {
+                    minimums.pop_back();
+                }
+                minimums.push_back(right);
+            }
+            while distinct == count {
+                let length = minimums.front().map(|rank| self.lcp[*rank]).unwrap_or(0);
+                if best.is_none_or(|(_, best)| length > best) {
+                    best = Some((self.suffix_array[right], length));
+                }
+                let sequence = self.sequences[self.suffix_array[left]];
+                covered[sequence] -= 1;
+                if covered[sequence] == 0 {
+                    distinct -= 1;
+                }
+                left += 1;
+                while minimums.front().is_some_and(|rank| *rank <= left) {
+                    minimums.pop_front();
+                }
+            }
+        }
+        // A sequence without suffixes leaves the window incomplete, and only the empty substring
+        // is shared
+        Some(best.map_or((0, 0, 0), |(position, length)| {
+            let (sequence, offset) = self.locate(position);
+            (sequence, offset, length)
+        }))
+    }
+}
+
+/// Return the suffix array of symbols from 0 to upper by SA-IS
+///
+/// Suffixes are classified as S-type if smaller than the next suffix and L-type otherwise, and
+/// the leftmost S-type suffixes of every run (LMS) are sorted first, recursively if needed.  The
+/// order of every other suffix is then induced from them.
+///
+fn sa_is(text: &[usize], upper: usize) -> Vec<usize> {
+    let n = text.len();
+    match n {
+        0 => return Vec::new(),
+        1 => return vec![0],
+        2 => {
+            return match text[0] < text[1] {
+                true => vec![0, 1],
+                false => vec![1, 0],
+            }
+        }
+        _ => (),
+    }
+    let mut is_s = vec![false; n];
+    for i in (0..n - 1).rev() {
+        is_s[i] = match text[i] == text[i + 1] {
+            true => is_s[i + 1],
+            false => text[i] < text[i + 1],
+        };
+    }
+    // Start of the L-type and of the S-type suffixes of every symbol bucket
+    let mut sum_l = vec![0; upper + 2];
+    let mut sum_s = vec![0; upper + 2];
+    for i in 0..n {
+        match is_s[i] {
+            false => sum_s[text[i]] += 1,
+            true => sum_l[text[i] + 1] += 1,
+        }
+    }
+    for i in 0..=upper {
+        sum_s[i] += sum_l[i];
+        sum_l[i + 1] += sum_s[i];
+    }
+    let is_lms = |i: usize| i > 0 && !is_s[i - 1] && is_s[i];
+    let lms: Vec<usize> = (1..n).filter(|i| is_lms(*i)).collect();
+    let mut lms_map = vec![NONE; n];
+    for (k, i) in lms.iter().enumerate() {
+        lms_map[*i] = k;
+    }
+    let mut sa = vec![NONE; n];
+    let induce = |sa: &mut Vec<usize>, lms: &[usize]| {
+        sa.fill(NONE);
+        let mut buckets = sum_s.clone();
+        for i in lms {
+            sa[buckets[text[*i]]] = *i;
+            buckets[text[*i]] += 1;
+        }
+        let mut buckets = sum_l.clone();
+        sa[buckets[text[n - 1]]] = n - 1;
+        buckets[text[n - 1]] += 1;
+        for k in 0..n {
+            let i = sa[k];
+            if i != NONE && i >= 1 && !is_s[i - 1] {
+                sa[buckets[text[i - 1]]] = i - 1;
+                buckets[text[i - 1]] += 1;
+            }
+        }
+        let mut buckets = sum_l.clone();
+        for k in (0..n).rev() {
+            let i = sa[k];
+            if i != NONE && i >= 1 && is_s[i - 1] {
+                buckets[text[i - 1] + 1] -= 1;
+                sa[buckets[text[i - 1] + 1]] = i - 1;
+            }
+        }
+    };
+    induce(&mut sa, &lms);
+    let m = lms.len();
+    if m > 0 {
+        let mut sorted_lms: Vec<usize> = sa
+            .iter()
+            .copied()
+            .filter(|i| *i != NONE && lms_map[*i] != NONE)
+            .collect();
+        // Name the LMS substrings by rank, giving equal substrings the same name
+        let mut reduced = vec![0; m];
+        let mut name = 0;
+        for k in 1..m {
+            let (mut l, mut r) = (sorted_lms[k - 1], sorted_lms[k]);
+            let end_l = lms.get(lms_map[l] + 1).copied().unwrap_or(n);
+            let end_r = lms.get(lms_map[r] + 1).copied().unwrap_or(n);
+            let mut same = end_l - l == end_r - r;
+            if same {
+                while l < end_l && text[l] == text[r] {
+                    l += 1;
+                    r += 1;
+                }
+                same = l < n && text[l] == text[r];
+            }
+            if !same {
+                name += 1;
+            }
+            reduced[lms_map[sorted_lms[k]]] = name;
+        }
+        let reduced_sa = sa_is(&reduced, name);
+        for (k, rank) in reduced_sa.into_iter().enumerate() {
+            sorted_lms[k] = lms[rank];
+        }
+        induce(&mut sa, &sorted_lms);
+    }
+    sa
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the suffix array by sorting the suffixes
+    fn naive_suffix_array(text: &[u8]) -> Vec<usize> {
+        let mut suffixes: Vec<usize> = (0..text.len()).collect();
+        suffixes.sort_by_key(|i| &text[*i..]);
+        suffixes
+    }
+
+    #[test]
+    fn test_suffix_array() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            expected: Vec<usize>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should sort the suffixes of banana",
+                text: "banana",
+                expected: vec![5, 3, 1, 0, 4, 2],
+            },
+            TestCase {
+                name: "Should sort the suffixes of a repeated symbol",
+                text: "AAAA",
+                expected: vec![3, 2, 1, 0],
+            },
+            TestCase {
+                name: "Should sort an empty text",
+                text: "",
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                suffix_array(test_case.text.as_bytes()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_suffix_array_matches_sorting() {
+        // Every string over a small alphabet up to a length exercises the recursion and ties
+        for length in 0..=8u32 {
+            for code in 0..3usize.pow(length) {
+                let text: Vec<u8> = (0..length)
+                    .map(|i| b"ACG"[code / 3usize.pow(i) % 3])
+                    .collect();
+                assert_eq!(
+                    naive_suffix_array(&text),
+                    suffix_array(&text),
+                    "Should match sorting for {}",
+                    String::from_utf8_lossy(&text)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lcp_array() {
+        let text = b"banana";
+        assert_eq!(
+            vec![0, 1, 3, 0, 0, 2],
+            lcp_array(text, &suffix_array(text)),
+            "{}",
+            "Should find the common prefixes of neighbouring suffixes"
+        );
+    }
+
+    #[test]
+    fn test_longest_common_substring() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            expected: Option<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find the smallest longest common substring",
+                sequences: vec!["GATTACA", "TAGACCA", "ATACA"],
+                expected: Some("AC"),
+            },
+            TestCase {
+                name: "Should not run across the ends of sequences",
+                sequences: vec!["ACGT", "GTAC"],
+                expected: Some("AC"),
+            },
+            TestCase {
+                name: "Should find a whole sequence",
+                sequences: vec!["CGTA", "ACGTAC", "TTCGTATT"],
+                expected: Some("CGTA"),
+            },
+            TestCase {
+                name: "Should find nothing shared",
+                sequences: vec!["AAAA", "CCCC"],
+                expected: Some(""),
+            },
+            TestCase {
+                name: "Should return a single sequence",
+                sequences: vec!["ACGT"],
+                expected: Some("ACGT"),
+            },
+            TestCase {
+                name: "Should find the empty substring when a sequence is empty",
+                sequences: vec!["ACGT", "", "ACGT"],
+                expected: Some(""),
+            },
+            TestCase {
+                name: "Should return an empty single sequence",
+                sequences: vec![""],
+                expected: Some(""),
+            },
+            TestCase {
+                name: "Should return None without sequences",
+                sequences: vec![],
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            let index = GeneralizedSuffixArray::new(&test_case.sequences);
+            assert_eq!(
+                test_case.expected,
+                index
+                    .longest_common_substring()
+                    .map(|(k, offset, length)| &test_case.sequences[k][offset..offset + length]),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Sources of problem input
+use crate::common::{self, fasta, fastq};
+use crate::errors::{Error, Result};
+use std::io::BufRead;
+
+/// Path that reads the input from stdin
+pub const STDIN_PATH: &str = "-";
+pub const INPUT_ARG: &str = "input";
+
+/// Input to a problem
+///
+/// The input can either be read from a file, from stdin (by passing `-` as the file), or given
+/// inline with `--input "<text>"`.
+///
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    File(String),
+    Stdin,
+    Text(String),
+}
+
+impl Input {
+    /// Construct the input from the subcommand arguments
+    pub fn from_matches(matches: &clap::ArgMatches) -> Result<Self> {
+        if let Some(text) = matches.value_of(INPUT_ARG) {
+            return Ok(Self::Text(text.to_owned()));
+        }
+        match matches.value_of(common::FILE_ARG) {
+            Some(STDIN_PATH) => Ok(Self::Stdin),
+            Some(path) => Ok(Self::File(path.to_owned())),
+            None => Err(common::argument_err()),
+        }
+    }
+
+    /// Name of the input used when reporting errors
+    pub fn name(&self) -> &str {
+        match self {
+            Self::File(path) => path,
+            Self::Stdin => "<stdin>",
+            Self::Text(_) => "<input>",
+        }
+    }
+
+    /// Return a reader over the input
+    ///
+    /// Files and stdin are decompressed on the fly if they are gzip compressed.
+    ///
+    pub fn reader(&self) -> Result<Box<dyn std::io::Read>> {
+        match self {
+            Self::File(path) => common::gzip::decode(std::fs::File::open(path)?),
+            Self::Stdin => common::gzip::decode(std::io::stdin()),
+            Self::Text(text) => Ok(Box::new(std::io::Cursor::new(text.clone().into_bytes()))),
+        }
+    }
+
+    /// Read the entire input into a string
+    pub fn read_to_string(&self) -> Result<String> {
+        let mut string = String::new();
+        self.reader()?.read_to_string(&mut string)?;
+        Ok(string)
+    }
+
+    /// Call a function with the name and sequence of every fasta or fastq record of the input
+    ///
+    /// The input is read as fastq if it starts with '@', and as fasta otherwise.  Reading stops at
+    /// the first error returned by the function.
+    ///
+    pub fn for_each_sequence<F>(&self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str, &[u8]) -> Result<()>,
+    {
+        let mut reader =
+            std::io::BufReader::new(self.reader().map_err(|e| e.in_file(self.name()))?);
+        if reader
+            .fill_buf()
+            .map_err(|e| Error::from(e).in_file(self.name()))?
+            .starts_with(b"@")
+        {
+            for record in fastq::Reader::new(reader).iter() {
+                let record = record.map_err(|e| e.in_file(self.name()))?;
+                f(&record.name, record.sequence.as_bytes())?;
+            }
+            return Ok(());
+        }
+        let mut reader = fasta::Reader::new(reader);
+        while let Some(record) = reader.next_record() {
+            let record = record.map_err(|e| e.in_file(self.name()))?;
+            f(&String::from_utf8_lossy(&record.name), &record.sequence)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_from_matches() {
+        struct TestCase<'a> {
+            name: &'a str,
+            args: &'a [&'a str],
+            expected: Input,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should read from a file",
+                args: &["abc", "input.txt"],
+                expected: Input::File("input.txt".to_owned()),
+            },
+            TestCase {
+                name: "Should read from stdin",
+                args: &["abc", "-"],
+                expected: Input::Stdin,
+            },
+            TestCase {
+                name: "Should read inline text",
+                args: &["abc", "--input", "5 3"],
+                expected: Input::Text("5 3".to_owned()),
+            },
+        ];
+        for test_case in test_cases {
+            let matches = common::subcommand_file("abc").get_matches_from(test_case.args);
+            assert_eq!(
+                Ok(test_case.expected),
+                Input::from_matches(&matches),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_read_to_string() {
+        let input = Input::Text("ACGT\n".to_owned());
+        assert_eq!(
+            Ok("ACGT\n".to_owned()),
+            input.read_to_string(),
+            "{}",
+            "Should read inline text"
+        );
+    }
+
+    #[test]
+    fn test_input_for_each_sequence() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: &'a str,
+            expected: Vec<(String, String)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should read fasta records",
+                input: ">a desc\nAC\nGT\n>b\nTT\n",
+                expected: vec![
+                    ("a".to_owned(), "ACGT".to_owned()),
+                    ("b".to_owned(), "TT".to_owned()),
+                ],
+            },
+            TestCase {
+                name: "Should read fastq records",
+                input: "@a\nACGT\n+\nIIII\n@b\nTT\n+\nII\n",
+                expected: vec![
+                    ("a".to_owned(), "ACGT".to_owned()),
+                    ("b".to_owned(), "TT".to_owned()),
+                ],
+            },
+            TestCase {
+                name: "Should read nothing from empty input",
+                input: "",
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let mut sequences = Vec::new();
+            Input::Text(test_case.input.to_owned())
+                .for_each_sequence(|name, sequence| {
+                    sequences.push((
+                        name.to_owned(),
+                        String::from_utf8_lossy(sequence).into_owned(),
+                    ));
+                    Ok(())
+                })
+                .unwrap();
+            assert_eq!(test_case.expected, sequences, "{}", test_case.name);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Exact string matching
+//!
+//! The Knuth-Morris-Pratt failure array gives, for every prefix of a pattern, the longest border
+//! to fall back to on a mismatch.  An Aho-Corasick automaton generalizes it to many patterns at
+//! once: the patterns form a trie whose failure links point to the longest proper suffix of each
+//! state that is also in the trie, so every occurrence of every pattern is found in one pass.
+//!
+use std::collections::{BTreeMap, VecDeque};
+
+/// Root state of the automaton
+const ROOT: usize = 0;
+
+/// Return the failure array of the text
+///
+/// Entry k is the length of the longest proper prefix of the text that is also a suffix of its
+/// first k + 1 symbols.
+///
+pub fn failure_array(text: &[u8]) -> Vec<usize> {
+    let mut failure = vec![0; text.len()];
+    let mut length = 0;
+    for k in 1..text.len() {
+        while length > 0 && text[k] != text[length] {
+            length = failure[length - 1];
+        }
+        if text[k] == text[length] {
+            length += 1;
+        }
+        failure[k] = length;
+    }
+    failure
+}
+
+/// Occurrence of a pattern in a text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern
+    pub pattern: usize,
+    /// 0-based start in the text
+    pub start: usize,
+    /// 0-based end in the text, exclusive
+    pub end: usize,
+}
+
+/// State of the automaton
+#[derive(Debug, Clone, Default)]
+struct State {
+    children: BTreeMap<u8, usize>,
+    /// Longest proper suffix of the state that is also a state
+    failure: usize,
+    /// Nearest state along the failure links where a pattern ends
+    dictionary: Option<usize>,
+    /// Patterns ending at the state
+    patterns: Vec<usize>,
+    depth: usize,
+}
+
+/// Aho-Corasick automaton over a collection of patterns
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    states: Vec<State>,
+}
+
+impl AhoCorasick {
+    /// Build the automaton of the patterns
+    ///
+    /// Empty patterns never match.
+    ///
+    pub fn new<S>(patterns: &[S]) -> Self
+    where
+        S: AsRef<[u8]>,
+    {
+        let mut states = vec![State::default()];
+        for (i, pattern) in patterns.iter().enumerate() {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = ROOT;
+            for symbol in pattern {
+                state = match states[state].children.get(symbol) {
+                    Some(child) => *child,
+                    None => {
+                        let child = states.len();
+                        states.push(State {
+                            depth: states[state].depth + 1,
+                            ..State::default()
+                        });
+                        states[state].children.insert(*symbol, child);
+                        child
+                    }
+                };
+            }
+            states[state].patterns.push(i);
+        }
+        let mut automaton = Self { states };
+        automaton.link();
+        automaton
+    }
+
+    /// Return every occurrence of every pattern, ordered by end and then by decreasing length
+    ///
+    /// Occurrences may overlap.
+    ///
+    pub fn find_overlapping(&self, text: &[u8]) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut state = ROOT;
+        for (i, symbol) in text.iter().enumerate() {
+            state = self.next(state, *symbol);
+            let mut output = Some(state);
+            while let Some(current) = output {
+                let current = &self.states[current];
+                for pattern in &current.patterns {
+                    matches.push(Match {
+                        pattern: *pattern,
+                        start: i + 1 - current.depth,
+                        end: i + 1,
+                    });
+                }
+                output = current.dictionary;
+            }
+        }
+        matches
+    }
+
+    /// Return the leftmost-longest occurrences that do not overlap, in order
+    ///
+    /// Scanning from the left, the longest occurrence starting earliest is kept and occurrences
+    /// overlapping it are skipped.  Among identical patterns the first is reported.
+    ///
+    pub fn find(&self, text: &[u8]) -> Vec<Match> {
+        let mut candidates = self.find_overlapping(text);
+        candidates.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end), m.pattern));
+        let mut matches: Vec<Match> = Vec::new();
+        for candidate in candidates {
+            if matches
+                .last()
+                .is_none_or(|last| candidate.start >= last.end)
+            {
+                matches.push(candidate);
+            }
+        }
+        matches
+    }
+
+    /// Set the failure and dictionary links, breadth first
+    fn link(&mut self) {
+        let mut queue: VecDeque<usize> = self.states[ROOT].children.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let children: Vec<(u8, usize)> = self.states[state]
+                .children
+                .iter()
+                .map(|(symbol, child)| (*symbol, *child))
+                .collect();
+            for (symbol, child) in children {
+                let failure = self.next(self.states[state].failure, symbol);
+                self.states[child].failure = failure;
+                self.states[child].dictionary = if self.states[failure].patterns.is_empty() {
+                    self.states[failure].dictionary
+                } else {
+                    Some(failure)
+                };
+                queue.push_back(child);
+            }
+        }
+    }
+
+    /// Return the state reached from the state on the symbol
+    fn next(&self, state: usize, symbol: u8) -> usize {
+        let mut state = state;
+        loop {
+            if let Some(child) = self.states[state].children.get(&symbol) {
+                return *child;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.states[state].failure;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_array() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            expected: Vec<usize>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should compute the failure array",
+                text: "CAGCATGGTATCACAGCAGAG",
+                expected: vec![
+                    0, 0, 0, 1, 2, 0, 0, 0, 0, 0, 0, 1, 2, 1, 2, 3, 4, 5, 3, 0, 0,
+                ],
+            },
+            TestCase {
+                name: "Should compute the failure array of a run",
+                text: "AAAA",
+                expected: vec![0, 1, 2, 3],
+            },
+            TestCase {
+                name: "Should compute nothing for an empty text",
+                text: "",
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                failure_array(test_case.text.as_bytes()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_overlapping() {
+        struct TestCase<'a> {
+            name: &'a str,
+            patterns: Vec<&'a str>,
+            text: &'a str,
+            expected: Vec<(usize, usize)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find patterns that are suffixes of each other",
+                patterns: vec!["he", "she", "his", "hers"],
+                text: "ushers",
+                expected: vec![(1, 1), (0, 2), (3, 2)],
+            },
+            TestCase {
+                name: "Should find overlapping occurrences",
+                patterns: vec!["AA"],
+                text: "AAAA",
+                expected: vec![(0, 0), (0, 1), (0, 2)],
+            },
+            TestCase {
+                name: "Should report duplicate patterns",
+                patterns: vec!["CG", "CG", ""],
+                text: "ACGT",
+                expected: vec![(0, 1), (1, 1)],
+            },
+            TestCase {
+                name: "Should find nothing without patterns",
+                patterns: vec![],
+                text: "ACGT",
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let automaton = AhoCorasick::new(&test_case.patterns);
+            let matches: Vec<(usize, usize)> = automaton
+                .find_overlapping(test_case.text.as_bytes())
+                .iter()
+                .map(|m| (m.pattern, m.start))
+                .collect();
+            assert_eq!(test_case.expected, matches, "{}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        struct TestCase<'a> {
+            name: &'a str,
+            patterns: Vec<&'a str>,
+            text: &'a str,
+            expected: Vec<(usize, usize, usize)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should prefer the leftmost occurrence",
+                patterns: vec!["BCD", "ABC"],
+                text: "ABCDE",
+                expected: vec![(1, 0, 3)],
+            },
+            TestCase {
+                name: "Should prefer the longest occurrence at a start",
+                patterns: vec!["AB", "ABCD"],
+                text: "ABCDAB",
+                expected: vec![(1, 0, 4), (0, 4, 6)],
+            },
+            TestCase {
+                name: "Should skip overlapping occurrences of a pattern",
+                patterns: vec!["AA"],
+                text: "AAAAA",
+                expected: vec![(0, 0, 2), (0, 2, 4)],
+            },
+        ];
+        for test_case in test_cases {
+            let automaton = AhoCorasick::new(&test_case.patterns);
+            let matches: Vec<(usize, usize, usize)> = automaton
+                .find(test_case.text.as_bytes())
+                .iter()
+                .map(|m| (m.pattern, m.start, m.end))
+                .collect();
+            assert_eq!(test_case.expected, matches, "{}", test_case.name);
+        }
+    }
+}
@@ -1,32 +1,288 @@
-use crate::errors::{Error, ErrorKind, Result};
-use std::io::Read;
+//! Building blocks shared across problems
+use crate::errors::{Error, ErrorKind, Location, Result};
 
+pub mod align;
 pub mod dna;
+pub mod faidx;
 pub mod fasta;
+pub mod fastq;
+pub mod fmindex;
+pub mod genetic_code;
+pub mod gzip;
+pub mod header;
+pub mod index;
+pub mod input;
+pub mod matching;
+pub mod msa;
+pub mod orf;
+pub mod profile;
+pub mod seq;
+pub mod stats;
 
 pub const FILE_ARG: &str = "file";
+pub const FASTA_OUT_ARG: &str = "fasta-out";
+pub const LINE_WIDTH_ARG: &str = "line-width";
+pub const TABLE_ARG: &str = "table";
+pub const PSEUDOCOUNT_ARG: &str = "pseudocount";
 
 /// Load simple input
 ///
-/// Load the entire file as a string and trim off leading and trailing white spaces.
+/// Load the entire input as a string and trim off leading and trailing white spaces.
 ///
-pub fn load_simple(path: &str) -> Result<String> {
-    let mut string = String::new();
-    std::fs::File::open(path)?.read_to_string(&mut string)?;
+pub fn load_simple(input: &input::Input) -> Result<String> {
+    let string = input
+        .read_to_string()
+        .map_err(|e| e.in_file(input.name()))?;
     Ok(string.trim().into())
 }
 
+/// Parse a token of the input
+///
+/// The token must be borrowed from the input so that the location of the token can be reported
+/// if it fails to parse.  A location within the token, such as the position of an invalid symbol
+/// in a sequence, is shifted to the matching column of the input.
+///
+pub fn parse_token<T>(input: &str, token: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    Error: From<T::Err>,
+{
+    token.parse::<T>().map_err(|e| {
+        let error = Error::from(e);
+        match Location::of_token(input, token) {
+            Some(mut location) => {
+                if let Some(inner) = error.location().filter(|inner| inner.line == 1) {
+                    location.column += inner.column - 1;
+                }
+                error.with_location(location)
+            }
+            None => error,
+        }
+    })
+}
+
+/// Return a parse error for input that does not have the expected shape
+pub fn invalid_input(message: &str) -> Error {
+    Error::new(ErrorKind::Parse, &format!("invalid input: {}", message))
+}
+
 /// Return a generic clap command with a file input
 ///
 /// The generated command looks like the following:
-///     SUBCOMMAND <file>
-/// where file is the path to the input txt from Rosalind.
+///
+/// ```text
+/// SUBCOMMAND <file>
+/// SUBCOMMAND --input <text>
+/// ```
+///
+/// where file is the path to the input txt from Rosalind, or `-` to read from stdin.
 ///
 pub fn subcommand_file(subcommand: &str) -> clap::Command<'static> {
-    clap::Command::new(subcommand).arg(clap::Arg::new(FILE_ARG).required(true))
+    clap::Command::new(subcommand)
+        .arg(
+            clap::Arg::new(FILE_ARG)
+                .required_unless_present(input::INPUT_ARG)
+                .conflicts_with(input::INPUT_ARG)
+                .help("Path to the input file, or - to read from stdin"),
+        )
+        .arg(
+            clap::Arg::new(input::INPUT_ARG)
+                .long(input::INPUT_ARG)
+                .takes_value(true)
+                .value_name("text")
+                .help("Input given inline"),
+        )
+}
+
+/// Return a clap command with a file input whose answer can be written as fasta
+///
+/// The generated command looks like the following:
+///
+/// ```text
+/// SUBCOMMAND <file> [--fasta-out[=<path>] [--line-width <width>]]
+/// ```
+///
+/// where the fasta is written to stdout unless a path is given.  Paths ending in `.gz` are gzip
+/// compressed.
+///
+pub fn subcommand_fasta_out(subcommand: &str) -> clap::Command<'static> {
+    subcommand_file(subcommand)
+        .arg(
+            clap::Arg::new(FASTA_OUT_ARG)
+                .long(FASTA_OUT_ARG)
+                .takes_value(true)
+                .min_values(0)
+                .require_equals(true)
+                .value_name("path")
+                .help("Write the answer as fasta records, to stdout or to a .fa or .fa.gz file"),
+        )
+        .arg(
+            clap::Arg::new(LINE_WIDTH_ARG)
+                .long(LINE_WIDTH_ARG)
+                .takes_value(true)
+                .value_name("width")
+                .requires(FASTA_OUT_ARG)
+                .help("Number of sequence characters per fasta line, or 0 to not wrap"),
+        )
+}
+
+/// Add a genetic code option to a clap command
+///
+/// The option looks like `--table <id>` where id is an NCBI translation table, and defaults to the
+/// standard code.
+///
+pub fn with_table(command: clap::Command<'static>) -> clap::Command<'static> {
+    command.arg(
+        clap::Arg::new(TABLE_ARG)
+            .long(TABLE_ARG)
+            .takes_value(true)
+            .value_name("id")
+            .help("NCBI genetic code table used for translation [default: 1]"),
+    )
+}
+
+/// Return the genetic code selected with `--table`
+pub fn genetic_code(matches: &clap::ArgMatches) -> Result<&'static genetic_code::GeneticCode> {
+    match matches.try_get_one::<String>(TABLE_ARG) {
+        Ok(Some(id)) => genetic_code::GeneticCode::from_id(parse_token(id, id)?),
+        _ => Ok(genetic_code::GeneticCode::standard()),
+    }
+}
+
+/// Add a pseudocount option to a clap command
+///
+/// The option looks like `--pseudocount <count>` and is added to every count of position weight
+/// matrices.
+///
+pub fn with_pseudocount(command: clap::Command<'static>) -> clap::Command<'static> {
+    command.arg(
+        clap::Arg::new(PSEUDOCOUNT_ARG)
+            .long(PSEUDOCOUNT_ARG)
+            .takes_value(true)
+            .value_name("count")
+            .help("Pseudocount added to every count of the position weight matrix [default: 0.5]"),
+    )
+}
+
+/// Return the pseudocount selected with `--pseudocount`
+///
+/// The pseudocount must be positive, or symbols missing from a column would score minus infinity.
+///
+pub fn pseudocount(matches: &clap::ArgMatches) -> Result<f64> {
+    let count = match matches.try_get_one::<String>(PSEUDOCOUNT_ARG) {
+        Ok(Some(count)) => parse_token(count, count)?,
+        _ => profile::PSEUDOCOUNT,
+    };
+    if count > 0.0 {
+        Ok(count)
+    } else {
+        Err(Error::new(
+            ErrorKind::User,
+            &format!("{} must be positive, found {}", PSEUDOCOUNT_ARG, count),
+        ))
+    }
 }
 
 /// Return argument error
 pub fn argument_err() -> Error {
     Error::new(ErrorKind::User, &format!("{} argument required", FILE_ARG))
 }
+
+/// Load simple input as a single sequence
+///
+/// The location of an invalid symbol is reported against the input.
+///
+pub fn load_sequence<T>(input: &input::Input) -> Result<T>
+where
+    T: std::str::FromStr<Err = Error>,
+{
+    let string = load_simple(input)?;
+    parse_token(&string, &string)
+}
+
+/// Parse every line of simple input into a sequence
+///
+/// The location of an invalid symbol is reported against the input.
+///
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>>
+where
+    T: std::str::FromStr<Err = Error>,
+{
+    input
+        .lines()
+        .map(|line| parse_token(input, line.trim()))
+        .collect()
+}
+
+/// Load fasta input
+///
+/// Load every record in the fasta input.
+///
+pub fn load_fasta(input: &input::Input) -> Result<Vec<fasta::Record>> {
+    fasta::Reader::new(input.reader()?)
+        .iter()
+        .collect::<Result<Vec<fasta::Record>>>()
+        .map_err(|e| e.in_file(input.name()))
+}
+
+/// Load fasta input made of exactly two records and parse their sequences
+pub fn load_fasta_pair<T>(input: &input::Input) -> Result<(T, T)>
+where
+    T: std::str::FromStr<Err = Error>,
+{
+    let records = load_fasta(input)?;
+    match records.as_slice() {
+        [record1, record2] => Ok((record1.parse_sequence()?, record2.parse_sequence()?)),
+        _ => Err(invalid_input(&format!(
+            "expected two fasta records, found {}",
+            records.len()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudocount() {
+        struct TestCase<'a> {
+            name: &'a str,
+            args: &'a [&'a str],
+            expected: std::result::Result<f64, ErrorKind>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should default the pseudocount",
+                args: &["abc", "-"],
+                expected: Ok(profile::PSEUDOCOUNT),
+            },
+            TestCase {
+                name: "Should parse the pseudocount",
+                args: &["abc", "-", "--pseudocount", "0.25"],
+                expected: Ok(0.25),
+            },
+            TestCase {
+                name: "Should reject a zero pseudocount",
+                args: &["abc", "-", "--pseudocount", "0"],
+                expected: Err(ErrorKind::User),
+            },
+            TestCase {
+                name: "Should reject a negative pseudocount",
+                args: &["abc", "-", "--pseudocount", "-1"],
+                expected: Err(ErrorKind::User),
+            },
+        ];
+        for test_case in test_cases {
+            let matches = with_pseudocount(subcommand_file("abc"))
+                .allow_hyphen_values(true)
+                .get_matches_from(test_case.args);
+            assert_eq!(
+                test_case.expected,
+                pseudocount(&matches).map_err(|e| *e.kind()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
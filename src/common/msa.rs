@@ -0,0 +1,530 @@
+//! Progressive multiple sequence alignment
+//!
+//! Sequences are joined in the order of a guide tree built by UPGMA from the pairwise distances of
+//! their global alignments.  Every join aligns the profiles of two groups of aligned sequences
+//! with Gotoh's algorithm, scoring a pair of columns by the sum of the scores of all pairs of
+//! symbols, where a symbol against a gap costs the gap extension penalty and two gaps score 0.
+//!
+use crate::common::align::{
+    self, Gap, Matrix, Mode, Path, Scores, Scoring, Step, Substitution, GAP,
+};
+use crate::common::input::Input;
+use crate::common::{self, fasta};
+use crate::errors::Result;
+
+/// Default scoring of DNA alignments
+pub const DNA: Scoring = Scoring {
+    substitution: Substitution::DNA,
+    gap: Gap::Affine { open: 3, extend: 1 },
+};
+/// Default scoring of protein alignments
+pub const PROTEIN: Scoring = Scoring {
+    substitution: Substitution::Matrix(&Matrix::BLOSUM62),
+    gap: Gap::Affine {
+        open: 11,
+        extend: 1,
+    },
+};
+/// First line of a Clustal file
+pub const CLUSTAL_HEADER: &str = "CLUSTAL W multiple sequence alignment";
+/// Number of columns in every block of a Clustal file
+const CLUSTAL_WIDTH: usize = 60;
+
+/// Binary tree giving the order in which sequences are aligned
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuideTree {
+    /// Index of a sequence
+    Leaf(usize),
+    /// Alignment of the sequences of two subtrees
+    Join(Box<GuideTree>, Box<GuideTree>),
+}
+
+impl GuideTree {
+    /// Build a guide tree by UPGMA, returning None if there are no sequences
+    ///
+    /// The two closest clusters are joined until one is left, and the distance to a joined
+    /// cluster is the mean distance to its sequences.  Ties are broken by the order of the
+    /// sequences.
+    ///
+    pub fn upgma(distances: &[Vec<f64>]) -> Option<Self> {
+        let mut clusters: Vec<(Self, usize)> =
+            (0..distances.len()).map(|i| (Self::Leaf(i), 1)).collect();
+        let mut distances = distances.to_vec();
+        while clusters.len() > 1 {
+            let mut closest = (0, 1);
+            for a in 0..clusters.len() {
+                for b in a + 1..clusters.len() {
+                    if distances[a][b] < distances[closest.0][closest.1] {
+                        closest = (a, b);
+                    }
+                }
+            }
+            let (a, b) = closest;
+            let (size_a, size_b) = (clusters[a].1, clusters[b].1);
+            let joined: Vec<f64> = distances[a]
+                .iter()
+                .zip(&distances[b])
+                .map(|(da, db)| {
+                    (da * size_a as f64 + db * size_b as f64) / (size_a + size_b) as f64
+                })
+                .collect();
+            for (k, distance) in joined.into_iter().enumerate() {
+                distances[a][k] = distance;
+                distances[k][a] = distance;
+            }
+            distances[a][a] = 0.0;
+            distances.remove(b);
+            for row in distances.iter_mut() {
+                row.remove(b);
+            }
+            let (tree_b, _) = clusters.remove(b);
+            let tree_a = std::mem::replace(&mut clusters[a].0, Self::Leaf(0));
+            clusters[a] = (
+                Self::Join(Box::new(tree_a), Box::new(tree_b)),
+                size_a + size_b,
+            );
+        }
+        clusters.pop().map(|(tree, _)| tree)
+    }
+
+    /// Align the sequences of the tree from the leaves up
+    fn profile(&self, sequences: &[&[u8]], scoring: &Scoring) -> Profile {
+        match self {
+            Self::Leaf(i) => Profile {
+                members: vec![*i],
+                rows: vec![sequences[*i].to_vec()],
+            },
+            Self::Join(a, b) => a
+                .profile(sequences, scoring)
+                .merge(b.profile(sequences, scoring), scoring),
+        }
+    }
+}
+
+/// Return the distance between every two sequences
+///
+/// The distance is the fraction of the columns of their optimal global alignment that are not
+/// identical symbols.
+///
+pub fn distances(sequences: &[&[u8]], scoring: &Scoring) -> Result<Vec<Vec<f64>>> {
+    let mut distances = vec![vec![0.0; sequences.len()]; sequences.len()];
+    for i in 0..sequences.len() {
+        for j in i + 1..sequences.len() {
+            let alignment = align::global(sequences[i], sequences[j], scoring)?;
+            let identical = alignment
+                .aligned1
+                .bytes()
+                .zip(alignment.aligned2.bytes())
+                .filter(|(a, b)| *a != GAP && a.eq_ignore_ascii_case(b))
+                .count();
+            let columns = alignment.aligned1.len();
+            let distance = match columns {
+                0 => 0.0,
+                _ => 1.0 - identical as f64 / columns as f64,
+            };
+            distances[i][j] = distance;
+            distances[j][i] = distance;
+        }
+    }
+    Ok(distances)
+}
+
+/// Align the sequences progressively, returning the aligned rows in the order of the sequences
+pub fn align(sequences: &[&[u8]], scoring: &Scoring) -> Result<Vec<String>> {
+    for sequence in sequences {
+        scoring.substitution.validate(sequence)?;
+    }
+    let tree = match GuideTree::upgma(&distances(sequences, scoring)?) {
+        Some(tree) => tree,
+        None => return Ok(Vec::new()),
+    };
+    let profile = tree.profile(sequences, scoring);
+    let mut rows: Vec<(usize, Vec<u8>)> = profile.members.into_iter().zip(profile.rows).collect();
+    rows.sort_by_key(|(member, _)| *member);
+    Ok(rows
+        .into_iter()
+        .map(|(_, row)| row.into_iter().map(char::from).collect())
+        .collect())
+}
+
+/// Write the multiple alignment of the fasta records of the input as aligned fasta, or as Clustal
+/// with `clustal`
+pub fn write_alignment<W>(
+    input: &Input,
+    scoring: &Scoring,
+    clustal: bool,
+    mut writer: W,
+) -> Result<()>
+where
+    W: std::io::Write,
+{
+    let records = common::load_fasta(input)?;
+    let sequences: Vec<&[u8]> = records
+        .iter()
+        .map(|record| record.sequence.as_bytes())
+        .collect();
+    let rows = align(&sequences, scoring).map_err(|e| e.in_file(input.name()))?;
+    if clustal {
+        let names: Vec<&str> = records.iter().map(|record| record.name.as_str()).collect();
+        writer.write_all(to_clustal(&names, &rows).as_bytes())?;
+        return Ok(());
+    }
+    let mut writer = fasta::Writer::new(writer);
+    for (record, row) in records.into_iter().zip(rows) {
+        writer.write(&fasta::Record {
+            sequence: row,
+            ..record
+        })?;
+    }
+    writer.flush()
+}
+
+/// Return the sum of the scores of the pairwise alignments induced by aligned rows
+///
+/// Columns where both rows have gaps are dropped from the pairwise alignments.
+///
+pub fn sum_of_pairs<S>(rows: &[S], scoring: &Scoring) -> i32
+where
+    S: AsRef<str>,
+{
+    let (open, extend) = scoring.gap.penalties();
+    let mut score = 0;
+    for (i, row1) in rows.iter().enumerate() {
+        for row2 in &rows[i + 1..] {
+            let mut previous = Step::Substitution;
+            for (a, b) in row1.as_ref().bytes().zip(row2.as_ref().bytes()) {
+                let step = match (a == GAP, b == GAP) {
+                    (true, true) => continue,
+                    (false, false) => {
+                        score += scoring.substitution.score(a, b).unwrap_or(0);
+                        Step::Substitution
+                    }
+                    (false, true) => Step::Deletion,
+                    (true, false) => Step::Insertion,
+                };
+                if step != Step::Substitution {
+                    score -= match step == previous {
+                        true => extend,
+                        false => open,
+                    };
+                }
+                previous = step;
+            }
+        }
+    }
+    score
+}
+
+/// Return the rows as a Clustal file, with a line marking the identical columns under every block
+pub fn to_clustal<S, T>(names: &[S], rows: &[T]) -> String
+where
+    S: AsRef<str>,
+    T: AsRef<str>,
+{
+    let width = names
+        .iter()
+        .map(|name| name.as_ref().len())
+        .max()
+        .unwrap_or_default()
+        + 4;
+    let length = rows
+        .first()
+        .map(|row| row.as_ref().len())
+        .unwrap_or_default();
+    let mut output = format!("{}\n", CLUSTAL_HEADER);
+    for start in (0..length).step_by(CLUSTAL_WIDTH) {
+        let end = length.min(start + CLUSTAL_WIDTH);
+        output.push('\n');
+        for (name, row) in names.iter().zip(rows) {
+            output.push_str(&format!(
+                "{:<width$}{}\n",
+                name.as_ref(),
+                &row.as_ref()[start..end],
+                width = width
+            ));
+        }
+        let marks: String = (start..end)
+            .map(|column| {
+                let mut symbols = rows.iter().map(|row| row.as_ref().as_bytes()[column]);
+                let first = symbols.next().unwrap_or(GAP);
+                match first != GAP && symbols.all(|symbol| symbol.eq_ignore_ascii_case(&first)) {
+                    true => '*',
+                    false => ' ',
+                }
+            })
+            .collect();
+        output.push_str(&format!("{:<width$}{}\n", "", marks, width = width));
+    }
+    output
+}
+
+/// Group of aligned sequences
+struct Profile {
+    /// Indices of the sequences of the rows
+    members: Vec<usize>,
+    /// Aligned sequences, all of the same length
+    rows: Vec<Vec<u8>>,
+}
+
+impl Profile {
+    /// Return the number of columns
+    fn len(&self) -> usize {
+        self.rows.first().map(Vec::len).unwrap_or_default()
+    }
+
+    /// Return the counts of every symbol of every column
+    fn columns(&self) -> Vec<Column> {
+        (0..self.len())
+            .map(|i| {
+                let mut column = Column {
+                    symbols: Vec::new(),
+                    residues: 0,
+                };
+                for row in &self.rows {
+                    let symbol = row[i].to_ascii_uppercase();
+                    if symbol == GAP {
+                        continue;
+                    }
+                    column.residues += 1;
+                    match column.symbols.iter_mut().find(|(s, _)| *s == symbol) {
+                        Some((_, count)) => *count += 1,
+                        None => column.symbols.push((symbol, 1)),
+                    }
+                }
+                column
+            })
+            .collect()
+    }
+
+    /// Align two profiles into one
+    fn merge(self, other: Self, scoring: &Scoring) -> Self {
+        let profiles = Profiles {
+            columns1: self.columns(),
+            columns2: other.columns(),
+            size1: self.rows.len() as i32,
+            size2: other.rows.len() as i32,
+            scoring,
+        };
+        let path = Path::find(&profiles, self.len(), other.len(), Mode::Global);
+        let expand = |rows: Vec<Vec<u8>>, gapped: Step| -> Vec<Vec<u8>> {
+            rows.into_iter()
+                .map(|row| {
+                    let mut symbols = row.into_iter();
+                    path.steps
+                        .iter()
+                        .map(|step| match *step == gapped {
+                            true => GAP,
+                            false => symbols.next().unwrap_or(GAP),
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+        let mut rows = expand(self.rows, Step::Insertion);
+        rows.extend(expand(other.rows, Step::Deletion));
+        let mut members = self.members;
+        members.extend(other.members);
+        Self { members, rows }
+    }
+}
+
+/// Counts of the symbols of a profile column
+struct Column {
+    /// Count of every symbol other than gaps
+    symbols: Vec<(u8, usize)>,
+    /// Number of symbols other than gaps
+    residues: usize,
+}
+
+/// Scores of aligning the columns of two profiles
+struct Profiles<'a> {
+    columns1: Vec<Column>,
+    columns2: Vec<Column>,
+    size1: i32,
+    size2: i32,
+    scoring: &'a Scoring,
+}
+
+impl Profiles<'_> {
+    /// Return the penalties of aligning a column with the given number of symbols, out of a
+    /// profile of the given size, against a new column of gaps in a profile of the other size
+    fn gap(&self, residues: usize, size: i32, other: i32) -> (i32, i32) {
+        let (open, extend) = self.scoring.gap.penalties();
+        let extension = extend * residues as i32 * other;
+        (extension + (open - extend) * size * other, extension)
+    }
+}
+
+impl Scores for Profiles<'_> {
+    fn substitution(&self, i: usize, j: usize) -> i32 {
+        let (column1, column2) = (&self.columns1[i], &self.columns2[j]);
+        let (_, extend) = self.scoring.gap.penalties();
+        let mut score = 0;
+        for (a, count1) in &column1.symbols {
+            for (b, count2) in &column2.symbols {
+                let pair = self.scoring.substitution.score(*a, *b).unwrap_or(0);
+                score += pair * (count1 * count2) as i32;
+            }
+        }
+        let gaps1 = self.size1 - column1.residues as i32;
+        let gaps2 = self.size2 - column2.residues as i32;
+        score - extend * (column1.residues as i32 * gaps2 + gaps1 * column2.residues as i32)
+    }
+
+    fn deletion(&self, i: usize) -> (i32, i32) {
+        self.gap(self.columns1[i].residues, self.size1, self.size2)
+    }
+
+    fn insertion(&self, j: usize) -> (i32, i32) {
+        self.gap(self.columns2[j].residues, self.size2, self.size1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgma() {
+        let distances = vec![
+            vec![0.0, 0.5, 0.1],
+            vec![0.5, 0.0, 0.4],
+            vec![0.1, 0.4, 0.0],
+        ];
+        assert_eq!(
+            Some(GuideTree::Join(
+                Box::new(GuideTree::Join(
+                    Box::new(GuideTree::Leaf(0)),
+                    Box::new(GuideTree::Leaf(2))
+                )),
+                Box::new(GuideTree::Leaf(1))
+            )),
+            GuideTree::upgma(&distances),
+            "{}",
+            "Should join the closest sequences first"
+        );
+    }
+
+    #[test]
+    fn test_align() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            scoring: Scoring,
+            expected: Vec<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should align sequences in their original order",
+                sequences: vec!["ATATCCG", "TCCG", "ATGTACTG", "ATGTCTG"],
+                scoring: Scoring::edit_distance(),
+                expected: vec!["ATAT-CCG", "-T---CCG", "ATGTACTG", "ATGT-CTG"],
+            },
+            TestCase {
+                name: "Should keep a single sequence",
+                sequences: vec!["ACGT"],
+                scoring: Scoring::edit_distance(),
+                expected: vec!["ACGT"],
+            },
+            TestCase {
+                name: "Should align nothing",
+                sequences: vec![],
+                scoring: Scoring::edit_distance(),
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let sequences: Vec<&[u8]> = test_case.sequences.iter().map(|s| s.as_bytes()).collect();
+            assert_eq!(
+                test_case.expected,
+                align(&sequences, &test_case.scoring).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_alignment() {
+        struct TestCase<'a> {
+            name: &'a str,
+            clustal: bool,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should write aligned fasta",
+                clustal: false,
+                expected: ">a x\nATATCC-G\n>b\n-T--CC-G\n>c\nATGTACTG\n",
+            },
+            TestCase {
+                name: "Should write Clustal",
+                clustal: true,
+                expected: "CLUSTAL W multiple sequence alignment\n\n\
+                           a    ATATCC-G\n\
+                           b    -T--CC-G\n\
+                           c    ATGTACTG\n\
+                           \x20     *   * *\n",
+            },
+        ];
+        let input = Input::Text(">a x\nATATCCG\n>b\nTCCG\n>c\nATGTACTG\n".to_owned());
+        for test_case in test_cases {
+            let mut output = Vec::new();
+            write_alignment(
+                &input,
+                &Scoring::edit_distance(),
+                test_case.clustal,
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(
+                test_case.expected,
+                String::from_utf8(output).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_sum_of_pairs() {
+        struct TestCase<'a> {
+            name: &'a str,
+            rows: Vec<&'a str>,
+            scoring: Scoring,
+            expected: i32,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should score every pair of rows",
+                rows: vec!["ATAT-CCG", "-T---CCG", "ATGTACTG", "ATGT-CTG"],
+                scoring: Scoring::edit_distance(),
+                expected: -18,
+            },
+            TestCase {
+                name: "Should open gaps once per run",
+                rows: vec!["ACGT", "A--T"],
+                scoring: Scoring::new(Substitution::DNA, Gap::Affine { open: 3, extend: 1 }),
+                expected: -2,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                sum_of_pairs(&test_case.rows, &test_case.scoring),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_clustal() {
+        assert_eq!(
+            "CLUSTAL W multiple sequence alignment\n\nseq1     AC-GT\nseq22    ACTGA\n         ** * \n",
+            to_clustal(&["seq1", "seq22"], &["AC-GT", "ACTGA"]),
+            "{}",
+            "Should write a block with the identical columns marked"
+        );
+    }
+}
@@ -0,0 +1,575 @@
+//! Open reading frames and six-frame translation
+//!
+//! Frames are numbered +1 to +3 on the forward strand and -1 to -3 on the reverse strand, where
+//! frame +n starts at base n of the sequence and frame -n starts at base n of its reverse
+//! complement.  Positions are 1-based, inclusive and given on the forward strand, so the start of
+//! an ORF is never greater than its end whichever strand it is on.
+//!
+use crate::common::genetic_code::{self, GeneticCode};
+use crate::common::input::Input;
+use crate::common::{dna, fasta};
+use crate::errors::Result;
+
+/// Symbol of a codon that cannot be translated, such as one with an ambiguous base
+pub const UNKNOWN: u8 = b'X';
+/// Header of the tab separated ORF table
+pub const TABLE_HEADER: &str =
+    "sequence\tframe\tstart\tend\tnucleotide_length\tprotein_length\tprotein";
+/// First line of a GFF3 file
+pub const GFF_HEADER: &str = "##gff-version 3";
+const GFF_SOURCE: &str = "rosalind";
+const GFF_TYPE: &str = "CDS";
+const CODON_LENGTH: usize = 3;
+const START: u8 = b'M';
+
+/// Strand of a reading frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+impl Strand {
+    /// Returns the symbol of the strand as used in GFF
+    pub fn symbol(&self) -> char {
+        match self {
+            Self::Forward => '+',
+            Self::Reverse => '-',
+        }
+    }
+}
+
+/// Reading frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Frame {
+    pub strand: Strand,
+    /// Offset of the first codon on the strand, from 0 to 2
+    pub offset: usize,
+}
+
+impl Frame {
+    /// Every frame in the order +1, +2, +3, -1, -2, -3
+    pub const ALL: [Self; 6] = [
+        Self::new(Strand::Forward, 0),
+        Self::new(Strand::Forward, 1),
+        Self::new(Strand::Forward, 2),
+        Self::new(Strand::Reverse, 0),
+        Self::new(Strand::Reverse, 1),
+        Self::new(Strand::Reverse, 2),
+    ];
+
+    pub const fn new(strand: Strand, offset: usize) -> Self {
+        Self { strand, offset }
+    }
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.strand.symbol(), self.offset + 1)
+    }
+}
+
+/// Translation of an entire frame
+///
+/// Stop codons are translated as '*' and codons that cannot be translated as 'X'.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub frame: Frame,
+    pub protein: String,
+}
+
+/// Translate the frame +1 of a dna string
+pub fn translate(dna_string: &[u8], code: &GeneticCode) -> Translation {
+    Translation {
+        frame: Frame::ALL[0],
+        protein: translate_frame(dna_string, 0, code),
+    }
+}
+
+/// Translate all six frames of a dna string
+pub fn six_frame(dna_string: &[u8], code: &GeneticCode) -> Result<Vec<Translation>> {
+    let reverse = dna::reverse_complement(dna_string)?;
+    Ok(Frame::ALL
+        .iter()
+        .map(|frame| Translation {
+            frame: *frame,
+            protein: translate_frame(strand(frame, dna_string, &reverse), frame.offset, code),
+        })
+        .collect())
+}
+
+/// Write the translation of every fasta record of the input as fasta
+///
+/// With `six_frame`, every record is translated into six records named after the frame, such as
+/// `name_+1` and `name_-3`.
+///
+pub fn write_translations<W>(
+    input: &Input,
+    code: &GeneticCode,
+    six_frame: bool,
+    writer: W,
+) -> Result<()>
+where
+    W: std::io::Write,
+{
+    let mut reader = fasta::Reader::new(input.reader()?);
+    let mut writer = fasta::Writer::new(writer);
+    while let Some(record) = reader.next_record() {
+        let record = record
+            .and_then(|record| record.to_record())
+            .map_err(|e| e.in_file(input.name()))?;
+        if !six_frame {
+            let translation = translate(record.sequence.as_bytes(), code);
+            writer.write(&fasta::Record {
+                sequence: translation.protein,
+                ..record
+            })?;
+            continue;
+        }
+        let translations = self::six_frame(record.sequence.as_bytes(), code)
+            .map_err(|e| e.in_file(input.name()))?;
+        for translation in translations {
+            writer.write(&fasta::Record {
+                name: format!("{}_{}", record.name, translation.frame),
+                description: record.description.clone(),
+                sequence: translation.protein,
+            })?;
+        }
+    }
+    writer.flush()
+}
+
+/// Open reading frame running from a start codon up to and including a stop codon
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Orf {
+    pub start: usize,
+    pub end: usize,
+    pub frame: Frame,
+    /// Translated protein without the stop codon
+    pub protein: String,
+}
+
+impl Orf {
+    /// Returns the number of bases, including the stop codon
+    pub fn nucleotide_length(&self) -> usize {
+        self.end - self.start + 1
+    }
+
+    /// Returns the number of amino acids
+    pub fn protein_length(&self) -> usize {
+        self.protein.len()
+    }
+
+    /// Format as a row of the ORF table
+    pub fn to_row(&self, sequence: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            sequence,
+            self.frame,
+            self.start,
+            self.end,
+            self.nucleotide_length(),
+            self.protein_length(),
+            self.protein
+        )
+    }
+
+    /// Format as a GFF3 line with the given ID
+    pub fn to_gff(&self, sequence: &str, id: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t.\t{}\t0\tID={};frame={};protein_length={}",
+            sequence,
+            GFF_SOURCE,
+            GFF_TYPE,
+            self.start,
+            self.end,
+            self.frame.strand.symbol(),
+            id,
+            self.frame,
+            self.protein_length()
+        )
+    }
+}
+
+/// Configurable ORF finder
+///
+/// By default, ORFs start at codons that translate into methionine, nested ORFs are not reported
+/// and there is no minimum length.  ORFs that run off the end of the sequence are never reported.
+///
+pub struct Finder<'a> {
+    code: &'a GeneticCode,
+    min_length: usize,
+    alternative_starts: bool,
+    nested: bool,
+}
+
+impl<'a> Finder<'a> {
+    pub fn new(code: &'a GeneticCode) -> Self {
+        Self {
+            code,
+            min_length: 0,
+            alternative_starts: false,
+            nested: false,
+        }
+    }
+
+    /// Set the minimum number of bases of an ORF, including the stop codon
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Start ORFs at every start codon of the genetic code instead of only at methionine
+    ///
+    /// The first amino acid of an ORF is always translated as methionine.
+    ///
+    pub fn alternative_starts(mut self, alternative_starts: bool) -> Self {
+        self.alternative_starts = alternative_starts;
+        self
+    }
+
+    /// Report an ORF for every start codon, including those inside a longer ORF
+    pub fn nested(mut self, nested: bool) -> Self {
+        self.nested = nested;
+        self
+    }
+
+    /// Write the ORFs of every fasta record of the input as a table, or as GFF3 with `gff`
+    ///
+    /// The ORFs are numbered across all records to give them unique GFF IDs.
+    ///
+    pub fn write_orfs<W>(&self, input: &Input, gff: bool, mut writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(writer, "{}", if gff { GFF_HEADER } else { TABLE_HEADER })?;
+        let mut reader = fasta::Reader::new(input.reader()?);
+        let mut count = 0;
+        while let Some(record) = reader.next_record() {
+            let record = record.map_err(|e| e.in_file(input.name()))?;
+            let name = String::from_utf8_lossy(&record.name);
+            let orfs = self
+                .find(&record.sequence)
+                .map_err(|e| e.in_file(input.name()))?;
+            for orf in orfs {
+                count += 1;
+                match gff {
+                    true => writeln!(writer, "{}", orf.to_gff(&name, &format!("orf{}", count)))?,
+                    false => writeln!(writer, "{}", orf.to_row(&name))?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the ORFs on both strands of a dna string, ordered by position
+    pub fn find(&self, dna_string: &[u8]) -> Result<Vec<Orf>> {
+        let reverse = dna::reverse_complement(dna_string)?;
+        let mut orfs = Vec::new();
+        for frame in Frame::ALL {
+            self.find_in_frame(frame, strand(&frame, dna_string, &reverse), &mut orfs);
+        }
+        orfs.sort();
+        Ok(orfs)
+    }
+
+    /// Find the ORFs of a single frame
+    ///
+    /// A codon that cannot be translated closes every ORF open in the frame.  A codon that stops
+    /// translation only at the end of a gene ends every ORF open in the frame without closing
+    /// them, since it may also be read as its amino acid.
+    ///
+    fn find_in_frame(&self, frame: Frame, strand: &[u8], orfs: &mut Vec<Orf>) {
+        let mut starts: Vec<usize> = Vec::new();
+        for (i, codon) in codons(strand, frame.offset).enumerate() {
+            let position = frame.offset + i * CODON_LENGTH;
+            match self.code.translate(codon) {
+                Err(_) => starts.clear(),
+                Ok(None) => {
+                    let end = position + CODON_LENGTH;
+                    for start in starts.drain(..) {
+                        if end - start >= self.min_length {
+                            orfs.push(self.orf(frame, strand, start, end));
+                        }
+                    }
+                }
+                Ok(Some(_)) => {
+                    if self.code.is_stop(codon) {
+                        let end = position + CODON_LENGTH;
+                        for start in &starts {
+                            if end - start >= self.min_length {
+                                orfs.push(self.orf(frame, strand, *start, end));
+                            }
+                        }
+                    }
+                    if self.is_start(codon) && (self.nested || starts.is_empty()) {
+                        starts.push(position);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check to see if an ORF can start at the codon
+    fn is_start(&self, codon: &[u8]) -> bool {
+        if self.alternative_starts {
+            return self.code.is_start(codon);
+        }
+        matches!(self.code.translate(codon), Ok(Some(START)))
+    }
+
+    /// Build the ORF spanning the bases from start to end of the strand
+    fn orf(&self, frame: Frame, strand: &[u8], start: usize, end: usize) -> Orf {
+        let protein = format!(
+            "{}{}",
+            char::from(START),
+            translate_frame(
+                &strand[start + CODON_LENGTH..end - CODON_LENGTH],
+                0,
+                self.code
+            )
+        );
+        let (start, end) = match frame.strand {
+            Strand::Forward => (start + 1, end),
+            Strand::Reverse => (strand.len() - end + 1, strand.len() - start),
+        };
+        Orf {
+            start,
+            end,
+            frame,
+            protein,
+        }
+    }
+}
+
+/// Return the strand of the frame
+fn strand<'a>(frame: &Frame, forward: &'a [u8], reverse: &'a [u8]) -> &'a [u8] {
+    match frame.strand {
+        Strand::Forward => forward,
+        Strand::Reverse => reverse,
+    }
+}
+
+/// Return the full codons of a strand from the offset
+fn codons(strand: &[u8], offset: usize) -> std::slice::ChunksExact<'_, u8> {
+    strand
+        .get(offset..)
+        .unwrap_or_default()
+        .chunks_exact(CODON_LENGTH)
+}
+
+/// Translate every full codon of a strand from the offset
+fn translate_frame(strand: &[u8], offset: usize, code: &GeneticCode) -> String {
+    codons(strand, offset)
+        .map(|codon| match code.translate(codon) {
+            Ok(Some(amino_acid)) => char::from(amino_acid),
+            Ok(None) => char::from(genetic_code::STOP),
+            Err(_) => char::from(UNKNOWN),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_six_frame() {
+        let translations = six_frame(b"ATGAAATGA", GeneticCode::standard()).unwrap();
+        let actual: Vec<(String, &str)> = translations
+            .iter()
+            .map(|translation| (translation.frame.to_string(), translation.protein.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("+1".to_owned(), "MK*"),
+                ("+2".to_owned(), "*N"),
+                ("+3".to_owned(), "EM"),
+                ("-1".to_owned(), "SFH"),
+                ("-2".to_owned(), "HF"),
+                ("-3".to_owned(), "IS"),
+            ],
+            actual,
+            "{}",
+            "Should translate all six frames"
+        );
+    }
+
+    #[test]
+    fn test_finder_find() {
+        struct TestCase<'a> {
+            name: &'a str,
+            finder: Finder<'a>,
+            dna_string: &'a [u8],
+            expected: Vec<(&'a str, usize, usize, &'a str)>,
+        }
+        let code = GeneticCode::standard();
+        let test_cases = [
+            TestCase {
+                name: "Should find the longest ORF of each stop codon",
+                finder: Finder::new(code),
+                dna_string: b"CATGATGTAAC",
+                expected: vec![("+2", 2, 10, "MM")],
+            },
+            TestCase {
+                name: "Should find nested ORFs",
+                finder: Finder::new(code).nested(true),
+                dna_string: b"CATGATGTAAC",
+                expected: vec![("+2", 2, 10, "MM"), ("+2", 5, 10, "M")],
+            },
+            TestCase {
+                name: "Should find ORFs on the reverse strand",
+                finder: Finder::new(code),
+                dna_string: b"TTACATGG",
+                expected: vec![("-3", 1, 6, "M")],
+            },
+            TestCase {
+                name: "Should filter ORFs shorter than the minimum length",
+                finder: Finder::new(code).nested(true).min_length(9),
+                dna_string: b"CATGATGTAAC",
+                expected: vec![("+2", 2, 10, "MM")],
+            },
+            TestCase {
+                name: "Should start at alternative start codons",
+                finder: Finder::new(code).alternative_starts(true),
+                dna_string: b"TTGAAATGA",
+                expected: vec![("+1", 1, 9, "MK")],
+            },
+            TestCase {
+                name: "Should end ORFs at codons that are stops only at the end of a gene",
+                finder: Finder::new(GeneticCode::from_id(28).unwrap()),
+                dna_string: b"ATGAAATAAATGTAG",
+                expected: vec![("+1", 1, 9, "MK"), ("+1", 1, 15, "MKQM")],
+            },
+            TestCase {
+                name: "Should not report ORFs broken by an ambiguous base",
+                finder: Finder::new(code),
+                dna_string: b"ATGNAATGA",
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let actual = test_case.finder.find(test_case.dna_string).unwrap();
+            let actual: Vec<(String, usize, usize, &str)> = actual
+                .iter()
+                .map(|orf| {
+                    (
+                        orf.frame.to_string(),
+                        orf.start,
+                        orf.end,
+                        orf.protein.as_str(),
+                    )
+                })
+                .collect();
+            let expected: Vec<(String, usize, usize, &str)> = test_case
+                .expected
+                .into_iter()
+                .map(|(frame, start, end, protein)| (frame.to_owned(), start, end, protein))
+                .collect();
+            assert_eq!(expected, actual, "{}", test_case.name);
+        }
+    }
+
+    #[test]
+    fn test_orf_format() {
+        let orf = Orf {
+            start: 1,
+            end: 6,
+            frame: Frame::new(Strand::Reverse, 2),
+            protein: "M".into(),
+        };
+        assert_eq!(
+            "seq1\t-3\t1\t6\t6\t1\tM",
+            orf.to_row("seq1"),
+            "{}",
+            "Should format a table row"
+        );
+        assert_eq!(
+            "seq1\trosalind\tCDS\t1\t6\t.\t-\t0\tID=orf1;frame=-3;protein_length=1",
+            orf.to_gff("seq1", "orf1"),
+            "{}",
+            "Should format a GFF line"
+        );
+    }
+
+    #[test]
+    fn test_finder_write_orfs() {
+        struct TestCase<'a> {
+            name: &'a str,
+            gff: bool,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should write a table",
+                gff: false,
+                expected:
+                    "sequence\tframe\tstart\tend\tnucleotide_length\tprotein_length\tprotein\n\
+                           s1\t+1\t1\t9\t9\t2\tMK\n\
+                           s1\t+1\t13\t21\t9\t2\tMF\n\
+                           s2\t-1\t1\t6\t6\t1\tM\n",
+            },
+            TestCase {
+                name: "Should write GFF3 numbered across records",
+                gff: true,
+                expected: "##gff-version 3\n\
+                           s1\trosalind\tCDS\t1\t9\t.\t+\t0\tID=orf1;frame=+1;protein_length=2\n\
+                           s1\trosalind\tCDS\t13\t21\t.\t+\t0\tID=orf2;frame=+1;protein_length=2\n\
+                           s2\trosalind\tCDS\t1\t6\t.\t-\t0\tID=orf3;frame=-1;protein_length=1\n",
+            },
+        ];
+        let input = Input::Text(">s1\nATGAAATAGCCCATGTTTTGA\n>s2\nCTACATTTT\n".to_owned());
+        for test_case in test_cases {
+            let mut output = Vec::new();
+            Finder::new(GeneticCode::from_id(1).unwrap())
+                .write_orfs(&input, test_case.gff, &mut output)
+                .unwrap();
+            assert_eq!(
+                test_case.expected,
+                String::from_utf8(output).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_translations() {
+        struct TestCase<'a> {
+            name: &'a str,
+            six_frame: bool,
+            expected: &'a str,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should translate the first frame",
+                six_frame: false,
+                expected: ">s1 d\nMK*\n",
+            },
+            TestCase {
+                name: "Should translate six frames into records named after the frame",
+                six_frame: true,
+                expected: ">s1_+1 d\nMK*\n>s1_+2 d\n*N\n>s1_+3 d\nEI\n\
+                           >s1_-1 d\nLFH\n>s1_-2 d\nYF\n>s1_-3 d\nIS\n",
+            },
+        ];
+        let input = Input::Text(">s1 d\nATGAAATAG\n".to_owned());
+        for test_case in test_cases {
+            let mut output = Vec::new();
+            write_translations(
+                &input,
+                GeneticCode::from_id(1).unwrap(),
+                test_case.six_frame,
+                &mut output,
+            )
+            .unwrap();
+            assert_eq!(
+                test_case.expected,
+                String::from_utf8(output).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
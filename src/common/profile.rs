@@ -0,0 +1,533 @@
+//! Profiles of aligned sequences and position weight matrices
+//!
+//! A profile counts every symbol of an alphabet, and gaps, in every column of a set of aligned
+//! sequences.  Frequencies and information content are computed over the symbols of a column
+//! other than gaps.  Position weight matrices hold the log2-odds of every symbol against a uniform
+//! background, with a pseudocount added to every count, and are used to scan sequences for motifs.
+//!
+use crate::common::align::GAP;
+use crate::common::input::Input;
+use crate::common::orf::Strand;
+use crate::common::seq::{Alphabet, Dna};
+use crate::common::{self, dna, fasta};
+use crate::errors::{Error, ErrorKind, Result};
+use std::marker::PhantomData;
+
+/// Default pseudocount added to every count of a position weight matrix
+pub const PSEUDOCOUNT: f64 = 0.5;
+/// Header of the tab separated table of scan hits
+pub const TABLE_HEADER: &str = "sequence\tstart\tend\tstrand\tscore\tsite";
+/// Fraction of the best possible score that hits need by default
+const THRESHOLD_FRACTION: f64 = 0.8;
+
+/// Counts of the symbols and gaps in every column of aligned sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile<A> {
+    /// Count of every symbol of the alphabet, in the order of the alphabet, per column
+    counts: Vec<Vec<usize>>,
+    /// Count of gaps per column
+    gaps: Vec<usize>,
+    /// Number of sequences
+    depth: usize,
+    alphabet: PhantomData<A>,
+}
+
+impl<A> Profile<A>
+where
+    A: Alphabet,
+{
+    /// Construct an empty profile of sequences of the given length
+    pub fn new(length: usize) -> Self {
+        Self {
+            counts: vec![vec![0; A::SYMBOLS.len()]; length],
+            gaps: vec![0; length],
+            depth: 0,
+            alphabet: PhantomData,
+        }
+    }
+
+    /// Construct the profile of aligned sequences, which all have the length of the first one
+    pub fn from_sequences<S>(sequences: &[S]) -> Result<Self>
+    where
+        S: AsRef<[u8]>,
+    {
+        let length = sequences.first().map(|s| s.as_ref().len()).unwrap_or(0);
+        let mut profile = Self::new(length);
+        for sequence in sequences {
+            profile.add(sequence.as_ref())?;
+        }
+        Ok(profile)
+    }
+
+    /// Count the symbols of a sequence, which may contain gaps
+    ///
+    /// An error is returned if the sequence is not as long as the profile, or if it contains a
+    /// symbol outside of the alphabet.
+    ///
+    pub fn add(&mut self, sequence: &[u8]) -> Result<()> {
+        if sequence.len() != self.len() {
+            return Err(Error::new(
+                ErrorKind::Validation,
+                &format!(
+                    "expected aligned sequences of length {}, found length {}",
+                    self.len(),
+                    sequence.len()
+                ),
+            ));
+        }
+        for (i, symbol) in sequence.iter().enumerate() {
+            if *symbol == GAP {
+                self.gaps[i] += 1;
+                continue;
+            }
+            match index::<A>(*symbol) {
+                Some(index) => self.counts[i][index] += 1,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::Alphabet,
+                        &format!(
+                            "not a {} symbol: {} at position {}",
+                            A::NAME,
+                            *symbol as char,
+                            i + 1
+                        ),
+                    ))
+                }
+            }
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Returns the number of columns
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns true if the profile has no columns
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Returns the number of sequences
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the count of every symbol of the alphabet per column
+    pub fn counts(&self) -> &[Vec<usize>] {
+        &self.counts
+    }
+
+    /// Returns the count of gaps per column
+    pub fn gaps(&self) -> &[usize] {
+        &self.gaps
+    }
+
+    /// Check to see if any column has a gap
+    pub fn has_gaps(&self) -> bool {
+        self.gaps.iter().any(|gaps| *gaps > 0)
+    }
+
+    /// Return the most common symbol of every column
+    ///
+    /// Ties go to the symbol first in the alphabet, and columns of only gaps give a gap.
+    ///
+    pub fn consensus(&self) -> String {
+        self.counts
+            .iter()
+            .map(|counts| {
+                let (mut best, mut max_count) = (GAP, 0);
+                for (symbol, count) in A::SYMBOLS.iter().zip(counts) {
+                    if *count > max_count {
+                        best = *symbol;
+                        max_count = *count;
+                    }
+                }
+                char::from(best)
+            })
+            .collect()
+    }
+
+    /// Return the frequency of every symbol per column, which is 0 in columns of only gaps
+    pub fn frequencies(&self) -> Vec<Vec<f64>> {
+        self.counts
+            .iter()
+            .map(|counts| {
+                let total: usize = counts.iter().sum();
+                counts
+                    .iter()
+                    .map(|count| match total {
+                        0 => 0.0,
+                        _ => *count as f64 / total as f64,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Return the information content of every symbol per column in bits
+    ///
+    /// The information content of a column is the maximum entropy of the alphabet less the
+    /// entropy of the column, and is shared out between the symbols by frequency.
+    ///
+    pub fn information_content(&self) -> Vec<Vec<f64>> {
+        let max_entropy = (A::SYMBOLS.len() as f64).log2();
+        self.frequencies()
+            .into_iter()
+            .map(|frequencies| {
+                let entropy: f64 = frequencies
+                    .iter()
+                    .filter(|f| **f > 0.0)
+                    .map(|f| -f * f.log2())
+                    .sum();
+                let content = match frequencies.iter().any(|f| *f > 0.0) {
+                    true => max_entropy - entropy,
+                    false => 0.0,
+                };
+                frequencies.iter().map(|f| f * content).collect()
+            })
+            .collect()
+    }
+
+    /// Return the position weight matrix of the profile with the pseudocount added to every count
+    pub fn pwm(&self, pseudocount: f64) -> Pwm<A> {
+        let symbols = A::SYMBOLS.len() as f64;
+        let scores = self
+            .counts
+            .iter()
+            .map(|counts| {
+                let total = counts.iter().sum::<usize>() as f64 + pseudocount * symbols;
+                counts
+                    .iter()
+                    .map(|count| {
+                        let probability = (*count as f64 + pseudocount) / total;
+                        (probability * symbols).log2()
+                    })
+                    .collect()
+            })
+            .collect();
+        Pwm {
+            scores,
+            alphabet: PhantomData,
+        }
+    }
+}
+
+/// Position weight matrix of log2-odds scores
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pwm<A> {
+    /// Score of every symbol of the alphabet, in the order of the alphabet, per column
+    scores: Vec<Vec<f64>>,
+    alphabet: PhantomData<A>,
+}
+
+impl<A> Pwm<A>
+where
+    A: Alphabet,
+{
+    /// Returns the number of columns
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Returns true if the matrix has no columns
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Returns the score of every symbol of the alphabet per column
+    pub fn scores(&self) -> &[Vec<f64>] {
+        &self.scores
+    }
+
+    /// Return the best score of any window
+    pub fn max_score(&self) -> f64 {
+        self.scores
+            .iter()
+            .map(|scores| scores.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+            .sum()
+    }
+
+    /// Return the default threshold of a scan, which is a fraction of the best score
+    pub fn default_threshold(&self) -> f64 {
+        self.max_score() * THRESHOLD_FRACTION
+    }
+
+    /// Return the score of a window as long as the matrix, or None if it has other symbols
+    pub fn score(&self, window: &[u8]) -> Option<f64> {
+        if window.len() != self.len() {
+            return None;
+        }
+        window
+            .iter()
+            .zip(&self.scores)
+            .map(|(symbol, scores)| Some(scores[index::<A>(*symbol)?]))
+            .sum()
+    }
+
+    /// Return the windows of the sequence that score at least the threshold
+    pub fn scan(&self, sequence: &[u8], threshold: f64) -> Vec<Hit> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        sequence
+            .windows(self.len())
+            .enumerate()
+            .filter_map(|(start, window)| {
+                let score = self.score(window)?;
+                (score >= threshold).then(|| Hit {
+                    start: start + 1,
+                    end: start + self.len(),
+                    strand: Strand::Forward,
+                    score,
+                    site: String::from_utf8_lossy(window).to_ascii_uppercase(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Pwm<Dna> {
+    /// Construct the matrix of the aligned sites of a motif given as fasta records
+    pub fn from_fasta(input: &Input, pseudocount: f64) -> Result<Self> {
+        let sites = common::load_fasta(input)?;
+        let sites: Vec<&[u8]> = sites.iter().map(|site| site.sequence.as_bytes()).collect();
+        Ok(Profile::<Dna>::from_sequences(&sites)
+            .map_err(|e| e.in_file(input.name()))?
+            .pwm(pseudocount))
+    }
+
+    /// Return the matrix of the reverse complement of the motif
+    pub fn reverse_complement(&self) -> Self {
+        let complements: Vec<usize> = Dna::SYMBOLS
+            .iter()
+            .map(|base| {
+                dna::complement(base)
+                    .ok()
+                    .and_then(index::<Dna>)
+                    .unwrap_or_default()
+            })
+            .collect();
+        Self {
+            scores: self
+                .scores
+                .iter()
+                .rev()
+                .map(|scores| complements.iter().map(|i| scores[*i]).collect())
+                .collect(),
+            alphabet: PhantomData,
+        }
+    }
+
+    /// Return the windows of both strands of the sequence that score at least the threshold
+    ///
+    /// Hits are sorted by position, with the forward strand first.
+    ///
+    pub fn scan_both_strands(&self, sequence: &[u8], threshold: f64) -> Vec<Hit> {
+        let mut hits = self.scan(sequence, threshold);
+        hits.extend(
+            self.reverse_complement()
+                .scan(sequence, threshold)
+                .into_iter()
+                .map(|hit| Hit {
+                    strand: Strand::Reverse,
+                    site: dna::reverse_complement(hit.site.as_bytes())
+                        .map(|site| String::from_utf8_lossy(&site).into_owned())
+                        .unwrap_or(hit.site.clone()),
+                    ..hit
+                }),
+        );
+        hits.sort_by_key(|hit| (hit.start, hit.strand));
+        hits
+    }
+
+    /// Write the hits on both strands of every fasta record of the input as a table
+    pub fn write_scan<W>(&self, input: &Input, threshold: f64, mut writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+    {
+        writeln!(writer, "{}", TABLE_HEADER)?;
+        let mut reader = fasta::Reader::new(input.reader()?);
+        while let Some(record) = reader.next_record() {
+            let record = record.map_err(|e| e.in_file(input.name()))?;
+            let name = String::from_utf8_lossy(&record.name);
+            for hit in self.scan_both_strands(&record.sequence, threshold) {
+                writeln!(writer, "{}", hit.to_row(&name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Window of a sequence matching a position weight matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    /// 1-based position of the first base on the forward strand
+    pub start: usize,
+    /// 1-based position of the last base on the forward strand
+    pub end: usize,
+    pub strand: Strand,
+    pub score: f64,
+    /// Window as read on its strand
+    pub site: String,
+}
+
+impl Hit {
+    /// Return the hit as a row of the tab separated table
+    pub fn to_row(&self, sequence: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{:.3}\t{}",
+            sequence,
+            self.start,
+            self.end,
+            self.strand.symbol(),
+            self.score,
+            self.site
+        )
+    }
+}
+
+/// Return the index of a symbol in the alphabet, in either case
+fn index<A>(symbol: u8) -> Option<usize>
+where
+    A: Alphabet,
+{
+    A::SYMBOLS
+        .iter()
+        .position(|s| *s == symbol.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::seq::Protein;
+
+    #[test]
+    fn test_profile_add() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            expected: Result<(Vec<Vec<usize>>, Vec<usize>)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should count symbols and gaps",
+                sequences: vec!["AC-T", "aGGT"],
+                expected: Ok((
+                    vec![
+                        vec![2, 0, 0, 0],
+                        vec![0, 1, 1, 0],
+                        vec![0, 0, 1, 0],
+                        vec![0, 0, 0, 2],
+                    ],
+                    vec![0, 0, 1, 0],
+                )),
+            },
+            TestCase {
+                name: "Should reject ragged sequences",
+                sequences: vec!["ACGT", "ACG"],
+                expected: Err(Error::new(
+                    ErrorKind::Validation,
+                    "expected aligned sequences of length 4, found length 3",
+                )),
+            },
+            TestCase {
+                name: "Should reject symbols outside of the alphabet",
+                sequences: vec!["ACNT"],
+                expected: Err(Error::new(
+                    ErrorKind::Alphabet,
+                    "not a dna symbol: N at position 3",
+                )),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                Profile::<Dna>::from_sequences(&test_case.sequences)
+                    .map(|profile| (profile.counts().to_vec(), profile.gaps().to_vec())),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_profile_matrices() {
+        let profile = Profile::<Dna>::from_sequences(&["AC", "AG", "A-"]).unwrap();
+        assert_eq!("AC", profile.consensus(), "Should break ties by alphabet");
+        assert_eq!(
+            vec![vec![1.0, 0.0, 0.0, 0.0], vec![0.0, 0.5, 0.5, 0.0]],
+            profile.frequencies(),
+            "Should ignore gaps in frequencies"
+        );
+        assert_eq!(
+            vec![vec![2.0, 0.0, 0.0, 0.0], vec![0.0, 0.5, 0.5, 0.0]],
+            profile.information_content(),
+            "Should share out the information content by frequency"
+        );
+        assert_eq!(
+            vec![
+                vec![(3.5f64 / 5.0 * 4.0).log2(), (0.5f64 / 5.0 * 4.0).log2()],
+                vec![(0.5f64 / 4.0 * 4.0).log2(), (1.5f64 / 4.0 * 4.0).log2()],
+            ],
+            profile
+                .pwm(0.5)
+                .scores()
+                .iter()
+                .map(|scores| scores[..2].to_vec())
+                .collect::<Vec<Vec<f64>>>(),
+            "Should add the pseudocount to every count"
+        );
+    }
+
+    #[test]
+    fn test_profile_protein() {
+        let profile = Profile::<Protein>::from_sequences(&["MEAN", "MEAT"]).unwrap();
+        assert_eq!("MEAN", profile.consensus(), "Should count amino acids");
+    }
+
+    #[test]
+    fn test_scan_both_strands() {
+        let pwm = Profile::<Dna>::from_sequences(&["GATA", "GATA"])
+            .unwrap()
+            .pwm(PSEUDOCOUNT);
+        let hits = pwm.scan_both_strands(b"CCGATAGTATCNGATA", pwm.max_score());
+        assert_eq!(
+            vec![
+                (3, 6, Strand::Forward, "GATA"),
+                (8, 11, Strand::Reverse, "GATA"),
+                (13, 16, Strand::Forward, "GATA"),
+            ],
+            hits.iter()
+                .map(|hit| (hit.start, hit.end, hit.strand, hit.site.as_str()))
+                .collect::<Vec<_>>(),
+            "{}",
+            "Should find the motif on both strands and skip unknown bases"
+        );
+    }
+
+    #[test]
+    fn test_pwm_write_scan() {
+        let pwm = Pwm::<Dna>::from_fasta(
+            &Input::Text(">m1\nGATA\n>m2\nGATA\n".to_owned()),
+            PSEUDOCOUNT,
+        )
+        .unwrap();
+        let mut output = Vec::new();
+        pwm.write_scan(
+            &Input::Text(">s1\nCCGATAG\n>s2\nTATC\n".to_owned()),
+            pwm.default_threshold(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            "sequence\tstart\tend\tstrand\tscore\tsite\n\
+             s1\t3\t6\t+\t5.288\tGATA\n\
+             s2\t1\t4\t-\t5.288\tGATA\n",
+            String::from_utf8(output).unwrap(),
+            "{}",
+            "Should write the hits of every record"
+        );
+    }
+}
@@ -0,0 +1,410 @@
+//! Sequences over validated alphabets
+//!
+//! `DnaSeq`, `RnaSeq` and `ProteinSeq` can only be constructed by parsing, which checks every
+//! symbol against the alphabet once.  Operations on them are then infallible.  Lowercase input is
+//! accepted and stored in uppercase.
+//!
+use crate::common::dna;
+use crate::common::genetic_code::GeneticCode;
+use crate::errors::{Error, ErrorKind, Location, Result};
+use std::marker::PhantomData;
+
+/// Alphabet of a sequence
+pub trait Alphabet {
+    /// Name of the alphabet used when reporting errors
+    const NAME: &'static str;
+    /// Valid symbols of the alphabet in uppercase
+    const SYMBOLS: &'static [u8];
+}
+
+/// Alphabet of DNA strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Dna;
+
+impl Alphabet for Dna {
+    const NAME: &'static str = "dna";
+    const SYMBOLS: &'static [u8] = dna::BASES;
+}
+
+/// Alphabet of DNA strings that may contain IUPAC ambiguity codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Iupac;
+
+impl Alphabet for Iupac {
+    const NAME: &'static str = "iupac dna";
+    const SYMBOLS: &'static [u8] = dna::IUPAC_CODES;
+}
+
+/// Alphabet of RNA strings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rna;
+
+impl Alphabet for Rna {
+    const NAME: &'static str = "rna";
+    const SYMBOLS: &'static [u8] = b"ACGU";
+}
+
+/// Alphabet of protein strings made of the 20 standard amino acids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Protein;
+
+impl Alphabet for Protein {
+    const NAME: &'static str = "protein";
+    const SYMBOLS: &'static [u8] = b"ACDEFGHIKLMNPQRSTVWY";
+}
+
+/// Sequence whose symbols all belong to the alphabet
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Seq<A> {
+    sequence: String,
+    alphabet: PhantomData<A>,
+}
+
+pub type DnaSeq = Seq<Dna>;
+pub type IupacSeq = Seq<Iupac>;
+pub type RnaSeq = Seq<Rna>;
+pub type ProteinSeq = Seq<Protein>;
+
+impl<A> Seq<A>
+where
+    A: Alphabet,
+{
+    /// Validate bytes against the alphabet
+    ///
+    /// An error naming the 1-based position of the first invalid symbol is returned.
+    ///
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        match find_invalid::<A>(bytes) {
+            Some(i) => Err(invalid_symbol::<A>(bytes, i)),
+            None => Ok(Self::from_validated(bytes.to_ascii_uppercase())),
+        }
+    }
+
+    /// Construct a sequence from bytes already known to belong to the alphabet
+    fn from_validated(sequence: Vec<u8>) -> Self {
+        Self {
+            sequence: sequence.into_iter().map(char::from).collect(),
+            alphabet: PhantomData,
+        }
+    }
+
+    /// Returns the sequence as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.sequence
+    }
+
+    /// Returns the sequence as bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.sequence.as_bytes()
+    }
+
+    /// Returns the length of the sequence
+    pub fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    /// Returns true if the sequence is empty
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    /// Convert the sequence into a string
+    pub fn into_string(self) -> String {
+        self.sequence
+    }
+}
+
+impl DnaSeq {
+    /// Complement every base
+    pub fn complement(&self) -> Self {
+        Self::from_validated(self.as_bytes().iter().map(complement_dna).collect())
+    }
+
+    /// Reverse complement the sequence
+    pub fn reverse_complement(&self) -> Self {
+        Self::from_validated(self.as_bytes().iter().rev().map(complement_dna).collect())
+    }
+
+    /// Transcribe into RNA by replacing 'T' with 'U'
+    pub fn transcribe(&self) -> RnaSeq {
+        RnaSeq::from_validated(
+            self.as_bytes()
+                .iter()
+                .map(|b| swap(*b, b'T', b'U'))
+                .collect(),
+        )
+    }
+
+    /// Translate into protein
+    ///
+    /// Translation starts at the first base and stops at the first stop codon.  Trailing bases
+    /// that do not make up a full codon are ignored.
+    ///
+    pub fn translate(&self) -> ProteinSeq {
+        self.translate_with(GeneticCode::standard())
+    }
+
+    /// Translate into protein using the genetic code
+    pub fn translate_with(&self, code: &GeneticCode) -> ProteinSeq {
+        translate(self.as_bytes(), code)
+    }
+}
+
+impl IupacSeq {
+    /// Complement every base, including ambiguity codes
+    pub fn complement(&self) -> Self {
+        Self::from_validated(self.as_bytes().iter().map(complement_dna).collect())
+    }
+
+    /// Reverse complement the sequence, including ambiguity codes
+    pub fn reverse_complement(&self) -> Self {
+        Self::from_validated(self.as_bytes().iter().rev().map(complement_dna).collect())
+    }
+}
+
+impl From<DnaSeq> for IupacSeq {
+    fn from(dna_string: DnaSeq) -> Self {
+        Self::from_validated(dna_string.into_string().into_bytes())
+    }
+}
+
+impl RnaSeq {
+    /// Complement every base
+    pub fn complement(&self) -> Self {
+        self.back_transcribe().complement().transcribe()
+    }
+
+    /// Reverse complement the sequence
+    pub fn reverse_complement(&self) -> Self {
+        self.back_transcribe().reverse_complement().transcribe()
+    }
+
+    /// Back transcribe into DNA by replacing 'U' with 'T'
+    pub fn back_transcribe(&self) -> DnaSeq {
+        DnaSeq::from_validated(
+            self.as_bytes()
+                .iter()
+                .map(|b| swap(*b, b'U', b'T'))
+                .collect(),
+        )
+    }
+
+    /// Translate into protein
+    ///
+    /// Translation starts at the first base and stops at the first stop codon.  Trailing bases
+    /// that do not make up a full codon are ignored.
+    ///
+    pub fn translate(&self) -> ProteinSeq {
+        self.translate_with(GeneticCode::standard())
+    }
+
+    /// Translate into protein using the genetic code
+    pub fn translate_with(&self, code: &GeneticCode) -> ProteinSeq {
+        translate(self.as_bytes(), code)
+    }
+}
+
+impl<A> std::str::FromStr for Seq<A>
+where
+    A: Alphabet,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match find_invalid::<A>(s.as_bytes()) {
+            Some(i) => {
+                Err(invalid_symbol::<A>(s.as_bytes(), i).with_location(Location::from_offset(s, i)))
+            }
+            None => Ok(Self::from_validated(s.as_bytes().to_ascii_uppercase())),
+        }
+    }
+}
+
+impl<A> std::fmt::Display for Seq<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.sequence)
+    }
+}
+
+impl<A> AsRef<str> for Seq<A> {
+    fn as_ref(&self) -> &str {
+        &self.sequence
+    }
+}
+
+impl<A> AsRef<[u8]> for Seq<A> {
+    fn as_ref(&self) -> &[u8] {
+        self.sequence.as_bytes()
+    }
+}
+
+impl<A> serde::Serialize for Seq<A> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.sequence)
+    }
+}
+
+/// Return the offset of the first symbol that does not belong to the alphabet
+fn find_invalid<A>(bytes: &[u8]) -> Option<usize>
+where
+    A: Alphabet,
+{
+    bytes
+        .iter()
+        .position(|symbol| !A::SYMBOLS.contains(&symbol.to_ascii_uppercase()))
+}
+
+/// Return an error for the invalid symbol at the offset
+fn invalid_symbol<A>(bytes: &[u8], offset: usize) -> Error
+where
+    A: Alphabet,
+{
+    Error::new(
+        ErrorKind::Alphabet,
+        &format!(
+            "not a {} symbol: {} at position {}",
+            A::NAME,
+            String::from_utf8_lossy(&bytes[offset..=offset]),
+            offset + 1
+        ),
+    )
+}
+
+/// Complement a base known to be a valid DNA base or IUPAC code
+fn complement_dna(base: &u8) -> u8 {
+    dna::complement(base).unwrap_or(*base)
+}
+
+/// Replace one symbol with another
+fn swap(symbol: u8, from: u8, to: u8) -> u8 {
+    if symbol == from {
+        to
+    } else {
+        symbol
+    }
+}
+
+/// Translate valid DNA or RNA bases into protein
+///
+/// A last codon that stops translation only at the end of a gene is read as a stop.
+///
+fn translate(bases: &[u8], code: &GeneticCode) -> ProteinSeq {
+    let codons: Vec<&[u8]> = bases.chunks_exact(3).collect();
+    let mut protein: Vec<u8> = codons
+        .iter()
+        .map_while(|codon| code.translate(codon).ok().flatten())
+        .collect();
+    if protein.len() == codons.len() && codons.last().is_some_and(|codon| code.is_stop(codon)) {
+        protein.pop();
+    }
+    ProteinSeq::from_validated(protein)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seq_from_str() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            expected: Result<DnaSeq>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should parse a dna string",
+                s: "acGT",
+                expected: Ok(DnaSeq::from_validated(b"ACGT".to_vec())),
+            },
+            TestCase {
+                name: "Should report the position of an invalid base",
+                s: "ACGU",
+                expected: Err(
+                    Error::new(ErrorKind::Alphabet, "not a dna symbol: U at position 4")
+                        .with_location(Location::new(1, 4, "ACGU")),
+                ),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                test_case.s.parse::<DnaSeq>(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_dna_seq() {
+        let dna: DnaSeq = "AAAACCCGGT".parse().unwrap();
+        assert_eq!(
+            "TTTTGGGCCA",
+            dna.complement().as_str(),
+            "{}",
+            "Should complement"
+        );
+        assert_eq!(
+            "ACCGGGTTTT",
+            dna.reverse_complement().as_str(),
+            "{}",
+            "Should reverse complement"
+        );
+        assert_eq!(
+            "AAAACCCGGU",
+            dna.transcribe().as_str(),
+            "{}",
+            "Should transcribe"
+        );
+        assert_eq!(
+            dna,
+            dna.transcribe().back_transcribe(),
+            "{}",
+            "Should back transcribe"
+        );
+    }
+
+    #[test]
+    fn test_iupac_seq() {
+        let dna: IupacSeq = "ACGTRYN".parse().unwrap();
+        assert_eq!(
+            "NRYACGT",
+            dna.reverse_complement().as_str(),
+            "{}",
+            "Should reverse complement ambiguity codes"
+        );
+    }
+
+    #[test]
+    fn test_translate() {
+        let rna: RnaSeq = "AUGGCCAUGGCGCCCAGAACUGAGAUCAAUAGUACCCGUAUUAACGGGUGA"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            "MAMAPRTEINSTRING",
+            rna.translate().as_str(),
+            "{}",
+            "Should translate until the stop codon"
+        );
+        let dna: DnaSeq = "ATGTGAAGATAA".parse().unwrap();
+        assert_eq!(
+            "MW",
+            dna.translate_with(GeneticCode::from_id(2).unwrap())
+                .as_str(),
+            "{}",
+            "Should translate with an alternative genetic code"
+        );
+        let dna: DnaSeq = "ATGTAAGAATAG".parse().unwrap();
+        assert_eq!(
+            "MEE",
+            dna.translate_with(GeneticCode::from_id(31).unwrap())
+                .as_str(),
+            "{}",
+            "Should read a last codon that is a stop only at the end of a gene as a stop"
+        );
+    }
+}
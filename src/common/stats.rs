@@ -0,0 +1,519 @@
+//! Sequence statistics
+//!
+//! `Stats` counts the composition of one sequence, or of many sequences added together, in a
+//! single pass.  `Summary` adds the length distribution of a whole file on top of that, so that
+//! files of any size can be summarized while they are streamed.
+//!
+use crate::common::dna;
+use crate::common::input::Input;
+use crate::errors::Result;
+
+/// Leading columns of the tab separated table, which are followed by the dinucleotides
+const TABLE_COLUMNS: &str = "name\tlength\tA\tC\tG\tT\tN\tother\tgc\tgc_skew\tmasked_fraction";
+/// Name of the row that adds up every sequence
+pub const TOTAL: &str = "total";
+const SYMBOLS: usize = 256;
+
+/// Composition of one or more sequences
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    counts: [usize; SYMBOLS],
+    dinucleotides: [[usize; 4]; 4],
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            counts: [0; SYMBOLS],
+            dinucleotides: [[0; 4]; 4],
+        }
+    }
+}
+
+impl Stats {
+    /// Construct empty statistics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the symbols of a sequence
+    ///
+    /// Dinucleotides are only counted within the sequence, and only when both bases are A, C, G
+    /// or T in either case.
+    ///
+    pub fn add(&mut self, sequence: &[u8]) {
+        for symbol in sequence {
+            self.counts[*symbol as usize] += 1;
+        }
+        for pair in sequence.windows(2) {
+            if let (Some(first), Some(second)) = (base_index(&pair[0]), base_index(&pair[1])) {
+                self.dinucleotides[first][second] += 1;
+            }
+        }
+    }
+
+    /// Add the statistics of other sequences
+    pub fn merge(&mut self, other: &Self) {
+        for (count, other) in self.counts.iter_mut().zip(other.counts) {
+            *count += other;
+        }
+        for (row, other) in self.dinucleotides.iter_mut().zip(other.dinucleotides) {
+            for (count, other) in row.iter_mut().zip(other) {
+                *count += other;
+            }
+        }
+    }
+
+    /// Returns the number of symbols
+    pub fn length(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the number of times a symbol occurs, in either case
+    pub fn count(&self, symbol: u8) -> usize {
+        let upper = symbol.to_ascii_uppercase();
+        let lower = symbol.to_ascii_lowercase();
+        match upper == lower {
+            true => self.counts[upper as usize],
+            false => self.counts[upper as usize] + self.counts[lower as usize],
+        }
+    }
+
+    /// Returns the number of symbols other than A, C, G, T and N
+    pub fn other(&self) -> usize {
+        self.length()
+            - b"ACGTN"
+                .iter()
+                .map(|symbol| self.count(*symbol))
+                .sum::<usize>()
+    }
+
+    /// Returns the GC content of the unambiguous bases as a percentage
+    pub fn gc_content(&self) -> f64 {
+        let gc = self.count(b'G') + self.count(b'C');
+        let total: usize = dna::BASES.iter().map(|base| self.count(*base)).sum();
+        100.0 * ratio(gc, total)
+    }
+
+    /// Returns the GC skew, (G - C) / (G + C)
+    pub fn gc_skew(&self) -> f64 {
+        let (g, c) = (self.count(b'G'), self.count(b'C'));
+        match g + c {
+            0 => 0.0,
+            total => (g as f64 - c as f64) / total as f64,
+        }
+    }
+
+    /// Returns the fraction of symbols that are soft-masked
+    pub fn masked_fraction(&self) -> f64 {
+        let masked = self.counts[b'a' as usize..=b'z' as usize].iter().sum();
+        ratio(masked, self.length())
+    }
+
+    /// Returns the frequency of every dinucleotide, in the order AA, AC, ..., TT
+    pub fn dinucleotide_frequencies(&self) -> Vec<(String, f64)> {
+        let total = self.dinucleotides.iter().flatten().sum();
+        let mut frequencies = Vec::new();
+        for (i, first) in dna::BASES.iter().enumerate() {
+            for (j, second) in dna::BASES.iter().enumerate() {
+                let name = String::from_utf8(vec![*first, *second]).unwrap_or_default();
+                frequencies.push((name, ratio(self.dinucleotides[i][j], total)));
+            }
+        }
+        frequencies
+    }
+
+    /// Returns the header of the table
+    pub fn header() -> String {
+        let dinucleotides: Vec<String> = Self::new()
+            .dinucleotide_frequencies()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        format!("{}\t{}", TABLE_COLUMNS, dinucleotides.join("\t"))
+    }
+
+    /// Format as a row of the table
+    pub fn to_row(&self, name: &str) -> String {
+        let mut fields = vec![name.to_owned(), self.length().to_string()];
+        fields.extend(b"ACGTN".iter().map(|base| self.count(*base).to_string()));
+        fields.push(self.other().to_string());
+        fields.push(format!("{:.2}", self.gc_content()));
+        fields.push(format!("{:.4}", self.gc_skew()));
+        fields.push(format!("{:.4}", self.masked_fraction()));
+        fields.extend(
+            self.dinucleotide_frequencies()
+                .iter()
+                .map(|(_, frequency)| format!("{:.4}", frequency)),
+        );
+        fields.join("\t")
+    }
+
+    /// Format as indented text under the name
+    pub fn to_text(&self, name: &str) -> String {
+        let composition: Vec<String> = b"ACGTN"
+            .iter()
+            .map(|base| format!("{}={}", *base as char, self.count(*base)))
+            .chain(std::iter::once(format!("other={}", self.other())))
+            .collect();
+        let dinucleotides: Vec<String> = self
+            .dinucleotide_frequencies()
+            .iter()
+            .map(|(name, frequency)| format!("{}={:.4}", name, frequency))
+            .collect();
+        [
+            name.to_owned(),
+            field("length", self.length()),
+            field("composition", composition.join(" ")),
+            field("gc", format!("{:.2}%", self.gc_content())),
+            field("gc skew", format!("{:.4}", self.gc_skew())),
+            field("masked", format!("{:.2}%", 100.0 * self.masked_fraction())),
+            field("dinucleotides", dinucleotides.join(" ")),
+        ]
+        .join("\n")
+    }
+}
+
+impl From<&[u8]> for Stats {
+    fn from(sequence: &[u8]) -> Self {
+        let mut stats = Self::new();
+        stats.add(sequence);
+        stats
+    }
+}
+
+/// Statistics of every sequence in a file
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    total: Stats,
+    lengths: Vec<usize>,
+}
+
+impl Summary {
+    /// Construct an empty summary
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the statistics of a sequence
+    pub fn add(&mut self, stats: &Stats) {
+        self.lengths.push(stats.length());
+        self.total.merge(stats);
+    }
+
+    /// Returns the statistics of every sequence added together
+    pub fn total(&self) -> &Stats {
+        &self.total
+    }
+
+    /// Returns the number of sequences
+    pub fn records(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Returns the length of the shortest sequence
+    pub fn min_length(&self) -> usize {
+        self.lengths.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Returns the length of the longest sequence
+    pub fn max_length(&self) -> usize {
+        self.lengths.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the mean length of the sequences
+    pub fn mean_length(&self) -> f64 {
+        ratio(self.total.length(), self.records())
+    }
+
+    /// Returns the N50 and L50
+    ///
+    /// The N50 is the length of the shortest sequence among the longest sequences that make up at
+    /// least half of the total length, and the L50 is the number of those sequences.
+    ///
+    pub fn n50(&self) -> (usize, usize) {
+        let mut lengths = self.lengths.clone();
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        let total: usize = lengths.iter().sum();
+        let mut cumulative = 0;
+        for (i, length) in lengths.iter().enumerate() {
+            cumulative += length;
+            if 2 * cumulative >= total {
+                return (*length, i + 1);
+            }
+        }
+        (0, 0)
+    }
+
+    /// Returns the length histogram as the lowest length, highest length and count of each bin
+    ///
+    /// Bins grow by powers of ten, so they are 0, 1-9, 10-99 and so on.  Empty bins are skipped.
+    ///
+    pub fn histogram(&self) -> Vec<(usize, usize, usize)> {
+        let mut bins = std::collections::BTreeMap::new();
+        for length in &self.lengths {
+            let low = match length {
+                0 => 0,
+                length => 10usize.pow(length.ilog10()),
+            };
+            *bins.entry(low).or_insert(0) += 1;
+        }
+        bins.into_iter()
+            .map(|(low, count)| (low, (low * 10).saturating_sub(1), count))
+            .collect()
+    }
+
+    /// Format as a two column table of metrics
+    pub fn to_rows(&self) -> String {
+        let (n50, l50) = self.n50();
+        let mut rows = vec![
+            "metric\tvalue".to_owned(),
+            format!("records\t{}", self.records()),
+            format!("total_length\t{}", self.total.length()),
+            format!("min_length\t{}", self.min_length()),
+            format!("max_length\t{}", self.max_length()),
+            format!("mean_length\t{:.2}", self.mean_length()),
+            format!("n50\t{}", n50),
+            format!("l50\t{}", l50),
+        ];
+        rows.extend(
+            self.histogram()
+                .iter()
+                .map(|(low, high, count)| format!("length_{}-{}\t{}", low, high, count)),
+        );
+        rows.join("\n")
+    }
+
+    /// Format as indented text
+    pub fn to_text(&self) -> String {
+        let (n50, l50) = self.n50();
+        let mut lines = vec![
+            self.total.to_text(TOTAL),
+            field("records", self.records()),
+            field("min length", self.min_length()),
+            field("max length", self.max_length()),
+            field("mean length", format!("{:.2}", self.mean_length())),
+            field("N50", n50),
+            field("L50", l50),
+            "  histogram".to_owned(),
+        ];
+        lines.extend(
+            self.histogram().iter().map(|(low, high, count)| {
+                format!("    {:<15}{}", format!("{}-{}", low, high), count)
+            }),
+        );
+        lines.join("\n")
+    }
+}
+
+/// Return the index of an unambiguous base in either case
+fn base_index(base: &u8) -> Option<usize> {
+    dna::BASES
+        .iter()
+        .position(|b| *b == base.to_ascii_uppercase())
+}
+
+/// Return the ratio of two counts, or zero if the denominator is zero
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    match denominator {
+        0 => 0.0,
+        denominator => numerator as f64 / denominator as f64,
+    }
+}
+
+/// Write the statistics of every fasta or fastq record of the input, followed by the whole input
+///
+/// The statistics are written as text, or as tab separated tables with `tsv`.  With `summary`,
+/// only the statistics of the whole input are written.
+///
+pub fn write_report<W>(input: &Input, tsv: bool, summary: bool, mut writer: W) -> Result<()>
+where
+    W: std::io::Write,
+{
+    if tsv {
+        writeln!(writer, "{}", Stats::header())?;
+    }
+    let mut total = Summary::new();
+    input.for_each_sequence(|name, sequence| {
+        let stats = Stats::from(sequence);
+        if !summary {
+            match tsv {
+                true => writeln!(writer, "{}", stats.to_row(name))?,
+                false => writeln!(writer, "{}\n", stats.to_text(name))?,
+            }
+        }
+        total.add(&stats);
+        Ok(())
+    })?;
+    match tsv {
+        true => writeln!(
+            writer,
+            "{}\n\n{}",
+            total.total().to_row(TOTAL),
+            total.to_rows()
+        )?,
+        false => writeln!(writer, "{}", total.to_text())?,
+    }
+    Ok(())
+}
+
+/// Format a labelled field of the text output
+fn field<T>(label: &str, value: T) -> String
+where
+    T: std::fmt::Display,
+{
+    format!("  {:<17}{}", label, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from(&b"GGCAnnRT"[..]);
+        assert_eq!(
+            (8, 2, 1, 1, 2, 1),
+            (
+                stats.length(),
+                stats.count(b'G'),
+                stats.count(b'C'),
+                stats.count(b'A'),
+                stats.count(b'N'),
+                stats.other()
+            ),
+            "{}",
+            "Should count symbols in either case"
+        );
+        assert_eq!(
+            (60.0, 1.0 / 3.0, 0.25),
+            (stats.gc_content(), stats.gc_skew(), stats.masked_fraction()),
+            "{}",
+            "Should compute gc content, gc skew and masked fraction"
+        );
+        let frequencies = stats.dinucleotide_frequencies();
+        assert_eq!(
+            (("CA".to_owned(), 1.0 / 3.0), ("GG".to_owned(), 1.0 / 3.0)),
+            (frequencies[4].clone(), frequencies[10].clone()),
+            "{}",
+            "Should only count dinucleotides of unambiguous bases"
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: &'a [&'a str],
+            expected: (usize, usize, (usize, usize)),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should summarize the lengths",
+                sequences: &["ACGTACGTAC", "ACGT", "ACG", "A"],
+                expected: (1, 10, (10, 1)),
+            },
+            TestCase {
+                name: "Should count the sequences needed to reach half of the length",
+                sequences: &["AAAA", "CCC", "GGG", "T"],
+                expected: (1, 4, (3, 2)),
+            },
+            TestCase {
+                name: "Should summarize no sequences",
+                sequences: &[],
+                expected: (0, 0, (0, 0)),
+            },
+        ];
+        for test_case in test_cases {
+            let mut summary = Summary::new();
+            for sequence in test_case.sequences {
+                summary.add(&Stats::from(sequence.as_bytes()));
+            }
+            assert_eq!(
+                test_case.expected,
+                (summary.min_length(), summary.max_length(), summary.n50()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_summary_histogram() {
+        let mut summary = Summary::new();
+        for sequence in ["", "A", "ACGTACGTAC", "ACGTACGTACG", "ACG"] {
+            summary.add(&Stats::from(sequence.as_bytes()));
+        }
+        assert_eq!(
+            vec![(0, 0, 1), (1, 9, 2), (10, 99, 2)],
+            summary.histogram(),
+            "{}",
+            "Should bin lengths by powers of ten"
+        );
+    }
+
+    #[test]
+    fn test_write_report() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: &'a str,
+            summary: bool,
+            expected: &'a [&'a str],
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should write a row per record followed by the total",
+                input: ">a\nACGTN\n>b\nGG\n",
+                summary: false,
+                expected: &[
+                    "name",
+                    "a",
+                    "b",
+                    "total",
+                    "",
+                    "metric",
+                    "records",
+                    "total_length",
+                ],
+            },
+            TestCase {
+                name: "Should only write the total of fastq records",
+                input: "@a\nACGTN\n+\nIIIII\n",
+                summary: true,
+                expected: &["name", "total", "", "metric", "records", "total_length"],
+            },
+        ];
+        for test_case in test_cases {
+            let mut output = Vec::new();
+            write_report(
+                &Input::Text(test_case.input.to_owned()),
+                true,
+                test_case.summary,
+                &mut output,
+            )
+            .unwrap();
+            let output = String::from_utf8(output).unwrap();
+            let names: Vec<&str> = output
+                .lines()
+                .take(test_case.expected.len())
+                .map(|line| line.split('\t').next().unwrap_or_default())
+                .collect();
+            assert_eq!(test_case.expected, names, "{}", test_case.name);
+        }
+        let mut output = Vec::new();
+        write_report(
+            &Input::Text(">a\nACGTN\n".to_owned()),
+            false,
+            false,
+            &mut output,
+        )
+        .unwrap();
+        assert!(
+            String::from_utf8(output)
+                .unwrap()
+                .starts_with("a\n  length           5\n"),
+            "{}",
+            "Should write text"
+        );
+    }
+}
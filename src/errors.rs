@@ -2,10 +2,101 @@
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// The kind of error
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ErrorKind {
+    /// Reading or writing failed
     IO,
+    /// The command line was used incorrectly
     User,
+    /// The input could not be parsed
+    Parse,
+    /// The input was parsed but does not satisfy the problem constraints
+    Validation,
+    /// The input contains a symbol outside of the expected alphabet
+    Alphabet,
+    /// A network request failed
+    Network,
+    /// A number does not fit in its type
+    Overflow,
+}
+
+impl ErrorKind {
+    /// Process exit code for the kind of error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::User => 2,
+            Self::IO => 3,
+            Self::Parse => 4,
+            Self::Validation => 5,
+            Self::Alphabet => 6,
+            Self::Network => 7,
+            Self::Overflow => 8,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Self::IO => "io",
+            Self::User => "usage",
+            Self::Parse => "parse",
+            Self::Validation => "validation",
+            Self::Alphabet => "alphabet",
+            Self::Network => "network",
+            Self::Overflow => "overflow",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Location of an error in the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number
+    pub column: usize,
+    /// Content of the offending line
+    pub text: String,
+}
+
+impl Location {
+    /// Constructor for Location
+    pub fn new(line: usize, column: usize, text: &str) -> Self {
+        Self {
+            line,
+            column,
+            text: text.into(),
+        }
+    }
+
+    /// Return the location of a byte offset within the input
+    pub fn from_offset(input: &str, offset: usize) -> Self {
+        let offset = offset.min(input.len());
+        let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        Self::new(
+            input[..start].matches('\n').count() + 1,
+            input[start..offset].chars().count() + 1,
+            input[start..end].trim_end_matches('\r'),
+        )
+    }
+
+    /// Return the location of a token borrowed from the input
+    ///
+    /// None is returned if the token is not a slice of the input.
+    ///
+    pub fn of_token(input: &str, token: &str) -> Option<Self> {
+        let start = input.as_ptr() as usize;
+        let token_start = token.as_ptr() as usize;
+        if token_start < start || token_start + token.len() > start + input.len() {
+            return None;
+        }
+        Some(Self::from_offset(input, token_start - start))
+    }
 }
 
 /// Custom error type for the crate
@@ -13,13 +104,23 @@ pub enum ErrorKind {
 pub struct Error {
     kind: ErrorKind,
     message: String,
+    file: Option<String>,
+    location: Option<Location>,
 }
 
 impl std::error::Error for Error {}
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "kind: {:?}, message: {}", self.kind, self.message)
+        write!(f, "{} error: {}", self.kind, self.message)?;
+        match (&self.file, &self.location) {
+            (Some(file), Some(location)) => {
+                write!(f, " at {}:{}:{}", file, location.line, location.column)
+            }
+            (None, Some(location)) => write!(f, " at {}:{}", location.line, location.column),
+            (Some(file), None) => write!(f, " in {}", file),
+            (None, None) => Ok(()),
+        }
     }
 }
 
@@ -29,35 +130,159 @@ impl Error {
         Error {
             kind,
             message: message.into(),
+            file: None,
+            location: None,
         }
     }
 
+    /// Attach the location of the error in the input
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Attach the name of the input the error occurred in, unless one is already attached
+    pub fn in_file(mut self, file: &str) -> Self {
+        if self.file.is_none() {
+            self.file = Some(file.into());
+        }
+        self
+    }
+
     /// Returns the kind of error
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
     }
+
+    /// Returns the error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the name of the input the error occurred in
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the location of the error in the input
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Returns the process exit code for the error
+    pub fn exit_code(&self) -> i32 {
+        self.kind.exit_code()
+    }
 }
 
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
-        Self::new(ErrorKind::IO, &e.to_string())
+        match e.kind() {
+            std::io::ErrorKind::InvalidData => Self::new(ErrorKind::Parse, &e.to_string()),
+            _ => Self::new(ErrorKind::IO, &e.to_string()),
+        }
     }
 }
 
 impl From<std::num::ParseIntError> for Error {
     fn from(e: std::num::ParseIntError) -> Self {
-        Self::new(ErrorKind::IO, &e.to_string())
+        match e.kind() {
+            std::num::IntErrorKind::PosOverflow | std::num::IntErrorKind::NegOverflow => {
+                Self::new(ErrorKind::Overflow, &e.to_string())
+            }
+            _ => Self::new(ErrorKind::Parse, &e.to_string()),
+        }
+    }
+}
+
+impl From<std::num::ParseFloatError> for Error {
+    fn from(e: std::num::ParseFloatError) -> Self {
+        Self::new(ErrorKind::Parse, &e.to_string())
     }
 }
 
 impl From<std::string::FromUtf8Error> for Error {
     fn from(e: std::string::FromUtf8Error) -> Self {
-        Self::new(ErrorKind::IO, &e.to_string())
+        Self::new(ErrorKind::Parse, &e.to_string())
     }
 }
 
 impl From<std::str::Utf8Error> for Error {
     fn from(e: std::str::Utf8Error) -> Self {
+        Self::new(ErrorKind::Parse, &e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
         Self::new(ErrorKind::IO, &e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_from_offset() {
+        struct TestCase<'a> {
+            name: &'a str,
+            input: &'a str,
+            offset: usize,
+            expected: Location,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should locate an offset on the first line",
+                input: "5 x\n7",
+                offset: 2,
+                expected: Location::new(1, 3, "5 x"),
+            },
+            TestCase {
+                name: "Should locate an offset on a later line",
+                input: "5 3\nA C\nx",
+                offset: 8,
+                expected: Location::new(3, 1, "x"),
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                Location::from_offset(test_case.input, test_case.offset),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_location_of_token() {
+        let input = "12 ab\n34";
+        let token = input.split_whitespace().nth(1).unwrap();
+        assert_eq!(
+            Some(Location::new(1, 4, "12 ab")),
+            Location::of_token(input, token),
+            "{}",
+            "Should locate a token borrowed from the input",
+        );
+        assert_eq!(
+            None,
+            Location::of_token(input, "ab"),
+            "{}",
+            "Should return None for a token that is not borrowed from the input",
+        );
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = Error::new(ErrorKind::Parse, "invalid digit found in string")
+            .with_location(Location::new(1, 3, "5 x"))
+            .in_file("fib.txt");
+        assert_eq!(
+            "parse error: invalid digit found in string at fib.txt:1:3",
+            error.to_string(),
+            "{}",
+            "Should display the kind, message, and location",
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Rust solutions to Rosalind problems (https://rosalind.info)
+//!
+//! The crate is split into the following modules:
+//!
+//! ```text
+//! common   - shared building blocks such as the fasta reader and typed sequences
+//! errors   - the error and result types used throughout the crate
+//! problems - one module per Rosalind problem, each exposing a typed `solve` function
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use rosalind_rust::common::seq::DnaSeq;
+//! use rosalind_rust::problems::hamm;
+//!
+//! let seq1: DnaSeq = "GAGCCTACTAACGGGAT".parse().unwrap();
+//! let seq2: DnaSeq = "CATCGTAATGACGGCCT".parse().unwrap();
+//! let distance = hamm::solve(&seq1, &seq2).unwrap();
+//! assert_eq!(7, distance);
+//! ```
+//!
+extern crate clap;
+extern crate itertools;
+extern crate num_bigint;
+extern crate num_traits;
+extern crate reqwest;
+extern crate serde;
+extern crate serde_json;
+
+pub mod common;
+pub mod errors;
+pub mod problems;
@@ -0,0 +1,103 @@
+//! Construct the Burrows-Wheeler Transform of a String (https://rosalind.info/problems/ba9i/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: A string Text.
+//!
+//! Return: BWT(Text).
+//! ```
+//!
+use crate::common;
+use crate::common::fmindex;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
+
+/// Rosalind problem BA9I
+pub struct Ba9i;
+
+impl Problem for Ba9i {
+    type Input = String;
+    type Output = String;
+
+    const ID: &'static str = "ba9i";
+    const TITLE: &'static str = "Construct the Burrows-Wheeler Transform of a String";
+    const SAMPLE_DATASET: &'static str = "
+        GCGTGCCTGGTCA$
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        ACTGGCT$TGCGGC
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_simple(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_owned()
+    }
+}
+
+/// Solve BA9I
+///
+/// Return the Burrows-Wheeler transform of the text, which must end with its only '$'.
+///
+pub fn solve(text: &str) -> Result<String> {
+    let sentinel = fmindex::SENTINEL as char;
+    if text.find(sentinel) != Some(text.len().wrapping_sub(1)) {
+        return Err(Error::new(
+            ErrorKind::Validation,
+            &format!("expected a text ending with its only '{}'", sentinel),
+        ));
+    }
+    Ok(String::from_utf8(fmindex::bwt(text.as_bytes()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            expected: Option<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should transform the text",
+                text: "GCGTGCCTGGTCA$",
+                expected: Some("ACTGGCT$TGCGGC"),
+            },
+            TestCase {
+                name: "Should reject a text without a sentinel",
+                text: "GCGTGCCTGGTCA",
+                expected: None,
+            },
+            TestCase {
+                name: "Should reject a text with a sentinel inside",
+                text: "GC$GT$",
+                expected: None,
+            },
+            TestCase {
+                name: "Should reject an empty text",
+                text: "",
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected.map(String::from),
+                solve(test_case.text).ok(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Find All Occurrences of a Collection of Patterns in a String
+//! (https://rosalind.info/problems/ba9n/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: A string Text and a collection of strings Patterns.
+//!
+//! Return: All starting positions in Text where a string from Patterns appears as a substring.
+//! ```
+//!
+use crate::common;
+use crate::common::fmindex::FmIndex;
+use crate::common::seq::DnaSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+/// Rosalind problem BA9N
+pub struct Ba9n;
+
+impl Problem for Ba9n {
+    type Input = (DnaSeq, Vec<DnaSeq>);
+    type Output = Vec<usize>;
+
+    const ID: &'static str = "ba9n";
+    const TITLE: &'static str = "Find All Occurrences of a Collection of Patterns in a String";
+    const SAMPLE_DATASET: &'static str = "
+        AATCGGGTTCAATCGGGGT
+        ATCG
+        GGGT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        1 4 11 15
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let input = common::load_simple(&input)?;
+        let (text, patterns) = input
+            .split_once('\n')
+            .ok_or_else(|| common::invalid_input("expected a text and patterns"))?;
+        Ok((
+            common::parse_token(&input, text.trim())?,
+            patterns
+                .split_whitespace()
+                .map(|pattern| common::parse_token(&input, pattern))
+                .collect::<Result<_>>()?,
+        ))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
+}
+
+/// Format output
+fn format_output(starts: &[usize]) -> String {
+    starts
+        .iter()
+        .map(|start| start.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Solve BA9N
+///
+/// Return the sorted 0-based starts of every occurrence of every pattern in the text.
+///
+pub fn solve(text: &DnaSeq, patterns: &[DnaSeq]) -> Result<Vec<usize>> {
+    let index = FmIndex::new(&[text])?;
+    let mut starts: Vec<usize> = patterns
+        .iter()
+        .flat_map(|pattern| index.locate(pattern.as_bytes()))
+        .map(|hit| hit.start)
+        .collect();
+    starts.sort_unstable();
+    Ok(starts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            patterns: Vec<&'a str>,
+            expected: Vec<usize>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find every occurrence of every pattern",
+                text: "AATCGGGTTCAATCGGGGT",
+                patterns: vec!["ATCG", "GGGT"],
+                expected: vec![1, 4, 11, 15],
+            },
+            TestCase {
+                name: "Should report overlapping occurrences",
+                text: "AAAA",
+                patterns: vec!["AA"],
+                expected: vec![0, 1, 2],
+            },
+            TestCase {
+                name: "Should report a start once per matching pattern",
+                text: "ACGT",
+                patterns: vec!["AC", "ACG", "TT"],
+                expected: vec![0, 0],
+            },
+        ];
+        for test_case in test_cases {
+            let text = test_case.text.parse().unwrap();
+            let patterns: Vec<DnaSeq> = test_case
+                .patterns
+                .iter()
+                .map(|pattern| pattern.parse().unwrap())
+                .collect();
+            assert_eq!(
+                test_case.expected,
+                solve(&text, &patterns).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Find All Approximate Occurrences of a Collection of Patterns in a String
+//! (https://rosalind.info/problems/ba9o/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: A string Text, a collection of strings Patterns, and an integer d.
+//!
+//! Return: All positions in Text where a string from Patterns appears as a substring with at
+//!         most d mismatches.
+//! ```
+//!
+use crate::common;
+use crate::common::fmindex::FmIndex;
+use crate::common::seq::DnaSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+/// Rosalind problem BA9O
+pub struct Ba9o;
+
+impl Problem for Ba9o {
+    type Input = (DnaSeq, Vec<DnaSeq>, usize);
+    type Output = Vec<usize>;
+
+    const ID: &'static str = "ba9o";
+    const TITLE: &'static str =
+        "Find All Approximate Occurrences of a Collection of Patterns in a String";
+    const SAMPLE_DATASET: &'static str = "
+        ACATGCTACTTT
+        ATT GCC GCTA TATT
+        1
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        2 4 4 6 7 8 9
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let input = common::load_simple(&input)?;
+        let lines: Vec<&str> = input.lines().map(str::trim).collect();
+        let [text, patterns, mismatches] = lines[..] else {
+            return Err(common::invalid_input("expected three lines"));
+        };
+        Ok((
+            common::parse_token(&input, text)?,
+            patterns
+                .split_whitespace()
+                .map(|pattern| common::parse_token(&input, pattern))
+                .collect::<Result<_>>()?,
+            common::parse_token(&input, mismatches)?,
+        ))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1, input.2)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
+}
+
+/// Format output
+fn format_output(starts: &[usize]) -> String {
+    starts
+        .iter()
+        .map(|start| start.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Solve BA9O
+///
+/// Return the sorted 0-based starts of every substring of the text within the mismatches of a
+/// pattern.
+///
+pub fn solve(text: &DnaSeq, patterns: &[DnaSeq], mismatches: usize) -> Result<Vec<usize>> {
+    let index = FmIndex::new(&[text])?;
+    let mut starts: Vec<usize> = patterns
+        .iter()
+        .flat_map(|pattern| index.locate_approximate(pattern.as_bytes(), mismatches))
+        .map(|hit| hit.start)
+        .collect();
+    starts.sort_unstable();
+    Ok(starts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            text: &'a str,
+            patterns: Vec<&'a str>,
+            mismatches: usize,
+            expected: Vec<usize>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find every approximate occurrence",
+                text: "ACATGCTACTTT",
+                patterns: vec!["ATT", "GCC", "GCTA", "TATT"],
+                mismatches: 1,
+                expected: vec![2, 4, 4, 6, 7, 8, 9],
+            },
+            TestCase {
+                name: "Should find exact occurrences without mismatches",
+                text: "ACATGCTACTTT",
+                patterns: vec!["ATT", "GCTA"],
+                mismatches: 0,
+                expected: vec![4],
+            },
+            TestCase {
+                name: "Should match every window when mismatches cover the pattern",
+                text: "ACGT",
+                patterns: vec!["TT"],
+                mismatches: 2,
+                expected: vec![0, 1, 2],
+            },
+        ];
+        for test_case in test_cases {
+            let text = test_case.text.parse().unwrap();
+            let patterns: Vec<DnaSeq> = test_case
+                .patterns
+                .iter()
+                .map(|pattern| pattern.parse().unwrap())
+                .collect();
+            assert_eq!(
+                test_case.expected,
+                solve(&text, &patterns, test_case.mismatches).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -2,132 +2,292 @@
 //!
 //! # Problem
 //!
-//!     Given: A collection of at most 10 DNA strings of equal length (at most 1 kbp) in FASTA
-//!             format
+//! ```text
+//! Given: A collection of at most 10 DNA strings of equal length (at most 1 kbp) in FASTA
+//!         format
 //!
-//!     Return: A consensus string and profile matrix for the collection.  (If several possible
-//!             consensus strings exist, you may return any one of them.)
+//! Return: A consensus string and profile matrix for the collection.  (If several possible
+//!         consensus strings exist, you may return any one of them.)
+//! ```
 //!
-//! # Sample Dataset
-//!     >Rosalind_1
-//!     ATCCAGCT
-//!     >Rosalind_2
-//!     GGGCAACT
-//!     >Rosalind_3
-//!     ATGGATCT
-//!     >Rosalind_4
-//!     AAGCAACC
-//!     >Rosalind_5
-//!     TTGGAACT
-//!     >Rosalind_6
-//!     ATGCCATT
-//!     >Rosalind_7
-//!     ATGGCACT
-//!
-//! # Sample Output
-//!     ATGCAACT
-//!     A: 5 1 0 0 5 5 0 0
-//!     C: 0 0 1 4 2 0 6 1
-//!     G: 1 1 6 3 0 1 0 0
-//!     T: 1 5 0 0 0 1 1 6
+//! The strings may be aligned and contain gaps, which are counted in a row of their own.  With
+//! `--align`, the strings may have different lengths and are aligned by progressive multiple
+//! alignment first.  With `--protein`, the strings are protein instead of DNA, and `--matrix`
+//! replaces the counts with frequencies, a position weight matrix or information content.
 //!
 use crate::common;
-use crate::errors::Result;
+use crate::common::align::{self, GAP};
+use crate::common::fasta::Record;
+use crate::common::msa;
+use crate::common::profile::Profile;
+use crate::common::seq::{Alphabet, Dna, Protein, Seq};
+use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
 use std::collections::BTreeMap;
 
-pub const SUBCOMMAND: &str = "cons";
+const ALIGN_ARG: &str = "align";
+const PROTEIN_ARG: &str = "protein";
+const MATRIX_ARG: &str = "matrix";
 
-/// Return subcommand for CONS
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem CONS
+pub struct Cons;
 
-/// Run the cons workflow
-///
-/// Run the workflow by loading the fasta file and incrementing a counter per sequence.
-///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let reader = common::fasta::Reader::new(std::fs::File::open(path)?);
-        let mut counter = Counter::new(0);
-        for record in reader.iter() {
-            let sequence = record?.sequence;
-            if counter.sequence_length == 0 {
-                counter = Counter::new(sequence.len());
-            }
-            counter.update(&sequence);
-        }
-        println!("{}", counter.format_output()?);
-        return Ok(());
+impl Problem for Cons {
+    type Input = (Vec<Record>, Options);
+    type Output = Output;
+
+    const ID: &'static str = "cons";
+    const TITLE: &'static str = "Consensus and Profile";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_1
+        ATCCAGCT
+        >Rosalind_2
+        GGGCAACT
+        >Rosalind_3
+        ATGGATCT
+        >Rosalind_4
+        AAGCAACC
+        >Rosalind_5
+        TTGGAACT
+        >Rosalind_6
+        ATGCCATT
+        >Rosalind_7
+        ATGGCACT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        ATGCAACT
+        A: 5 1 0 0 5 5 0 0
+        C: 0 0 1 4 2 0 6 1
+        G: 1 1 6 3 0 1 0 0
+        T: 1 5 0 0 0 1 1 6
+    ";
+
+    fn command() -> clap::Command<'static> {
+        common::with_pseudocount(common::subcommand_file(Self::ID))
+            .arg(
+                clap::Arg::new(ALIGN_ARG)
+                    .long(ALIGN_ARG)
+                    .help("Align the strings first, allowing them to have different lengths"),
+            )
+            .arg(
+                clap::Arg::new(PROTEIN_ARG)
+                    .long(PROTEIN_ARG)
+                    .help("Read protein strings instead of DNA strings"),
+            )
+            .arg(
+                clap::Arg::new(MATRIX_ARG)
+                    .long(MATRIX_ARG)
+                    .takes_value(true)
+                    .possible_values(Matrix::NAMES)
+                    .default_value("counts")
+                    .help("Matrix reported after the consensus"),
+            )
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let options = Options {
+            align: matches.contains_id(ALIGN_ARG),
+            protein: matches.contains_id(PROTEIN_ARG),
+            matrix: matches.value_of(MATRIX_ARG).unwrap_or_default().parse()?,
+            pseudocount: common::pseudocount(matches)?,
+        };
+        Ok((common::load_fasta(&input)?, options))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
-/// Counter for bases
-#[derive(Debug, PartialEq)]
-struct Counter {
-    counter: BTreeMap<u8, Vec<usize>>,
-    sequence_length: usize,
+/// Options beyond the Rosalind problem
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Align the strings before counting
+    pub align: bool,
+    /// Read protein strings instead of DNA strings
+    pub protein: bool,
+    /// Matrix reported after the consensus
+    pub matrix: Matrix,
+    /// Pseudocount of the position weight matrix
+    pub pseudocount: f64,
 }
 
-impl Counter {
-    /// Construct a new counter
-    fn new(sequence_length: usize) -> Self {
+impl Default for Options {
+    fn default() -> Self {
         Self {
-            counter: BTreeMap::from([
-                (b'A', vec![0; sequence_length]),
-                (b'C', vec![0; sequence_length]),
-                (b'G', vec![0; sequence_length]),
-                (b'T', vec![0; sequence_length]),
-            ]),
-            sequence_length,
+            align: false,
+            protein: false,
+            matrix: Matrix::Counts,
+            pseudocount: common::profile::PSEUDOCOUNT,
         }
     }
+}
 
-    /// Update counter with a sequence
-    fn update(&mut self, sequence: &str) {
-        let seq = sequence.as_bytes();
-        for index in 0..std::cmp::max(self.sequence_length, seq.len()) {
-            if let Some(counter) = self.counter.get_mut(&seq[index]) {
-                counter[index] += 1;
-            }
+/// Matrix computed from the profile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Matrix {
+    Counts,
+    Frequency,
+    Pwm,
+    Information,
+}
+
+impl Matrix {
+    /// Names accepted on the command line
+    pub const NAMES: [&'static str; 4] = ["counts", "frequency", "pwm", "information"];
+}
+
+impl std::str::FromStr for Matrix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "counts" => Ok(Self::Counts),
+            "frequency" => Ok(Self::Frequency),
+            "pwm" => Ok(Self::Pwm),
+            "information" => Ok(Self::Information),
+            _ => Err(Error::new(
+                ErrorKind::User,
+                &format!("unknown matrix: {}", s),
+            )),
         }
     }
+}
 
-    /// Return the consensus sequence
-    fn consensus(&self) -> Result<String> {
-        let mut consensus = vec![b'A'; self.sequence_length];
-        for (index, base) in consensus.iter_mut().enumerate().take(self.sequence_length) {
-            let mut max_count = 0;
-            for b in [b'A', b'C', b'G', b'T'] {
-                let count = self
-                    .counter
-                    .get(&b)
-                    .unwrap_or(&vec![0; self.sequence_length])[index];
-                if count > max_count {
-                    *base = b;
-                    max_count = count;
-                }
-            }
+/// Solve CONS
+///
+/// Return the consensus string and profile matrix of a collection of equal length strings.
+///
+pub fn solve(records: &[Record], options: &Options) -> Result<Output> {
+    match options.protein {
+        true => summarize::<Protein>(records, options, &msa::PROTEIN),
+        false => summarize::<Dna>(records, options, &msa::DNA),
+    }
+}
+
+/// Return the consensus string and matrix of the strings over the alphabet
+fn summarize<A>(records: &[Record], options: &Options, scoring: &align::Scoring) -> Result<Output>
+where
+    A: Alphabet,
+{
+    let aligned;
+    let records = match options.align {
+        true => {
+            aligned = align_records::<A>(records, scoring)?;
+            &aligned
         }
-        let consensus = std::str::from_utf8(&consensus)?;
-        Ok(consensus.to_owned())
+        false => records,
+    };
+    let length = records.first().map(|r| r.sequence.len()).unwrap_or(0);
+    let mut profile = Profile::<A>::new(length);
+    for record in records {
+        profile
+            .add(record.sequence.as_bytes())
+            .map_err(|e| Error::new(*e.kind(), &format!("{} in {}", e.message(), record.name)))?;
     }
+    let profile_rows = match options.matrix {
+        Matrix::Counts => {
+            let mut rows = rows::<A, usize>(profile.counts());
+            if profile.has_gaps() {
+                rows.insert(char::from(GAP), profile.gaps().to_vec());
+            }
+            Rows::Counts(rows)
+        }
+        Matrix::Frequency => Rows::Scores(rows::<A, f64>(&profile.frequencies())),
+        Matrix::Pwm => Rows::Scores(rows::<A, f64>(profile.pwm(options.pseudocount).scores())),
+        Matrix::Information => Rows::Scores(rows::<A, f64>(&profile.information_content())),
+    };
+    Ok(Output {
+        consensus: profile.consensus(),
+        profile: profile_rows,
+    })
+}
+
+/// Align the strings by progressive multiple alignment, ignoring any gaps they already have
+fn align_records<A>(records: &[Record], scoring: &align::Scoring) -> Result<Vec<Record>>
+where
+    A: Alphabet,
+{
+    let sequences = records
+        .iter()
+        .map(|record| {
+            let sequence = record.sequence.replace(char::from(GAP), "");
+            Record::with_sequence(&record.name, &sequence).parse_sequence::<Seq<A>>()
+        })
+        .collect::<Result<Vec<Seq<A>>>>()?;
+    let sequences: Vec<&[u8]> = sequences.iter().map(|s| s.as_bytes()).collect();
+    let rows = msa::align(&sequences, scoring)?;
+    Ok(records
+        .iter()
+        .zip(rows)
+        .map(|(record, row)| Record::with_sequence(&record.name, &row))
+        .collect())
+}
+
+/// Return the values of every symbol of the alphabet from values per column
+fn rows<A, T>(columns: &[Vec<T>]) -> BTreeMap<char, Vec<T>>
+where
+    A: Alphabet,
+    T: Copy,
+{
+    A::SYMBOLS
+        .iter()
+        .enumerate()
+        .map(|(i, symbol)| {
+            (
+                char::from(*symbol),
+                columns.iter().map(|column| column[i]).collect(),
+            )
+        })
+        .collect()
+}
+
+/// Consensus string and profile matrix
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    /// The consensus string
+    pub consensus: String,
+    /// Value of each symbol per position
+    pub profile: Rows,
+}
+
+/// Rows of a profile matrix
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Rows {
+    /// Count of each symbol per position
+    Counts(BTreeMap<char, Vec<usize>>),
+    /// Score of each symbol per position
+    Scores(BTreeMap<char, Vec<f64>>),
+}
 
-    /// Format the counter output
-    fn format_output(&self) -> Result<String> {
-        let mut output = self.consensus()?;
-        for base in [b'A', b'C', b'G', b'T'] {
-            output = format!("{}\n{}:", output, base as char);
-            for count in self
-                .counter
-                .get(&base)
-                .unwrap_or(&vec![0; self.sequence_length])
-            {
-                output = format! {"{} {}", output, count};
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.consensus)?;
+        match &self.profile {
+            Rows::Counts(rows) => {
+                for (symbol, counts) in rows {
+                    write!(f, "\n{}:", symbol)?;
+                    for count in counts {
+                        write!(f, " {}", count)?;
+                    }
+                }
+            }
+            Rows::Scores(rows) => {
+                for (symbol, scores) in rows {
+                    write!(f, "\n{}:", symbol)?;
+                    for score in scores {
+                        write!(f, " {:.3}", score)?;
+                    }
+                }
             }
         }
-        Ok(output)
+        Ok(())
     }
 }
 
@@ -136,34 +296,62 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_counter_update() {
-        let mut counter = Counter::new(3);
-        counter.update("AGC");
-        assert_eq!(
-            Counter {
-                counter: BTreeMap::from([
-                    (b'A', vec![1, 0, 0]),
-                    (b'C', vec![0, 0, 1]),
-                    (b'G', vec![0, 1, 0]),
-                    (b'T', vec![0, 0, 0]),
-                ]),
-                sequence_length: 3,
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            options: Options,
+            expected: Result<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should count gaps in a row of their own",
+                sequences: vec!["AC-T", "AGGT"],
+                options: Options::default(),
+                expected: Ok("ACGT\n-: 0 0 1 0\nA: 2 0 0 0\nC: 0 1 0 0\nG: 0 1 1 0\nT: 0 0 0 2"),
             },
-            counter,
-        );
-    }
-
-    #[test]
-    fn test_counter_consensus() {
-        let counter = Counter {
-            counter: BTreeMap::from([
-                (b'A', vec![5, 1, 0, 0, 5, 5, 0, 0]),
-                (b'C', vec![0, 0, 1, 4, 2, 0, 6, 1]),
-                (b'G', vec![1, 1, 6, 3, 0, 1, 0, 0]),
-                (b'T', vec![1, 5, 0, 0, 0, 1, 1, 6]),
-            ]),
-            sequence_length: 8,
-        };
-        assert_eq!(counter.consensus(), Ok("ATGCAACT".to_owned()));
+            TestCase {
+                name: "Should reject ragged strings",
+                sequences: vec!["ACGT", "ACG"],
+                options: Options::default(),
+                expected: Err(Error::new(
+                    ErrorKind::Validation,
+                    "expected aligned sequences of length 4, found length 3 in seq2",
+                )),
+            },
+            TestCase {
+                name: "Should align ragged strings first",
+                sequences: vec!["ACGT", "ACT"],
+                options: Options {
+                    align: true,
+                    ..Options::default()
+                },
+                expected: Ok("ACGT\n-: 0 0 1 0\nA: 2 0 0 0\nC: 0 2 0 0\nG: 0 0 1 0\nT: 0 0 0 2"),
+            },
+            TestCase {
+                name: "Should report frequencies of protein strings",
+                sequences: vec!["MA", "MC"],
+                options: Options {
+                    protein: true,
+                    matrix: Matrix::Frequency,
+                    ..Options::default()
+                },
+                expected: Ok("MA\nA: 0.000 0.500\nC: 0.000 0.500\nD: 0.000 0.000\nE: 0.000 0.000\nF: 0.000 0.000\nG: 0.000 0.000\nH: 0.000 0.000\nI: 0.000 0.000\nK: 0.000 0.000\nL: 0.000 0.000\nM: 1.000 0.000\nN: 0.000 0.000\nP: 0.000 0.000\nQ: 0.000 0.000\nR: 0.000 0.000\nS: 0.000 0.000\nT: 0.000 0.000\nV: 0.000 0.000\nW: 0.000 0.000\nY: 0.000 0.000"),
+            },
+        ];
+        for test_case in test_cases {
+            let records: Vec<Record> = test_case
+                .sequences
+                .iter()
+                .enumerate()
+                .map(|(i, sequence)| Record::with_sequence(&format!("seq{}", i + 1), sequence))
+                .collect();
+            assert_eq!(
+                test_case.expected.map(|expected| expected.to_owned()),
+                solve(&records, &test_case.options).map(|output| output.to_string()),
+                "{}",
+                test_case.name
+            );
+        }
     }
 }
@@ -2,63 +2,92 @@
 //!
 //! # Problem
 //!
-//!     Given: A DNA string s of length at most 1000 nt.
+//! ```text
+//! Given: A DNA string s of length at most 1000 nt.
 //!
-//!     Return: Four integers (separated by spaces) counting the respective number of times that
-//!             the symbols 'A', 'C', 'G', and 'T' occur in s.
+//! Return: Four integers (separated by spaces) counting the respective number of times that
+//!         the symbols 'A', 'C', 'G', and 'T' occur in s.
+//! ```
 //!
-//! # Sample Dataset
-//!     AGCTTTTCATTCTGACTGCAACGGGCAATATGTCTCTGTGTGGATTAAAAAAAGAGTGTCTGATAGCAGC
-//!
-//! # Sample Output
-//!     20 12 17 21
+//! The JSON answer also reports the ambiguity codes and soft-masked bases of the input.
 //!
 use crate::common;
+use crate::common::dna::{self, Report};
+use crate::common::seq::IupacSeq;
 use crate::errors::Result;
+use crate::problems::Problem;
 use std::collections::BTreeMap;
 
-pub const SUBCOMMAND: &str = "dna";
+/// Rosalind problem DNA
+pub struct Dna;
+
+impl Problem for Dna {
+    type Input = (IupacSeq, Report);
+    type Output = Counts;
+
+    const ID: &'static str = "dna";
+    const TITLE: &'static str = "Counting DNA Nucleotides";
+    const SAMPLE_DATASET: &'static str = "
+        AGCTTTTCATTCTGACTGCAACGGGCAATATGTCTCTGTGTGGATTAAAAAAAGAGTGTCTGATAGCAGC
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        20 12 17 21
+    ";
+
+    /// The report is taken from the input as given, before the bases are upper cased
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let sequence = common::load_simple(&input)?;
+        let dna_string = common::parse_token(&sequence, &sequence)?;
+        Ok((dna_string, dna::report(sequence.as_bytes())?))
+    }
 
-/// Return subcommand for DNA
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        let (dna_string, report) = input;
+        Ok(Counts {
+            counts: solve(&dna_string),
+            report,
+        })
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(&output.counts)
+    }
 }
 
-/// Run the dna workflow
+/// Answer to DNA
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Counts {
+    /// Number of times each base and ambiguity code occurs
+    pub counts: BTreeMap<char, usize>,
+    /// Ambiguous and soft-masked bases of the input
+    pub report: Report,
+}
+
+/// Solve DNA
 ///
-/// Run the workflow by loading the dna string from the input text file, building a counter,
-/// formatting the output, and printing it.
+/// Return the number of times each of 'A', 'C', 'G', and 'T' occurs in the dna string, along with
+/// the number of times each IUPAC ambiguity code occurs.
 ///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let dna_string = common::load_simple(path)?;
-        let counter = count_bases(&dna_string);
-        let output = format_output(counter);
-        println!("{}", output);
-        return Ok(());
-    }
-    Err(common::argument_err())
+pub fn solve(dna_string: &IupacSeq) -> BTreeMap<char, usize> {
+    count_bases(dna_string.as_str())
 }
 
 /// Count bases in the dna string and return a BTreeMap
 ///
-/// Any bases that are not 'A', 'C', 'G', or 'T' are ignored.
+/// 'A', 'C', 'G', and 'T' are always counted, while any other symbol such as an ambiguity code is
+/// only counted if it occurs.  Only 'A', 'C', 'G', and 'T' are part of the text answer.
 ///
-fn count_bases(dna_string: &str) -> BTreeMap<char, usize> {
+pub fn count_bases(dna_string: &str) -> BTreeMap<char, usize> {
     let mut counter = BTreeMap::from([('A', 0), ('C', 0), ('G', 0), ('T', 0)]);
     for base in dna_string.chars() {
-        match counter.get_mut(&base) {
-            Some(count) => {
-                *count += 1;
-            }
-            _ => continue,
-        }
+        *counter.entry(base).or_insert(0) += 1;
     }
     counter
 }
 
 /// Format output
-fn format_output(counter: BTreeMap<char, usize>) -> String {
+fn format_output(counter: &BTreeMap<char, usize>) -> String {
     format!(
         "{} {} {} {}",
         counter.get(&'A').unwrap_or(&0),
@@ -87,9 +116,16 @@ mod tests {
                 expected: BTreeMap::from([('A', 20), ('C', 12), ('G', 17), ('T', 21)]),
             },
             TestCase {
-                name: "Should ignore non DNA nucleotides",
-                dna_string: "ZZZZXagct",
-                expected: BTreeMap::from([('A', 0), ('C', 0), ('G', 0), ('T', 0)]),
+                name: "Should count ambiguity codes",
+                dna_string: "ACNNR",
+                expected: BTreeMap::from([
+                    ('A', 1),
+                    ('C', 1),
+                    ('G', 0),
+                    ('T', 0),
+                    ('N', 2),
+                    ('R', 1),
+                ]),
             },
             TestCase {
                 name: "Should not fail on empty string",
@@ -107,6 +143,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_report() {
+        let matches = Dna::command()
+            .try_get_matches_from(["dna", "--input", "acgNNRt"])
+            .unwrap();
+        let output = Dna::solve(Dna::parse(&matches).unwrap()).unwrap();
+        assert_eq!(
+            Report {
+                length: 7,
+                ambiguous: 3,
+                masked: 4,
+                codes: BTreeMap::from([('N', 2), ('R', 1)]),
+            },
+            output.report,
+            "Should report the masked bases of the input before they are upper cased"
+        );
+        assert_eq!(
+            "1 1 1 1",
+            Dna::format(&output),
+            "Should count bases in any case"
+        );
+    }
+
     #[test]
     fn test_format_output() {
         struct TestCase<'a> {
@@ -129,7 +188,7 @@ mod tests {
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                format_output(test_case.counter),
+                format_output(&test_case.counter),
                 "{}",
                 test_case.name
             );
@@ -0,0 +1,107 @@
+//! Edit Distance (https://rosalind.info/problems/edit/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (each of length at most 1000 aa).
+//!
+//! Return: The edit distance d_E(s,t).
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Scoring};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+/// Rosalind problem EDIT
+pub struct Edit;
+
+impl Problem for Edit {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = usize;
+
+    const ID: &'static str = "edit";
+    const TITLE: &'static str = "Edit Distance";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_39
+        PLEASANTLY
+        >Rosalind_11
+        MEANLY
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        5
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve EDIT
+///
+/// Return the minimum number of substitutions, insertions and deletions that turn one protein
+/// string into the other.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<usize> {
+    let alignment = align::global(s.as_bytes(), t.as_bytes(), &Scoring::edit_distance())?;
+    Ok(alignment.score.unsigned_abs() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: usize,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                s: "PLEASANTLY",
+                t: "MEANLY",
+                expected: 5,
+            },
+            TestCase {
+                name: "Should return the length of the other string if one is empty",
+                s: "",
+                t: "MEANLY",
+                expected: 6,
+            },
+            TestCase {
+                name: "Should return 0 for identical strings",
+                s: "MEANLY",
+                t: "MEANLY",
+                expected: 0,
+            },
+            TestCase {
+                name: "Should return 0 for two empty strings",
+                s: "",
+                t: "",
+                expected: 0,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                solve(&test_case.s.parse().unwrap(), &test_case.t.parse().unwrap()).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Edit Distance Alignment (https://rosalind.info/problems/edta/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (with each string having length at most
+//!         1000 aa).
+//!
+//! Return: The edit distance d_E(s,t) followed by two augmented strings s' and t' representing
+//!         an optimal alignment of s and t.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Scoring};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+/// Rosalind problem EDTA
+pub struct Edta;
+
+impl Problem for Edta {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = Output;
+
+    const ID: &'static str = "edta";
+    const TITLE: &'static str = "Edit Distance Alignment";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_43
+        PRETTY
+        >Rosalind_97
+        PRTTEIN
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        4
+        PRETTY--
+        PR-TTEIN
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve EDTA
+///
+/// Return the edit distance of two protein strings along with an alignment that achieves it.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<Output> {
+    let alignment = align::global(s.as_bytes(), t.as_bytes(), &Scoring::edit_distance())?;
+    Ok(Output {
+        distance: alignment.score.unsigned_abs() as usize,
+        aligned1: alignment.aligned1,
+        aligned2: alignment.aligned2,
+    })
+}
+
+/// Edit distance and an optimal alignment
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    pub distance: usize,
+    pub aligned1: String,
+    pub aligned2: String,
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{}\n{}", self.distance, self.aligned1, self.aligned2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (usize, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                s: "PRETTY",
+                t: "PRTTEIN",
+                expected: (4, "PRETTY--", "PR-TTEIN"),
+            },
+            TestCase {
+                name: "Should align identical strings without edits",
+                s: "MEANLY",
+                t: "MEANLY",
+                expected: (0, "MEANLY", "MEANLY"),
+            },
+            TestCase {
+                name: "Should insert every symbol of the other string if one is empty",
+                s: "",
+                t: "MEANLY",
+                expected: (6, "------", "MEANLY"),
+            },
+            TestCase {
+                name: "Should align two empty strings",
+                s: "",
+                t: "",
+                expected: (0, "", ""),
+            },
+        ];
+        for test_case in test_cases {
+            let output =
+                solve(&test_case.s.parse().unwrap(), &test_case.t.parse().unwrap()).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.distance,
+                    output.aligned1.as_str(),
+                    output.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
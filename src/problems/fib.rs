@@ -1,44 +1,59 @@
 //! Rabbits and Recurrence Relations (https://rosalind.info/problems/fib/)
 //!
 //! # Problem
-//!     Given: Positive integers n <= 40 and k <= 5
+//! ```text
+//! Given: Positive integers n <= 40 and k <= 5
 //!
-//!     Return: The total number of rabbit pairs that will be present after n months, if we begin
-//!             with 1 pair and in each generation, every pair of reproduction-age rabbits produces
-//!             a litter of k rabbit pairs (instead of only 1 pair).
-//!
-//! # Sample Dataset
-//!     5 3
-//!
-//! # Sample Output
-//!     19
+//! Return: The total number of rabbit pairs that will be present after n months, if we begin
+//!         with 1 pair and in each generation, every pair of reproduction-age rabbits produces
+//!         a litter of k rabbit pairs (instead of only 1 pair).
+//! ```
 //!
 use crate::common;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "fib";
 const DELIMITER: &str = " ";
 
-/// Return the command for FIB
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+/// Rosalind problem FIB
+pub struct Fib;
+
+impl Problem for Fib {
+    type Input = (i64, i64);
+    type Output = i64;
+
+    const ID: &'static str = "fib";
+    const TITLE: &'static str = "Rabbits and Recurrence Relations";
+    const SAMPLE_DATASET: &'static str = "
+        5 3
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        19
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(input.0, input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
 }
 
-/// Run the fib workflow
+/// Solve FIB
 ///
-/// Run the workflow by loading the data from the text file, parsing the n and k integers, running
-/// the population counting algorithm, and then printing the results.
+/// Return the total number of rabbit pairs present after n months when every reproductive pair
+/// produces a litter of k pairs each month.  An error is returned if the population overflows.
 ///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (n, k) = parse_input(input)?;
-        let mut population = Population::new(k);
-        population.advance_n(n);
-        println!("{}", population.total());
-        return Ok(());
-    }
-    Err(common::argument_err())
+pub fn solve(n: i64, k: i64) -> Result<i64> {
+    let mut population = Population::new(k);
+    population.advance_n(n)?;
+    population.total()
 }
 
 /// Parse input
@@ -46,12 +61,12 @@ pub fn run(matches: &clap::ArgMatches) -> Result<()> {
 /// Parse input into k and n and return an error if the input is invalid
 ///
 fn parse_input(input: String) -> Result<(i64, i64)> {
-    let input: Vec<&str> = input.split(DELIMITER).collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let tokens: Vec<&str> = input.split(DELIMITER).collect();
+    if tokens.len() != 2 {
+        return Err(common::invalid_input("expected two integers n and k"));
     }
-    let n = input[0].parse::<i64>()?;
-    let k = input[1].parse::<i64>()?;
+    let n = common::parse_token::<i64>(&input, tokens[0])?;
+    let k = common::parse_token::<i64>(&input, tokens[1])?;
     Ok((n, k))
 }
 
@@ -76,25 +91,40 @@ impl Population {
     }
 
     /// Total number of rabbit pairs in the population
-    fn total(&self) -> i64 {
-        self.newborn + self.reproductive
+    fn total(&self) -> Result<i64> {
+        self.newborn
+            .checked_add(self.reproductive)
+            .ok_or_else(overflow_err)
     }
 
     /// Advances one cycle
-    fn advance(&mut self) {
-        let newborn = self.reproductive * self.k;
-        self.reproductive = self.total();
+    fn advance(&mut self) -> Result<()> {
+        let newborn = self
+            .reproductive
+            .checked_mul(self.k)
+            .ok_or_else(overflow_err)?;
+        self.reproductive = self.total()?;
         self.newborn = newborn;
+        Ok(())
     }
 
     /// Advance n cycles
-    fn advance_n(&mut self, n: i64) {
+    fn advance_n(&mut self, n: i64) -> Result<()> {
         for _ in 0..n - 1 {
-            self.advance()
+            self.advance()?;
         }
+        Ok(())
     }
 }
 
+/// Return overflow error
+fn overflow_err() -> Error {
+    Error::new(
+        ErrorKind::Overflow,
+        "rabbit population does not fit in 64 bits",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,7 +170,22 @@ mod tests {
     #[test]
     fn test_population_advance_n() {
         let mut population = Population::new(3);
-        population.advance_n(5);
-        assert_eq!(19, population.total(), "{}", "Sample Dataset from problem");
+        assert_eq!(Ok(()), population.advance_n(5));
+        assert_eq!(
+            Ok(19),
+            population.total(),
+            "{}",
+            "Sample Dataset from problem"
+        );
+    }
+
+    #[test]
+    fn test_solve_overflow() {
+        assert_eq!(
+            Some(&ErrorKind::Overflow),
+            solve(100, 5).as_ref().err().map(|e| e.kind()),
+            "{}",
+            "Should return an overflow error",
+        );
     }
 }
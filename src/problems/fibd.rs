@@ -1,51 +1,68 @@
 //! Mortal Fibonacci Rabbits (https://rosalind.info/problems/fibd/)
 //!
 //! # Problem
-//!     Given: Positive integers n <= 100 and m <= 20
+//! ```text
+//! Given: Positive integers n <= 100 and m <= 20
 //!
-//!     Return: The total number of pairs of rabbits that will remain after the nth month if all
-//!             rabbits live for m months.
-//!
-//! # Sample Dataset
-//!     6 3
-//!
-//! # Sample Output
-//!     4
+//! Return: The total number of pairs of rabbits that will remain after the nth month if all
+//!         rabbits live for m months.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::errors::Result;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "fibd";
 const DELIMITER: &str = " ";
 
-/// Return the command for FIBD
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem FIBD
+pub struct Fibd;
 
-/// Run the fibd workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (n, m) = parse_input(input)?;
-        let output = simulate(n, m);
-        println!("{}", output);
-        return Ok(());
+impl Problem for Fibd {
+    type Input = (u128, u128);
+    type Output = u128;
+
+    const ID: &'static str = "fibd";
+    const TITLE: &'static str = "Mortal Fibonacci Rabbits";
+    const SAMPLE_DATASET: &'static str = "
+        6 3
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        4
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(input.0, input.1))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
 /// Parse input
 fn parse_input(input: String) -> Result<(u128, u128)> {
-    let input: Vec<&str> = input.split(DELIMITER).collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let tokens: Vec<&str> = input.split(DELIMITER).collect();
+    if tokens.len() != 2 {
+        return Err(common::invalid_input("expected two integers n and m"));
     }
-    let n = input[0].parse::<u128>()?;
-    let m = input[1].parse::<u128>()?;
+    let n = common::parse_token::<u128>(&input, tokens[0])?;
+    let m = common::parse_token::<u128>(&input, tokens[1])?;
     Ok((n, m))
 }
 
+/// Solve FIBD
+///
+/// Return the number of rabbit pairs remaining after n months if every rabbit lives m months.
+///
+pub fn solve(n: u128, m: u128) -> u128 {
+    simulate(n, m)
+}
+
 /// Given lifespan m and n number of months, return the total number of pairs of rabbits
 fn simulate(n: u128, m: u128) -> u128 {
     // If n months is less than 2, then return 1
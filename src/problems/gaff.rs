@@ -0,0 +1,135 @@
+//! Global Alignment with Scoring Matrix and Affine Gap Penalty
+//! (https://rosalind.info/problems/gaff/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (each of length at most 100 aa).
+//!
+//! Return: The maximum alignment score between s and t, followed by two augmented strings s'
+//!         and t' representing an optimal alignment of s and t.  Use:
+//!         - The BLOSUM62 scoring matrix.
+//!         - Gap opening penalty equal to 11.
+//!         - Gap extension penalty equal to 1.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Alignment, Gap, Matrix, Scoring, Substitution};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+const GAP_OPEN: i32 = 11;
+const GAP_EXTEND: i32 = 1;
+
+/// Rosalind problem GAFF
+pub struct Gaff;
+
+impl Problem for Gaff {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = Alignment;
+
+    const ID: &'static str = "gaff";
+    const TITLE: &'static str = "Global Alignment with Scoring Matrix and Affine Gap Penalty";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_79
+        PRTEINS
+        >Rosalind_41
+        PRTWPSEIN
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        8
+        PRT---EINS
+        PRTWPSEIN-
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format!("{}\n{}\n{}", output.score, output.aligned1, output.aligned2)
+    }
+}
+
+/// Solve GAFF
+///
+/// Return the best global alignment of two protein strings using BLOSUM62, a gap opening penalty
+/// of 11 and a gap extension penalty of 1.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<Alignment> {
+    let scoring = Scoring::new(
+        Substitution::Matrix(&Matrix::BLOSUM62),
+        Gap::Affine {
+            open: GAP_OPEN,
+            extend: GAP_EXTEND,
+        },
+    );
+    align::global(s.as_bytes(), t.as_bytes(), &scoring)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                s: "PRTEINS",
+                t: "PRTWPSEIN",
+                expected: (8, "PRT---EINS", "PRTWPSEIN-"),
+            },
+            TestCase {
+                name: "Should align identical strings without gaps",
+                s: "MEANLY",
+                t: "MEANLY",
+                expected: (31, "MEANLY", "MEANLY"),
+            },
+            TestCase {
+                name: "Should open a single gap against an empty first string",
+                s: "",
+                t: "MEANLY",
+                expected: (-16, "------", "MEANLY"),
+            },
+            TestCase {
+                name: "Should open a single gap against an empty second string",
+                s: "MEANLY",
+                t: "",
+                expected: (-16, "MEANLY", "------"),
+            },
+            TestCase {
+                name: "Should align two empty strings",
+                s: "",
+                t: "",
+                expected: (0, "", ""),
+            },
+        ];
+        for test_case in test_cases {
+            let output =
+                solve(&test_case.s.parse().unwrap(), &test_case.t.parse().unwrap()).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.score,
+                    output.aligned1.as_str(),
+                    output.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
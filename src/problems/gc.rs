@@ -2,52 +2,356 @@
 //!
 //! # Problem
 //!
-//!     Given: At most 10 DNA strings in FASTA format (of length at most 1 kbp each.)
+//! ```text
+//! Given: At most 10 DNA strings in FASTA format (of length at most 1 kbp each.)
 //!
-//!     Return: The ID of the string having the highest GC-content, followed by the GC-content of
-//!             that string.  Rosalind allows for a default error of 0.001 in all decimal answers
-//!             unless otherwise stated; pleae see the note on absolute error below.
+//! Return: The ID of the string having the highest GC-content, followed by the GC-content of
+//!         that string.  Rosalind allows for a default error of 0.001 in all decimal answers
+//!         unless otherwise stated; pleae see the note on absolute error below.
+//! ```
 //!
-//! # Sample Dataset
-//!     >Rosalind_6404
-//!     CCTGCGGAAGATCGGCACTAGAATAGCCAGAACCGTTTCTCTGAGGCTTCCGGCCTTCCC
-//!     TCCCACTAATAATTCTGAGG
-//!     >Rosalind_5959
-//!     CCATCGGTAGCGCATCCTTAGTCCAATTAAGTCCCTATCCAGGCGCTCCGCCGAAGGTCT
-//!     ATATCCATTTGTCAGCAGACACGC
-//!     >Rosalind_0808
-//!     CCACCCTCGTGGTATGGCTAGGCATTCAGGAACCGGAGAACGCTTCAGACCAGCCCGGAC
-//!     TGGGAACCTGCGGGCAGTAGGTGGAAT
+//! # Sliding windows
 //!
-//! # Sample Output
-//!     Rosalind_0808
-//!     60.919540
+//! ```text
+//! gc <file> --window <size> [--step <size>] [--bedgraph]
+//! ```
+//!
+//! reports the GC content, GC skew and cumulative GC skew of every window of every record
+//! instead.  The cumulative skew is the running count of G minus C over the bases covered by the
+//! windows so far, so overlapping windows count every base once.  Windows use 0-based, half-open coordinates as in BED.  The windows where the
+//! cumulative skew is lowest and highest are marked, since they predict the origin and terminus
+//! of replication of bacterial genomes.
 //!
 use crate::common;
+use crate::common::seq::IupacSeq;
+use crate::common::stats::Stats;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const WINDOW_ARG: &str = "window";
+const STEP_ARG: &str = "step";
+const BEDGRAPH_ARG: &str = "bedgraph";
+const TABLE_HEADER: &str = "id\tstart\tend\tgc\tskew\tcumulative_skew\tmark";
+const GC_TRACK: &str = "gc";
+const SKEW_TRACK: &str = "cumulative_gc_skew";
+const MIN_SKEW: &str = "min_cumulative_skew";
+const MAX_SKEW: &str = "max_cumulative_skew";
+
+/// Rosalind problem GC
+pub struct Gc;
+
+impl Problem for Gc {
+    type Input = (Vec<(String, IupacSeq)>, Option<Windows>);
+    type Output = Output;
+
+    const ID: &'static str = "gc";
+    const TITLE: &'static str = "Computing GC Content";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_6404
+        CCTGCGGAAGATCGGCACTAGAATAGCCAGAACCGTTTCTCTGAGGCTTCCGGCCTTCCC
+        TCCCACTAATAATTCTGAGG
+        >Rosalind_5959
+        CCATCGGTAGCGCATCCTTAGTCCAATTAAGTCCCTATCCAGGCGCTCCGCCGAAGGTCT
+        ATATCCATTTGTCAGCAGACACGC
+        >Rosalind_0808
+        CCACCCTCGTGGTATGGCTAGGCATTCAGGAACCGGAGAACGCTTCAGACCAGCCCGGAC
+        TGGGAACCTGCGGGCAGTAGGTGGAAT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        Rosalind_0808
+        60.919540
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn command() -> clap::Command<'static> {
+        common::subcommand_file(Self::ID)
+            .arg(
+                clap::Arg::new(WINDOW_ARG)
+                    .long(WINDOW_ARG)
+                    .takes_value(true)
+                    .value_name("size")
+                    .help("Report every window of this many bases instead"),
+            )
+            .arg(
+                clap::Arg::new(STEP_ARG)
+                    .long(STEP_ARG)
+                    .takes_value(true)
+                    .value_name("size")
+                    .requires(WINDOW_ARG)
+                    .help("Number of bases between the starts of windows [default: window size]"),
+            )
+            .arg(
+                clap::Arg::new(BEDGRAPH_ARG)
+                    .long(BEDGRAPH_ARG)
+                    .requires(WINDOW_ARG)
+                    .help("Write windows as BedGraph instead of a tab separated table"),
+            )
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let records = common::load_fasta(&input)?
+            .into_iter()
+            .map(|record| Ok((record.name.clone(), record.parse_sequence()?)))
+            .collect::<Result<Vec<(String, IupacSeq)>>>()?;
+        Ok((records, Windows::from_matches(matches)?))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        match input.1 {
+            Some(windows) => Ok(Output::Windows {
+                profiles: input
+                    .0
+                    .iter()
+                    .map(|(id, sequence)| profile(id, sequence.as_bytes(), &windows))
+                    .collect(),
+                bedgraph: windows.bedgraph,
+            }),
+            None => Ok(Output::Highest(solve(&input.0)?)),
+        }
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve GC
+///
+/// Return the ID and GC content of the record with the highest GC content.  An error is returned
+/// if there are no records.
+///
+pub fn solve(records: &[(String, IupacSeq)]) -> Result<Highest> {
+    let mut gc = calculate_gc(records);
+    let (id, gc) = max_gc(&mut gc)
+        .ok_or_else(|| Error::new(ErrorKind::Validation, "at least one record is required"))?;
+    Ok(Highest {
+        id: id.to_owned(),
+        gc: *gc,
+    })
+}
+
+/// Answer to GC, either the record with the highest GC content or the windows of every record
+#[derive(Debug, PartialEq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Output {
+    Highest(Highest),
+    Windows {
+        profiles: Vec<Profile>,
+        #[serde(skip)]
+        bedgraph: bool,
+    },
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Highest(highest) => write!(f, "{}", highest),
+            Self::Windows { profiles, bedgraph } => {
+                let lines: Vec<String> = match bedgraph {
+                    true => format_bedgraph(profiles),
+                    false => format_table(profiles),
+                };
+                write!(f, "{}", lines.join("\n"))
+            }
+        }
+    }
+}
+
+/// Record with the highest GC content
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Highest {
+    /// ID of the fasta record
+    pub id: String,
+    /// GC content as a percentage
+    pub gc: f64,
+}
+
+impl std::fmt::Display for Highest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{:.6}", self.id, self.gc)
+    }
+}
 
-pub const SUBCOMMAND: &str = "gc";
+/// Sliding windows requested on the command line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Windows {
+    /// Number of bases in a window
+    pub size: usize,
+    /// Number of bases between the starts of consecutive windows
+    pub step: usize,
+    /// Write BedGraph instead of a tab separated table
+    pub bedgraph: bool,
+}
+
+impl Windows {
+    /// Parse the window arguments, returning None if no window is given
+    fn from_matches(matches: &clap::ArgMatches) -> Result<Option<Self>> {
+        let size: usize = match matches.value_of(WINDOW_ARG) {
+            Some(size) => common::parse_token(size, size)?,
+            None => return Ok(None),
+        };
+        let step = match matches.value_of(STEP_ARG) {
+            Some(step) => common::parse_token(step, step)?,
+            None => size,
+        };
+        if size == 0 || step == 0 {
+            return Err(Error::new(
+                ErrorKind::User,
+                "window and step sizes must be greater than 0",
+            ));
+        }
+        Ok(Some(Self {
+            size,
+            step,
+            bedgraph: matches.contains_id(BEDGRAPH_ARG),
+        }))
+    }
+}
 
-/// Return the subommand for GC
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+/// GC content and skew of a window
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Window {
+    /// 0-based start of the window
+    pub start: usize,
+    /// End of the window, exclusive
+    pub end: usize,
+    /// GC content of the unambiguous bases as a percentage
+    pub gc: f64,
+    /// GC skew, (G - C) / (G + C)
+    pub skew: f64,
+    /// Number of G minus number of C up to the end of this window, counting every base covered by
+    /// a window once
+    pub cumulative_skew: i64,
 }
 
-/// Run the gc workflow
+/// Windows of a single record
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Profile {
+    pub id: String,
+    pub windows: Vec<Window>,
+    /// Index of the window with the lowest cumulative skew, the predicted origin of replication
+    pub min_skew: Option<usize>,
+    /// Index of the window with the highest cumulative skew, the predicted terminus
+    pub max_skew: Option<usize>,
+}
+
+/// Compute the windows of a sequence
 ///
-/// Run the workflow by loading the fasta records, calculating the gc, finding the entry with
-/// the maximum gc, and printing it.
+/// Windows start every step bases and the last window is cut short at the end of the sequence.
+/// The cumulative skew adds the bases of each window not already covered by the previous one, so
+/// bases between windows that are further apart than their size are skipped.  Ties of the
+/// cumulative skew are broken by the first window.
 ///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let file = std::fs::File::open(path)?;
-        let reader = common::fasta::Reader::new(file);
-        let mut gc = calculate_gc(reader)?;
-        let max = max_gc(&mut gc).ok_or_else(|| Error::new(ErrorKind::IO, "invalid input"))?;
-        println!("{}\n{:.6}", max.0, max.1);
-        return Ok(());
+pub fn profile(id: &str, sequence: &[u8], windows: &Windows) -> Profile {
+    let mut profile = Profile {
+        id: id.to_owned(),
+        windows: Vec::new(),
+        min_skew: None,
+        max_skew: None,
+    };
+    let mut cumulative_skew = 0;
+    let mut covered = 0;
+    for start in (0..sequence.len()).step_by(windows.step) {
+        let end = sequence.len().min(start + windows.size);
+        let stats = Stats::from(&sequence[start..end]);
+        let added = Stats::from(&sequence[covered.max(start)..end]);
+        cumulative_skew += added.count(b'G') as i64 - added.count(b'C') as i64;
+        covered = end;
+        profile.windows.push(Window {
+            start,
+            end,
+            gc: stats.gc_content(),
+            skew: stats.gc_skew(),
+            cumulative_skew,
+        });
+        if end == sequence.len() {
+            break;
+        }
     }
-    Err(common::argument_err())
+    let skews = profile.windows.iter().map(|window| window.cumulative_skew);
+    profile.min_skew = extreme(skews.clone(), |a, b| a < b);
+    profile.max_skew = extreme(skews, |a, b| a > b);
+    profile
+}
+
+/// Return the index of the first value that is more extreme than every other value
+fn extreme<I, F>(values: I, is_more: F) -> Option<usize>
+where
+    I: Iterator<Item = i64>,
+    F: Fn(i64, i64) -> bool,
+{
+    let mut best: Option<(usize, i64)> = None;
+    for (i, value) in values.enumerate() {
+        if best.is_none_or(|(_, best)| is_more(value, best)) {
+            best = Some((i, value));
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// Format windows as a tab separated table, marking the extremes of the cumulative skew
+fn format_table(profiles: &[Profile]) -> Vec<String> {
+    let mut lines = vec![TABLE_HEADER.to_owned()];
+    for profile in profiles {
+        for (i, window) in profile.windows.iter().enumerate() {
+            let mark: Vec<&str> = [(MIN_SKEW, profile.min_skew), (MAX_SKEW, profile.max_skew)]
+                .iter()
+                .filter(|(_, index)| *index == Some(i))
+                .map(|(mark, _)| *mark)
+                .collect();
+            lines.push(format!(
+                "{}\t{}\t{}\t{:.2}\t{:.4}\t{}\t{}",
+                profile.id,
+                window.start,
+                window.end,
+                window.gc,
+                window.skew,
+                window.cumulative_skew,
+                mark.join(",")
+            ));
+        }
+    }
+    lines
+}
+
+/// Format windows as BedGraph tracks of the GC content and the cumulative skew
+///
+/// The extremes of the cumulative skew are given as comments since BedGraph has no column for
+/// them.
+///
+fn format_bedgraph(profiles: &[Profile]) -> Vec<String> {
+    let track = |name: &str, value: fn(&Window) -> String| {
+        let mut lines = vec![format!("track type=bedGraph name={}", name)];
+        for profile in profiles {
+            lines.extend(profile.windows.iter().map(|window| {
+                format!(
+                    "{}\t{}\t{}\t{}",
+                    profile.id,
+                    window.start,
+                    window.end,
+                    value(window)
+                )
+            }));
+        }
+        lines
+    };
+    let mut lines = track(GC_TRACK, |window| format!("{:.2}", window.gc));
+    lines.extend(track(SKEW_TRACK, |window| {
+        window.cumulative_skew.to_string()
+    }));
+    for profile in profiles {
+        for (mark, index) in [(MIN_SKEW, profile.min_skew), (MAX_SKEW, profile.max_skew)] {
+            if let Some(window) = index.map(|i| &profile.windows[i]) {
+                lines.push(format!(
+                    "# {}\t{}\t{}\t{}\t{}",
+                    mark, profile.id, window.start, window.end, window.cumulative_skew
+                ));
+            }
+        }
+    }
+    lines
 }
 
 /// Get record with highest GC content
@@ -57,20 +361,15 @@ fn max_gc(gc: &mut [(String, f64)]) -> Option<&(String, f64)> {
 }
 
 /// Calculate the GC of all records
-fn calculate_gc<R>(reader: common::fasta::Reader<R>) -> Result<Vec<(String, f64)>>
-where
-    R: std::io::Read,
-{
-    let mut output = Vec::new();
-    for result in reader.iter() {
-        let record = result?;
-        output.push((record.name, get_gc(record.sequence)))
-    }
-    Ok(output)
+fn calculate_gc(records: &[(String, IupacSeq)]) -> Vec<(String, f64)> {
+    records
+        .iter()
+        .map(|(id, sequence)| (id.clone(), get_gc(sequence.as_str())))
+        .collect()
 }
 
-/// Get the GC content as a formatted string
-fn get_gc(sequence: String) -> f64 {
+/// Get the GC content of a sequence as a percentage
+pub fn get_gc(sequence: &str) -> f64 {
     if sequence.is_empty() {
         return 0.0;
     }
@@ -113,18 +412,18 @@ mod tests {
     fn test_get_gc() {
         struct TestCase<'a> {
             name: &'a str,
-            sequence: String,
+            sequence: &'a str,
             expected: f64,
         }
         let test_cases = [
             TestCase {
                 name: "Should calculate GC correctly",
-                sequence: "GGCCAAT".into(),
+                sequence: "GGCCAAT",
                 expected: 57.142857,
             },
             TestCase {
                 name: "Should return 0 for an empty sequence",
-                sequence: "".into(),
+                sequence: "",
                 expected: 0.0,
             },
         ];
@@ -136,4 +435,86 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_profile() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequence: &'a [u8],
+            windows: Windows,
+            expected: Vec<(usize, usize, f64, i64)>,
+            min_skew: Option<usize>,
+            max_skew: Option<usize>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should compute windows and mark the extremes of the cumulative skew",
+                sequence: b"GGGGCCCCAAAA",
+                windows: Windows {
+                    size: 4,
+                    step: 4,
+                    bedgraph: false,
+                },
+                expected: vec![(0, 4, 100.0, 4), (4, 8, 100.0, 0), (8, 12, 0.0, 0)],
+                min_skew: Some(1),
+                max_skew: Some(0),
+            },
+            TestCase {
+                name: "Should overlap windows and cut the last window short",
+                sequence: b"GGCCAA",
+                windows: Windows {
+                    size: 4,
+                    step: 2,
+                    bedgraph: false,
+                },
+                expected: vec![(0, 4, 100.0, 0), (2, 6, 50.0, 0)],
+                min_skew: Some(0),
+                max_skew: Some(0),
+            },
+            TestCase {
+                name: "Should count every base of overlapping windows once",
+                sequence: b"GGGGCCCCCCCC",
+                windows: Windows {
+                    size: 4,
+                    step: 2,
+                    bedgraph: false,
+                },
+                expected: vec![
+                    (0, 4, 100.0, 4),
+                    (2, 6, 100.0, 2),
+                    (4, 8, 100.0, 0),
+                    (6, 10, 100.0, -2),
+                    (8, 12, 100.0, -4),
+                ],
+                min_skew: Some(4),
+                max_skew: Some(0),
+            },
+            TestCase {
+                name: "Should skip the bases between windows",
+                sequence: b"GGCCCCAA",
+                windows: Windows {
+                    size: 2,
+                    step: 4,
+                    bedgraph: false,
+                },
+                expected: vec![(0, 2, 100.0, 2), (4, 6, 100.0, 0)],
+                min_skew: Some(1),
+                max_skew: Some(0),
+            },
+        ];
+        for test_case in test_cases {
+            let profile = profile("id", test_case.sequence, &test_case.windows);
+            let windows = profile
+                .windows
+                .iter()
+                .map(|window| (window.start, window.end, window.gc, window.cumulative_skew))
+                .collect();
+            assert_eq!(
+                (test_case.expected, test_case.min_skew, test_case.max_skew),
+                (windows, profile.min_skew, profile.max_skew),
+                "{}",
+                test_case.name
+            );
+        }
+    }
 }
@@ -0,0 +1,111 @@
+//! Global Alignment with Scoring Matrix (https://rosalind.info/problems/glob/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (each of length at most 1000 aa).
+//!
+//! Return: The maximum alignment score between s and t.  Use:
+//!         - The BLOSUM62 scoring matrix.
+//!         - Linear gap penalty equal to 5 (i.e., a cost of -5 is assessed for each gap
+//!           symbol).
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Gap, Matrix, Scoring, Substitution};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
+
+const GAP_PENALTY: i32 = 5;
+
+/// Rosalind problem GLOB
+pub struct Glob;
+
+impl Problem for Glob {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = i32;
+
+    const ID: &'static str = "glob";
+    const TITLE: &'static str = "Global Alignment with Scoring Matrix";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_67
+        PLEASANTLY
+        >Rosalind_17
+        MEANLY
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        8
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve GLOB
+///
+/// Return the best global alignment score of two protein strings using BLOSUM62 and a linear gap
+/// penalty of 5.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<i32> {
+    let scoring = Scoring::new(
+        Substitution::Matrix(&Matrix::BLOSUM62),
+        Gap::Linear(GAP_PENALTY),
+    );
+    Ok(align::global(s.as_bytes(), t.as_bytes(), &scoring)?.score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: i32,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                s: "PLEASANTLY",
+                t: "MEANLY",
+                expected: 8,
+            },
+            TestCase {
+                name: "Should score identical strings by their substitutions",
+                s: "MEANLY",
+                t: "MEANLY",
+                expected: 31,
+            },
+            TestCase {
+                name: "Should pay for every gap if one string is empty",
+                s: "",
+                t: "MEANLY",
+                expected: -30,
+            },
+            TestCase {
+                name: "Should score two empty strings as 0",
+                s: "",
+                t: "",
+                expected: 0,
+            },
+        ];
+        for test_case in test_cases {
+            let output = solve(&test_case.s.parse().unwrap(), &test_case.t.parse().unwrap());
+            assert_eq!(test_case.expected, output.unwrap(), "{}", test_case.name);
+        }
+    }
+}
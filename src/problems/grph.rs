@@ -1,67 +1,144 @@
 //! Overlap Graphs (https://rosalind.info/problems/grph/)
 //!
 //! # Problem
-//!     Given: A collection of DNA strings in FASTA format having total length at most 10 kbp
+//! ```text
+//! Given: A collection of DNA strings in FASTA format having total length at most 10 kbp
 //!
-//!     Return: The adjacency list corresponding to O_3.  You may return edges in any order.
+//! Return: The adjacency list corresponding to O_3.  You may return edges in any order.
+//! ```
 //!
-//! # Sample Dataset
-//!     >Rosalind_0498
-//!     AAATAAA
-//!     >Rosalind_2391
-//!     AAATTTT
-//!     >Rosalind_2323
-//!     TTTTCCC
-//!     >Rosalind_0442
-//!     AAATCCC
-//!     >Rosalind_5013
-//!     GGGTGGG
-//!
-//! # Sample Output
-//!     Rosalind_0498 Rosalind_2391
-//!     Rosalind_0498 Rosalind_0442
-//!     Rosalind_2391 Rosalind_2323
+//! With `--min-score`, records are connected when the best overlap alignment of a suffix of one
+//! with a prefix of the other scores at least the given score instead, which tolerates the errors
+//! of noisy reads.
 //!
 use crate::common;
+use crate::common::align::{self, Gap, Mode, Scoring, Substitution};
+use crate::common::seq::DnaSeq;
 use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const MIN_SCORE_ARG: &str = "min-score";
+const MATCH_SCORE: i32 = 1;
+const MISMATCH_SCORE: i32 = -2;
+const GAP_PENALTY: i32 = 2;
+
+/// Rosalind problem GRPH
+pub struct Grph;
+
+impl Problem for Grph {
+    type Input = (Vec<(String, DnaSeq)>, Option<i32>);
+    type Output = Vec<Edge>;
+
+    const ID: &'static str = "grph";
+    const TITLE: &'static str = "Overlap Graphs";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_0498
+        AAATAAA
+        >Rosalind_2391
+        AAATTTT
+        >Rosalind_2323
+        TTTTCCC
+        >Rosalind_0442
+        AAATCCC
+        >Rosalind_5013
+        GGGTGGG
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        Rosalind_0498 Rosalind_2391
+        Rosalind_0498 Rosalind_0442
+        Rosalind_2391 Rosalind_2323
+    ";
+    const COMPARISON: Comparison = Comparison::Unordered;
+
+    fn command() -> clap::Command<'static> {
+        common::subcommand_file(Self::ID).arg(
+            clap::Arg::new(MIN_SCORE_ARG)
+                .long(MIN_SCORE_ARG)
+                .takes_value(true)
+                .value_name("score")
+                .help("Connect records whose overlap alignment scores at least this much instead"),
+        )
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let records = common::load_fasta(&input)?
+            .into_iter()
+            .map(|record| Ok((record.header().accession().into(), record.parse_sequence()?)))
+            .collect::<Result<_>>()?;
+        let min_score = match matches.value_of(MIN_SCORE_ARG) {
+            Some(score) => Some(common::parse_token(score, score)?),
+            None => None,
+        };
+        Ok((records, min_score))
+    }
 
-pub const SUBCOMMAND: &str = "grph";
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
+}
+
+/// Solve GRPH
+///
+/// Return the edges of the overlap graph O_3 between record IDs, or of the graph of overlap
+/// alignments scoring at least the minimum score if one is given.
+///
+pub fn solve(records: &[(String, DnaSeq)], min_score: Option<i32>) -> Result<Vec<Edge>> {
+    build_graph(records, |seq1, seq2| match min_score {
+        Some(min_score) => Ok(overlap_score(seq1, seq2)? >= min_score),
+        None => Ok(is_overlapped(seq1.as_str(), seq2.as_str())),
+    })
+}
 
-/// Return the subcommand for GRPH
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+/// Directed edge of the overlap graph
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Edge {
+    /// ID of the record whose suffix overlaps
+    pub from: String,
+    /// ID of the record whose prefix overlaps
+    pub to: String,
 }
 
-/// Run the grph workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let file = std::fs::File::open(path)?;
-        let reader = common::fasta::Reader::new(file);
-        build_graph(reader)?;
-        return Ok(());
+impl std::fmt::Display for Edge {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.from, self.to)
     }
-    Err(common::argument_err())
 }
 
-/// Build graph
-fn build_graph<R>(reader: common::fasta::Reader<R>) -> Result<()>
+/// Format output
+fn format_output(edges: &[Edge]) -> String {
+    edges
+        .iter()
+        .map(|edge| edge.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Build graph, connecting the records whose sequences are overlapped
+fn build_graph<F>(records: &[(String, DnaSeq)], is_overlapped: F) -> Result<Vec<Edge>>
 where
-    R: std::io::Read,
+    F: Fn(&DnaSeq, &DnaSeq) -> Result<bool>,
 {
-    let records: Vec<common::fasta::Record> = reader
-        .iter()
-        .collect::<Result<Vec<common::fasta::Record>>>()?;
-    for seq1 in &records {
-        for seq2 in &records {
-            if seq1 == seq2 {
+    let mut edges = Vec::new();
+    for (i, (id1, seq1)) in records.iter().enumerate() {
+        for (j, (id2, seq2)) in records.iter().enumerate() {
+            if i == j {
                 continue;
             }
-            if is_overlapped(&seq1.sequence, &seq2.sequence) {
-                println!("{} {}", seq1.name, seq2.name);
+            if is_overlapped(seq1, seq2)? {
+                edges.push(Edge {
+                    from: id1.clone(),
+                    to: id2.clone(),
+                });
             }
         }
     }
-    Ok(())
+    Ok(edges)
 }
 
 /// Checks to see if the two sequences are overlapped
@@ -79,10 +156,69 @@ fn is_overlapped(seq1: &str, seq2: &str) -> bool {
     seq1_suffix == seq2_prefix
 }
 
+/// Return the score of the best overlap alignment of a suffix of seq1 with a prefix of seq2
+///
+/// Matches score 1, and mismatches and gap symbols score -2, so that a score of n needs at least
+/// n matching bases.
+///
+fn overlap_score(seq1: &DnaSeq, seq2: &DnaSeq) -> Result<i32> {
+    let scoring = Scoring::new(
+        Substitution::MatchMismatch {
+            matching: MATCH_SCORE,
+            mismatch: MISMATCH_SCORE,
+        },
+        Gap::Linear(GAP_PENALTY),
+    );
+    let alignment = align::align(seq1.as_bytes(), seq2.as_bytes(), &scoring, Mode::Overlap)?;
+    Ok(alignment.score)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            records: Vec<(&'a str, &'a str)>,
+            expected: Vec<(&'a str, &'a str)>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should connect overlapping records",
+                records: vec![("a", "AAATAAA"), ("b", "AAATTTT"), ("c", "TTTTCCC")],
+                expected: vec![("a", "b"), ("b", "c")],
+            },
+            TestCase {
+                name: "Should connect duplicate records to each other but not to themselves",
+                records: vec![("a", "AAAAAA"), ("a", "AAAAAA")],
+                expected: vec![("a", "a"), ("a", "a")],
+            },
+            TestCase {
+                name: "Should not connect a record to itself",
+                records: vec![("a", "AAAAAA")],
+                expected: vec![],
+            },
+        ];
+        for test_case in test_cases {
+            let records: Vec<(String, DnaSeq)> = test_case
+                .records
+                .iter()
+                .map(|(id, sequence)| (id.to_string(), sequence.parse().unwrap()))
+                .collect();
+            let expected: Vec<Edge> = test_case
+                .expected
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })
+                .collect();
+            assert_eq!(Ok(expected), solve(&records, None), "{}", test_case.name);
+        }
+    }
+
     #[test]
     fn test_is_overlapped() {
         struct TestCase<'a> {
@@ -114,4 +250,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_overlap_score() {
+        struct TestCase<'a> {
+            name: &'a str,
+            seq1: &'a str,
+            seq2: &'a str,
+            expected: i32,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should score an exact overlap",
+                seq1: "GGGTACGTAC",
+                seq2: "TACGTACCCC",
+                expected: 7,
+            },
+            TestCase {
+                name: "Should score an overlap with a sequencing error",
+                seq1: "GGGTACGTAC",
+                seq2: "TACCTACCCC",
+                expected: 4,
+            },
+            TestCase {
+                name: "Should score no overlap as 0",
+                seq1: "AAAAAA",
+                seq2: "TTTTTT",
+                expected: 0,
+            },
+        ];
+        for test_case in test_cases {
+            let seq1 = test_case.seq1.parse().unwrap();
+            let seq2 = test_case.seq2.parse().unwrap();
+            assert_eq!(
+                test_case.expected,
+                overlap_score(&seq1, &seq2).unwrap(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
 }
@@ -2,56 +2,76 @@
 //!
 //! # Problem
 //!
-//!     Given: Two DNA strings s and t of equal length (not exceeding 1 kbp).
+//! ```text
+//! Given: Two DNA strings s and t of equal length (not exceeding 1 kbp).
 //!
-//!     Return: The Hamming distance d_H(s,t)
-//!
-//! # Sample Dataset
-//!     GAGCCTACTAACGGGAT
-//!     CATCGTAATGACGGCCT
-//!
-//! # Sample Output
-//!     7
+//! Return: The Hamming distance d_H(s,t)
+//! ```
 //!
 use crate::common;
+use crate::common::seq::DnaSeq;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "hamm";
+/// Rosalind problem HAMM
+pub struct Hamm;
 
-/// Return the subcommand for HAMM
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Hamm {
+    type Input = (DnaSeq, DnaSeq);
+    type Output = i64;
 
-/// Run the hamm workflow
-///
-/// Run the workflow by loading the sequences from the text file, calculating the hamming distance
-/// between the sequences, and then printing it.
-///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (seq1, seq2) = parse_input(input)?;
-        let distance = hamming_distance(&seq1, &seq2)?;
-        println!("{}", distance);
-        return Ok(());
+    const ID: &'static str = "hamm";
+    const TITLE: &'static str = "Counting Point Mutations";
+    const SAMPLE_DATASET: &'static str = "
+        GAGCCTACTAACGGGAT
+        CATCGTAATGACGGCCT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        7
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(&common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
 /// Parse input into the two sequences
-fn parse_input(input: String) -> Result<(String, String)> {
-    let input: Vec<&str> = input.split('\n').collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+fn parse_input(input: &str) -> Result<(DnaSeq, DnaSeq)> {
+    let mut sequences = common::parse_lines(input)?;
+    if sequences.len() != 2 {
+        return Err(common::invalid_input("expected two lines"));
     }
-    Ok((input[0].into(), input[1].into()))
+    let seq2 = sequences.remove(1);
+    Ok((sequences.remove(0), seq2))
+}
+
+/// Solve HAMM
+///
+/// Return the hamming distance between two sequences of equal length.
+///
+pub fn solve(seq1: &DnaSeq, seq2: &DnaSeq) -> Result<i64> {
+    hamming_distance(seq1.as_str(), seq2.as_str())
 }
 
 /// Calculate the hamming distance
-fn hamming_distance(seq1: &str, seq2: &str) -> Result<i64> {
+///
+/// An error is returned if the sequences are not the same length.
+///
+pub fn hamming_distance(seq1: &str, seq2: &str) -> Result<i64> {
     if seq1.len() != seq2.len() {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+        return Err(Error::new(
+            ErrorKind::Validation,
+            "sequences must have the same length",
+        ));
     }
     Ok(seq1
         .chars()
@@ -2,63 +2,84 @@
 //!
 //! # Problem
 //!
-//!     Given: Six nonnegative integers, each of which does not exceed 20,000.  The integers
-//!             correspond to the number of couples in a population possessing each genotype
-//!             pairing for a given factor.  In order, the six given integers represent the
-//!             number of couples having the following genotypes:
+//! ```text
+//! Given: Six nonnegative integers, each of which does not exceed 20,000.  The integers
+//!         correspond to the number of couples in a population possessing each genotype
+//!         pairing for a given factor.  In order, the six given integers represent the
+//!         number of couples having the following genotypes:
 //!
-//!             1. AA-AA
-//!             2. AA-Aa
-//!             3. AA-aa
-//!             4. Aa-Aa
-//!             5. Aa-aa
-//!             6. aa-aa
+//!         1. AA-AA
+//!         2. AA-Aa
+//!         3. AA-aa
+//!         4. Aa-Aa
+//!         5. Aa-aa
+//!         6. aa-aa
 //!
-//!     Return: The expected number of offspring displaying the dominant phenotype in the next
-//!             generation, under the assumption that every couple has exactly two offspring.
-//!
-//! # Sample Dataset
-//!     1 0 0 1 0 1
-//!
-//! # Sample Output
-//!     3.5
+//! Return: The expected number of offspring displaying the dominant phenotype in the next
+//!         generation, under the assumption that every couple has exactly two offspring.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "iev";
 const DELIMITER: &str = " ";
 
-/// Return the subcommand for IEV
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem IEV
+pub struct Iev;
 
-/// Run the iev workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let input = parse_input(input)?;
-        let output = calculate_expected(input);
-        println!("{}", output);
-        return Ok(());
+impl Problem for Iev {
+    type Input = [usize; 6];
+    type Output = f64;
+
+    const ID: &'static str = "iev";
+    const TITLE: &'static str = "Calculating Expected Offspring";
+    const SAMPLE_DATASET: &'static str = "
+        1 0 0 1 0 1
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        3.5
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(input))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
 /// Parse input into an array of usize
 fn parse_input(input: String) -> Result<[usize; 6]> {
     let split: Vec<&str> = input.split(DELIMITER).collect();
     if split.len() != 6 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+        return Err(common::invalid_input("expected six integers"));
     }
     let mut output: [usize; 6] = [0; 6];
     for (i, s) in split.iter().enumerate() {
-        output[i] = s.parse::<usize>()?;
+        output[i] = common::parse_token::<usize>(&input, s)?;
     }
     Ok(output)
 }
 
+/// Solve IEV
+///
+/// Return the expected number of offspring displaying the dominant phenotype given the number of
+/// couples for each of the six genotype pairings.
+///
+pub fn solve(input: [usize; 6]) -> f64 {
+    calculate_expected(input)
+}
+
+/// Calculate the expected number of dominant offspring
 fn calculate_expected(input: [usize; 6]) -> f64 {
     let prob: [f64; 6] = [2.0, 2.0, 2.0, 1.5, 1.0, 0.0];
     input
@@ -2,46 +2,53 @@
 //!
 //! # Problem
 //!
-//!     Given: Three positive integers k, m, and n, representing a population containing k + m + n
-//!             organisms: k individuals are homozygous dominant for a factor, m are heterozygous,
-//!             and n are homozygous recessive.
+//! ```text
+//! Given: Three positive integers k, m, and n, representing a population containing k + m + n
+//!         organisms: k individuals are homozygous dominant for a factor, m are heterozygous,
+//!         and n are homozygous recessive.
 //!
-//!     Return: The probability that two randomly selected mating organisms will produce an
-//!             individual possessing a dominant allele (and thus displaying the dominant
-//!             phenotype).  Assume that any two organisms can mate.
-//!
-//! # Sample Dataset
-//!     2 2 2
-//!
-//! # Sample Output
-//!     0.78333
+//! Return: The probability that two randomly selected mating organisms will produce an
+//!         individual possessing a dominant allele (and thus displaying the dominant
+//!         phenotype).  Assume that any two organisms can mate.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 use itertools::Itertools;
 
-pub const SUBCOMMAND: &str = "iprb";
 const DELIMITER: &str = " ";
 
-/// Return the command for IPRB
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem IPRB
+pub struct Iprb;
 
-/// Run the iprob workflow
-///
-/// Run the workflow by loading the data from the text file, parsing the k, m, and n integers,
-/// running the probability algorithm, and then printing the results.
-///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (k, m, n) = parse_input(input)?;
-        let prob = calc_probability(k, m, n);
-        println!("{}", prob);
-        return Ok(());
+impl Problem for Iprb {
+    type Input = (usize, usize, usize);
+    type Output = f64;
+
+    const ID: &'static str = "iprb";
+    const TITLE: &'static str = "Mendel's First Law";
+    const SAMPLE_DATASET: &'static str = "
+        2 2 2
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        0.78333
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(input.0, input.1, input.2))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
 /// Parse input
@@ -49,16 +56,26 @@ pub fn run(matches: &clap::ArgMatches) -> Result<()> {
 /// Parse input into k, m, and n and return an error if the input is invalid
 ///
 fn parse_input(input: String) -> Result<(usize, usize, usize)> {
-    let input: Vec<&str> = input.split(DELIMITER).collect();
-    if input.len() != 3 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let tokens: Vec<&str> = input.split(DELIMITER).collect();
+    if tokens.len() != 3 {
+        return Err(common::invalid_input("expected three integers k, m, and n"));
     }
-    let k = input[0].parse::<usize>()?;
-    let m = input[1].parse::<usize>()?;
-    let n = input[2].parse::<usize>()?;
+    let k = common::parse_token::<usize>(&input, tokens[0])?;
+    let m = common::parse_token::<usize>(&input, tokens[1])?;
+    let n = common::parse_token::<usize>(&input, tokens[2])?;
     Ok((k, m, n))
 }
 
+/// Solve IPRB
+///
+/// Return the probability that two randomly selected organisms from a population of k
+/// homozygous dominant, m heterozygous, and n homozygous recessive individuals produce an
+/// offspring possessing a dominant allele.
+///
+pub fn solve(k: usize, m: usize, n: usize) -> f64 {
+    calc_probability(k, m, n)
+}
+
 /// Allele pairs
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AllelePairs {
@@ -0,0 +1,61 @@
+//! Speeding Up Motif Finding (https://rosalind.info/problems/kmp/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: A DNA string s (of length at most 100 kbp) in FASTA format.
+//!
+//! Return: The failure array of s.
+//! ```
+//!
+use crate::common;
+use crate::common::matching;
+use crate::common::seq::DnaSeq;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
+
+/// Rosalind problem KMP
+pub struct Kmp;
+
+impl Problem for Kmp {
+    type Input = DnaSeq;
+    type Output = Vec<usize>;
+
+    const ID: &'static str = "kmp";
+    const TITLE: &'static str = "Speeding Up Motif Finding";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_87
+        CAGCATGGTATCACAGCAGAG
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        0 0 0 1 2 0 0 0 0 0 0 1 2 1 2 3 4 5 3 0 0
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta(&input)?
+            .first()
+            .ok_or_else(|| Error::new(ErrorKind::Validation, "empty fasta file"))?
+            .parse_sequence()
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output
+            .iter()
+            .map(|length| length.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// Solve KMP
+///
+/// Return the failure array of the dna string.
+///
+pub fn solve(dna_string: &DnaSeq) -> Vec<usize> {
+    matching::failure_array(dna_string.as_bytes())
+}
@@ -0,0 +1,140 @@
+//! Local Alignment with Affine Gap Penalty (https://rosalind.info/problems/laff/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (each having length at most 10,000 aa).
+//!
+//! Return: The maximum local alignment score of s and t, followed by substrings r and u of s
+//!         and t, respectively, that correspond to the optimal local alignment of s and t.
+//!         Use:
+//!         - The BLOSUM62 scoring matrix.
+//!         - Gap opening penalty equal to 11.
+//!         - Gap extension penalty equal to 1.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{Gap, LocalAlignment, Matrix, Scoring, Substitution};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const SCORING: Scoring = Scoring {
+    substitution: Substitution::Matrix(&Matrix::BLOSUM62),
+    gap: Gap::Affine {
+        open: 11,
+        extend: 1,
+    },
+};
+
+/// Rosalind problem LAFF
+pub struct Laff;
+
+impl Problem for Laff {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = LocalAlignment;
+
+    const ID: &'static str = "laff";
+    const TITLE: &'static str = "Local Alignment with Affine Gap Penalty";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_8
+        PLEASANTLY
+        >Rosalind_18
+        MEANLY
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        12
+        LEAS
+        MEAN
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        output.achieves_score(input.0.as_str(), input.1.as_str(), &SCORING)
+    }
+}
+
+/// Solve LAFF
+///
+/// Return the best local alignment score of two protein strings using BLOSUM62, a gap opening
+/// penalty of 11 and a gap extension penalty of 1, along with the aligned substrings.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<LocalAlignment> {
+    LocalAlignment::new(s.as_str(), t.as_str(), &SCORING)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset from problem",
+                s: "PLEASANTLY",
+                t: "MEANLY",
+                expected: (12, "LEAS", "MEAN"),
+            },
+            TestCase {
+                name: "Should align identical strings",
+                s: "MEANLY",
+                t: "MEANLY",
+                expected: (31, "MEANLY", "MEANLY"),
+            },
+            TestCase {
+                name: "Should open a single gap for a run of gaps",
+                s: "AAAAWWWAAAA",
+                t: "AAAAAAAA",
+                expected: (19, "AAAAWWWAAAA", "AAAAAAAA"),
+            },
+            TestCase {
+                name: "Should find nothing when every substitution scores below 0",
+                s: "WWW",
+                t: "PPP",
+                expected: (0, "", ""),
+            },
+        ];
+        for test_case in test_cases {
+            let s: ProteinSeq = test_case.s.parse().unwrap();
+            let t: ProteinSeq = test_case.t.parse().unwrap();
+            let output = solve(&s, &t).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.score,
+                    output.substring1.as_str(),
+                    output.substring2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+            assert_eq!(
+                Ok(true),
+                Laff::achieves_score(&(s, t), &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -2,84 +2,84 @@
 //!
 //! # Problem
 //!
-//!     Given: A collection of k (k <= 100) DNA strings of length at most 1 kbp each in FASTA
-//!             format.
+//! ```text
+//! Given: A collection of k (k <= 100) DNA strings of length at most 1 kbp each in FASTA
+//!         format.
 //!
-//!     Return: A longest common substring of the collection.  (If multiple solutions exist, you
-//!             may return any single solution.)
-//!
-//! # Sample Dataset
-//!     >Rosalind_1
-//!     GATTACA
-//!     >Rosalind_2
-//!     TAGACCA
-//!     >Rosalind_3
-//!     ATACA
-//!
-//! # Sample Output
-//!     AC
+//! Return: A longest common substring of the collection.  (If multiple solutions exist, you
+//!         may return any single solution.)
+//! ```
 //!
 use crate::common;
+use crate::common::index::GeneralizedSuffixArray;
+use crate::common::seq::DnaSeq;
 use crate::errors::Result;
-use std::collections::BTreeSet;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "lcsm";
+/// Length of the shortest substring reported
+const MIN_LENGTH: usize = 2;
 
-/// Return subcommand for LCSM
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem LCSM
+pub struct Lcsm;
+
+impl Problem for Lcsm {
+    type Input = Vec<DnaSeq>;
+    type Output = String;
 
-/// Run the lcsm workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let reader = common::fasta::Reader::new(std::fs::File::open(path)?);
-        let substrings = find_substrings(reader)?;
-        let longest = get_longest(substrings).unwrap_or_default();
-        println!("{}", longest);
-        return Ok(());
+    const ID: &'static str = "lcsm";
+    const TITLE: &'static str = "Finding a Shared Motif";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_1
+        GATTACA
+        >Rosalind_2
+        TAGACCA
+        >Rosalind_3
+        ATACA
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        AC
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let records = common::load_fasta(&input)?;
+        records
+            .iter()
+            .map(|record| record.parse_sequence())
+            .collect()
     }
-    Err(common::argument_err())
-}
 
-/// Get longest substring
-fn get_longest(substrings: BTreeSet<String>) -> Option<String> {
-    let mut sorted = Vec::from_iter(substrings);
-    sorted.sort_by_key(|s| std::cmp::Reverse(s.len()));
-    sorted.first().map(|substring| substring.to_owned())
-}
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input)
+    }
 
-/// Find all shared substrings
-fn find_substrings<R>(reader: common::fasta::Reader<R>) -> Result<BTreeSet<String>>
-where
-    R: std::io::Read,
-{
-    let mut output = BTreeSet::new();
-    for (i, result) in reader.iter().enumerate() {
-        let record = result?;
-        if i == 0 {
-            output = chop_sequence(&record.sequence)?;
-        } else {
-            for substring in output.clone() {
-                if !record.sequence.contains(&substring) {
-                    output.remove(&substring);
-                }
-            }
-        }
+    fn format(output: &Self::Output) -> String {
+        output.to_owned()
     }
-    Ok(output)
 }
 
-/// Chop sequence into substrings
-fn chop_sequence(seq: &str) -> Result<BTreeSet<String>> {
-    let mut substrings = BTreeSet::new();
-    for len in 2..=seq.len() {
-        for chunk in seq.as_bytes().windows(len) {
-            let substring = std::str::from_utf8(chunk)?.to_owned();
-            substrings.insert(substring);
+/// Solve LCSM
+///
+/// Return a longest common substring of the sequences, the lexicographically smallest if there
+/// are several.  An empty string is returned if the sequences share no substring of length 2 or
+/// more.
+///
+pub fn solve<S>(sequences: &[S]) -> Result<String>
+where
+    S: AsRef<str>,
+{
+    let index = GeneralizedSuffixArray::new(
+        &sequences
+            .iter()
+            .map(|sequence| sequence.as_ref().as_bytes())
+            .collect::<Vec<&[u8]>>(),
+    );
+    Ok(match index.longest_common_substring() {
+        Some((k, offset, length)) if length >= MIN_LENGTH => {
+            sequences[k].as_ref()[offset..offset + length].to_owned()
         }
-    }
-    Ok(substrings)
+        _ => String::new(),
+    })
 }
 
 #[cfg(test)]
@@ -87,27 +87,33 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_chop_sequence() {
+    fn test_solve() {
         struct TestCase<'a> {
             name: &'a str,
-            seq: &'a str,
-            expected: Result<BTreeSet<String>>,
+            sequences: Vec<&'a str>,
+            expected: &'a str,
         }
-        let test_cases = [TestCase {
-            name: "Should chop up sequences",
-            seq: "AAAC",
-            expected: Ok(BTreeSet::from([
-                "AA".to_owned(),
-                "AC".to_owned(),
-                "AAA".to_owned(),
-                "AAC".to_owned(),
-                "AAAC".to_owned(),
-            ])),
-        }];
+        let test_cases = [
+            TestCase {
+                name: "Should find a longest common substring",
+                sequences: vec!["GATTACA", "TAGACCA", "ATACA"],
+                expected: "AC",
+            },
+            TestCase {
+                name: "Should ignore common substrings of length 1",
+                sequences: vec!["ACGT", "TGCA"],
+                expected: "",
+            },
+            TestCase {
+                name: "Should find nothing without sequences",
+                sequences: vec![],
+                expected: "",
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
-                test_case.expected,
-                chop_sequence(test_case.seq),
+                Ok(test_case.expected.to_owned()),
+                solve(&test_case.sequences),
                 "{}",
                 test_case.name
             );
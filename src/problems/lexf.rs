@@ -1,72 +1,90 @@
 //! Enumerating k-mers Lexicographically (https://rosalind.info/problems/lexf/)
 //!
 //! # Problem
-//!     Given: A collection of at most 10 symbols defining an ordered alphabet, and a positive
-//!             integer n (n <= 10).
+//! ```text
+//! Given: A collection of at most 10 symbols defining an ordered alphabet, and a positive
+//!         integer n (n <= 10).
 //!
-//!     Return: All strings of length n that can be formed from the alphabet, ordered
-//!             lexicographically (use the standard order of symbols in the English alphabet).
-//!
-//! # Sample Dataset
-//!     A C G T
-//!     2
-//!
-//! # Sample Output
-//!     AA
-//!     AC
-//!     AG
-//!     AT
-//!     CA
-//!     CC
-//!     CG
-//!     CT
-//!     GA
-//!     GC
-//!     GG
-//!     GT
-//!     TA
-//!     TC
-//!     TG
-//!     TT
+//! Return: All strings of length n that can be formed from the alphabet, ordered
+//!         lexicographically (use the standard order of symbols in the English alphabet).
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::errors::Result;
+use crate::problems::Problem;
 use itertools::Itertools;
 
-pub const SUBCOMMAND: &str = "lexf";
 const LINE_DELIMITER: &str = "\n";
 const BASE_DELIMITER: &str = " ";
 
-/// Return the subcommand for LEXF
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem LEXF
+pub struct Lexf;
 
-/// Run the lexf workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (alphabet, n) = parse_input(input)?;
-        let output = permute(&alphabet, n)?;
-        for o in output {
-            println!("{}", o);
-        }
-        return Ok(());
+impl Problem for Lexf {
+    type Input = (Vec<u8>, usize);
+    type Output = Vec<String>;
+
+    const ID: &'static str = "lexf";
+    const TITLE: &'static str = "Enumerating k-mers Lexicographically";
+    const SAMPLE_DATASET: &'static str = "
+        A C G T
+        2
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        AA
+        AC
+        AG
+        AT
+        CA
+        CC
+        CG
+        CT
+        GA
+        GC
+        GG
+        GT
+        TA
+        TC
+        TG
+        TT
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.join("\n")
     }
-    Err(common::argument_err())
 }
 
 /// Parse input
 fn parse_input(input: String) -> Result<(Vec<u8>, usize)> {
-    let input: Vec<&str> = input.split(LINE_DELIMITER).collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let lines: Vec<&str> = input.split(LINE_DELIMITER).collect();
+    if lines.len() != 2 {
+        return Err(common::invalid_input(
+            "expected an alphabet and an integer n",
+        ));
     }
-    let alphabet = input[0].replace(BASE_DELIMITER, "").as_bytes().to_vec();
-    let n = input[1].parse::<usize>()?;
+    let alphabet = lines[0].replace(BASE_DELIMITER, "").as_bytes().to_vec();
+    let n = common::parse_token::<usize>(&input, lines[1])?;
     Ok((alphabet, n))
 }
 
+/// Solve LEXF
+///
+/// Return all strings of length n formed from the alphabet, ordered lexicographically by the order
+/// of the symbols in the alphabet.
+///
+pub fn solve(alphabet: &[u8], n: usize) -> Result<Vec<String>> {
+    permute(alphabet, n)
+}
+
 /// Create cartesian product
 fn permute(alphabet: &[u8], length: usize) -> Result<Vec<String>> {
     let alpha = alphabet.iter().map(|b| vec![b]);
@@ -1,43 +1,81 @@
 //! Longest Increasing Subsequence
 //!
 //! # Problem
-//!     Given: A positive integer n <= 10000 followed by a permutation pi of length n.
+//! ```text
+//! Given: A positive integer n <= 10000 followed by a permutation pi of length n.
 //!
-//!     Return: A longest increasing subsequence of pi, followed by a longest decreasing
-//!             subsequence of pi.
-//!
-//! # Sample Dataset
-//!     5
-//!     5 1 4 2 3
-//!
-//! # Sample Output
-//!     1 2 3
-//!     5 4 2
+//! Return: A longest increasing subsequence of pi, followed by a longest decreasing
+//!         subsequence of pi.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::errors::Result;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "lgis";
 const LINE_DELIMITER: &str = "\n";
 const DELIMITER: &str = " ";
 
-/// Return subcommand for LGIS
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+/// Rosalind problem LGIS
+pub struct Lgis;
+
+impl Problem for Lgis {
+    type Input = Vec<i64>;
+    type Output = Output;
+
+    const ID: &'static str = "lgis";
+    const TITLE: &'static str = "Longest Increasing Subsequence";
+    const SAMPLE_DATASET: &'static str = "
+        5
+        5 1 4 2 3
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        1 2 3
+        5 4 2
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve LGIS
+///
+/// Return a longest increasing subsequence and a longest decreasing subsequence of pi.
+///
+pub fn solve(pi: &[i64]) -> Output {
+    Output {
+        increasing: longest(pi, |left, right| left < right),
+        decreasing: longest(pi, |left, right| left > right),
+    }
+}
+
+/// Longest increasing and decreasing subsequences
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    /// A longest increasing subsequence
+    pub increasing: Vec<i64>,
+    /// A longest decreasing subsequence
+    pub decreasing: Vec<i64>,
 }
 
-/// Run the lgis workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let pi = parse_input(input)?;
-        let longest_inc = longest(&pi, |left, right| left < right);
-        let longest_dec = longest(&pi, |left, right| left > right);
-        println!("{}", format_output(&longest_inc));
-        println!("{}", format_output(&longest_dec));
-        return Ok(());
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\n{}",
+            format_output(&self.increasing),
+            format_output(&self.decreasing)
+        )
     }
-    Err(common::argument_err())
 }
 
 fn format_output(output: &[i64]) -> String {
@@ -50,14 +88,16 @@ fn format_output(output: &[i64]) -> String {
 
 /// Parse input
 fn parse_input(input: String) -> Result<Vec<i64>> {
-    let input: Vec<&str> = input.split(LINE_DELIMITER).collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let lines: Vec<&str> = input.split(LINE_DELIMITER).collect();
+    if lines.len() != 2 {
+        return Err(common::invalid_input(
+            "expected an integer n and a permutation",
+        ));
     }
-    let pi = input[1]
+    let pi = lines[1]
         .split(DELIMITER)
-        .map(|x| x.parse::<i64>())
-        .collect::<std::result::Result<_, std::num::ParseIntError>>()?;
+        .map(|x| common::parse_token::<i64>(&input, x))
+        .collect::<Result<_>>()?;
     Ok(pi)
 }
 
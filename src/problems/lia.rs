@@ -1,57 +1,76 @@
 //! Independent Alleles (https://rosalind.info/problems/lia/)
 //!
 //! # Problem
-//!     Given: Two positive integers k (k <= 7) and N (N <= 2^k).  In this problem, we begin with
-//!             Tom, who in the 0th generation has genotype Aa Bb.  Tom has two children in the 1st
-//!             generation, each of whom has two children, and so on.  Each organism always mates
-//!             with an organism having genotype Aa Bb.
+//! ```text
+//! Given: Two positive integers k (k <= 7) and N (N <= 2^k).  In this problem, we begin with
+//!         Tom, who in the 0th generation has genotype Aa Bb.  Tom has two children in the 1st
+//!         generation, each of whom has two children, and so on.  Each organism always mates
+//!         with an organism having genotype Aa Bb.
 //!
-//!     Return: The probability that at least N Aa Bb organisms will belong to the k-th generation
-//!             of Tom's family tree (don't count the Aa Bb mates at each level).  Assume that
-//!             Mendel's second law holds for the factors.
-//!
-//! # Sample Dataset
-//!     2 1
-//!
-//! # Sample Output
-//!     0.684
+//! Return: The probability that at least N Aa Bb organisms will belong to the k-th generation
+//!         of Tom's family tree (don't count the Aa Bb mates at each level).  Assume that
+//!         Mendel's second law holds for the factors.
+//! ```
 //!
 use crate::common;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
 
-pub const SUBCOMMAND: &str = "lia";
 const DELIMITER: &str = " ";
 
-/// Return the subcommand for LIA
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+/// Rosalind problem LIA
+pub struct Lia;
 
-/// Run the lia workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let (k, n) = parse_input(input)?;
-        let output = prob(k, n)?;
-        println!("{}", output);
-        return Ok(());
+impl Problem for Lia {
+    type Input = (u32, u32);
+    type Output = f64;
+
+    const ID: &'static str = "lia";
+    const TITLE: &'static str = "Independent Alleles";
+    const SAMPLE_DATASET: &'static str = "
+        2 1
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        0.684
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        parse_input(common::load_simple(&input)?)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(input.0, input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
 }
 
 /// Parse input
 fn parse_input(input: String) -> Result<(u32, u32)> {
-    let input: Vec<&str> = input.split(DELIMITER).collect();
-    if input.len() != 2 {
-        return Err(Error::new(ErrorKind::IO, "invalid input"));
+    let tokens: Vec<&str> = input.split(DELIMITER).collect();
+    if tokens.len() != 2 {
+        return Err(common::invalid_input("expected two integers k and n"));
     }
-    let k = input[0].parse::<u32>()?;
-    let n = input[1].parse::<u32>()?;
+    let k = common::parse_token::<u32>(&input, tokens[0])?;
+    let n = common::parse_token::<u32>(&input, tokens[1])?;
     Ok((k, n))
 }
 
+/// Solve LIA
+///
+/// Return the probability that at least n Aa Bb organisms belong to the k-th generation.
+///
+pub fn solve(k: u32, n: u32) -> Result<f64> {
+    prob(k, n)
+}
+
 /// Calculate the probability
 fn prob(k: u32, n: u32) -> Result<f64> {
     let mut prob = 0.0;
@@ -69,7 +88,7 @@ fn binom_coeff(n: u32, k: u32) -> Result<u128> {
     let coeff = factorial(n) / (factorial(k) * factorial(n - k));
     coeff
         .to_u128()
-        .ok_or_else(|| Error::new(ErrorKind::User, "input too large"))
+        .ok_or_else(|| Error::new(ErrorKind::Overflow, "input too large"))
 }
 
 /// Calculate the factorial
@@ -121,17 +140,17 @@ mod tests {
             TestCase {
                 name: "Should return 1",
                 n: 0,
-                expected: BigUint::from(1 as u32),
+                expected: BigUint::from(1_u32),
             },
             TestCase {
                 name: "Should return 1",
                 n: 1,
-                expected: BigUint::from(1 as u32),
+                expected: BigUint::from(1_u32),
             },
             TestCase {
                 name: "Should return 24",
                 n: 4,
-                expected: BigUint::from(24 as u32),
+                expected: BigUint::from(24_u32),
             },
         ];
         for test_case in test_cases {
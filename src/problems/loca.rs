@@ -0,0 +1,122 @@
+//! Local Alignment with Scoring Matrix (https://rosalind.info/problems/loca/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two protein strings s and t in FASTA format (each having length at most 1000 aa).
+//!
+//! Return: A maximum alignment score along with substrings r and u of s and t, respectively,
+//!         which produce this maximum alignment score (multiple solutions may exist, in which
+//!         case you may output any one).  Use:
+//!         - The PAM250 scoring matrix.
+//!         - Linear gap penalty equal to 5.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{Gap, LocalAlignment, Matrix, Scoring, Substitution};
+use crate::common::seq::ProteinSeq;
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const SCORING: Scoring = Scoring {
+    substitution: Substitution::Matrix(&Matrix::PAM250),
+    gap: Gap::Linear(5),
+};
+
+/// Rosalind problem LOCA
+pub struct Loca;
+
+impl Problem for Loca {
+    type Input = (ProteinSeq, ProteinSeq);
+    type Output = LocalAlignment;
+
+    const ID: &'static str = "loca";
+    const TITLE: &'static str = "Local Alignment with Scoring Matrix";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_80
+        MEANLYPRTEINSTRING
+        >Rosalind_21
+        PLEASANTLYEINSTEIN
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        23
+        LYPRTEINSTRIN
+        LEASANTLYEINSTEIN
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        output.achieves_score(input.0.as_str(), input.1.as_str(), &SCORING)
+    }
+}
+
+/// Solve LOCA
+///
+/// Return the best local alignment score of two protein strings using PAM250 and a linear gap
+/// penalty of 5, along with the aligned substrings.
+///
+pub fn solve(s: &ProteinSeq, t: &ProteinSeq) -> Result<LocalAlignment> {
+    LocalAlignment::new(s.as_str(), t.as_str(), &SCORING)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_achieves_score() {
+        struct TestCase<'a> {
+            name: &'a str,
+            output: (i32, &'a str, &'a str),
+            expected: bool,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should accept substrings achieving the score",
+                output: (23, "LYPRTEINSTRIN", "LYEINSTEIN"),
+                expected: true,
+            },
+            TestCase {
+                name: "Should reject substrings not achieving the score",
+                output: (24, "LYPRTEINSTRIN", "LYEINSTEIN"),
+                expected: false,
+            },
+            TestCase {
+                name: "Should reject strings that are not substrings of the input",
+                output: (23, "LYPRTEINSTRING", "LYEINSTEINS"),
+                expected: false,
+            },
+        ];
+        let input = (
+            "MEANLYPRTEINSTRING".parse().unwrap(),
+            "PLEASANTLYEINSTEIN".parse().unwrap(),
+        );
+        for test_case in test_cases {
+            let output = LocalAlignment {
+                score: test_case.output.0,
+                substring1: test_case.output.1.to_owned(),
+                substring2: test_case.output.2.to_owned(),
+            };
+            assert_eq!(
+                Ok(test_case.expected),
+                Loca::achieves_score(&input, &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
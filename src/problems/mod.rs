@@ -1,18 +1,41 @@
+//! Solutions to individual Rosalind problems
+//!
+//! Each module exposes a `solve` function that takes the parsed problem input and returns the
+//! typed answer, along with a type implementing `Problem` that is registered with the command
+//! line through `registry`.
+//!
+use crate::common;
+use crate::common::fasta;
+use crate::common::input::Input;
+use crate::errors::{Error, ErrorKind, Result};
+
+pub mod ba9i;
+pub mod ba9n;
+pub mod ba9o;
 pub mod cons;
 pub mod dna;
+pub mod edit;
+pub mod edta;
 pub mod fib;
 pub mod fibd;
+pub mod gaff;
 pub mod gc;
+pub mod glob;
 pub mod grph;
 pub mod hamm;
 pub mod iev;
 pub mod iprb;
+pub mod kmp;
+pub mod laff;
 pub mod lcsm;
 pub mod lexf;
 pub mod lgis;
 pub mod lia;
+pub mod loca;
 pub mod mprt;
 pub mod mrna;
+pub mod mult;
+pub mod oap;
 pub mod orf;
 pub mod perm;
 pub mod prot;
@@ -20,6 +43,391 @@ pub mod prtm;
 pub mod revc;
 pub mod revp;
 pub mod rna;
+pub mod sims;
+pub mod smgb;
 pub mod splc;
 pub mod subs;
 pub mod tran;
+pub mod verify;
+
+const BASE_URL: &str = "https://rosalind.info/problems";
+pub const FORMAT_ARG: &str = "format";
+
+/// Every registered problem in the order they are listed on the command line
+static REGISTRY: &[&dyn DynProblem] = &[
+    &dna::Dna,
+    &rna::Rna,
+    &revc::Revc,
+    &fib::Fib,
+    &gc::Gc,
+    &hamm::Hamm,
+    &iprb::Iprb,
+    &prot::Prot,
+    &subs::Subs,
+    &cons::Cons,
+    &fibd::Fibd,
+    &grph::Grph,
+    &iev::Iev,
+    &lcsm::Lcsm,
+    &lia::Lia,
+    &mprt::Mprt,
+    &mrna::Mrna,
+    &orf::Orf,
+    &perm::Perm,
+    &prtm::Prtm,
+    &revp::Revp,
+    &splc::Splc,
+    &lexf::Lexf,
+    &lgis::Lgis,
+    &tran::Tran,
+    &edit::Edit,
+    &edta::Edta,
+    &glob::Glob,
+    &gaff::Gaff,
+    &loca::Loca,
+    &laff::Laff,
+    &sims::Sims,
+    &oap::Oap,
+    &smgb::Smgb,
+    &mult::Mult,
+    &ba9i::Ba9i,
+    &ba9n::Ba9n,
+    &ba9o::Ba9o,
+    &kmp::Kmp,
+];
+
+/// Output format of an answer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Plain text, as expected by Rosalind
+    Text,
+    /// JSON document of the typed answer
+    Json,
+}
+
+impl Format {
+    /// Names of the formats, as accepted on the command line
+    pub const NAMES: [&'static str; 2] = ["text", "json"];
+}
+
+impl std::str::FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::new(
+                ErrorKind::User,
+                &format!("unknown format: {}", s),
+            )),
+        }
+    }
+}
+
+/// A Rosalind problem
+///
+/// A problem ties together its metadata, the clap subcommand used to invoke it, a parser that
+/// turns the subcommand arguments into the typed input, the solver, and a formatter that renders
+/// the answer the way Rosalind expects it.
+///
+pub trait Problem {
+    /// Parsed problem input
+    type Input;
+    /// Typed answer to the problem
+    type Output: serde::Serialize;
+
+    /// Rosalind ID of the problem, which is also the name of the subcommand
+    const ID: &'static str;
+    /// Title of the problem
+    const TITLE: &'static str;
+    /// Sample dataset from the problem statement
+    const SAMPLE_DATASET: &'static str;
+    /// Sample output from the problem statement
+    const SAMPLE_OUTPUT: &'static str;
+    /// How an answer is compared against the sample output
+    const COMPARISON: verify::Comparison = verify::Comparison::Exact;
+
+    /// Return the URL of the problem on Rosalind
+    fn url() -> String {
+        format!("{}/{}/", BASE_URL, Self::ID)
+    }
+
+    /// Return the subcommand for the problem
+    fn command() -> clap::Command<'static> {
+        crate::common::subcommand_file(Self::ID)
+    }
+
+    /// Parse the subcommand arguments into the problem input
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input>;
+
+    /// Solve the problem
+    fn solve(input: Self::Input) -> Result<Self::Output>;
+
+    /// Format the answer as text
+    fn format(output: &Self::Output) -> String;
+
+    /// Return the answer as fasta records
+    ///
+    /// Problems whose answer is made of sequences override this along with `command` so that the
+    /// answer can be written as fasta with `--fasta-out`.
+    ///
+    fn records(_output: &Self::Output) -> Option<Vec<fasta::Record>> {
+        None
+    }
+
+    /// Check that the answer achieves the score it reports
+    ///
+    /// Problems compared by score accept any optimal answer, so they override this to recompute
+    /// the score of their answer from the input.
+    ///
+    fn achieves_score(_input: &Self::Input, _output: &Self::Output) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+/// Object safe view of a `Problem` used by the registry
+pub trait DynProblem: Sync {
+    /// Rosalind ID of the problem
+    fn id(&self) -> &'static str;
+
+    /// Title of the problem
+    fn title(&self) -> &'static str;
+
+    /// URL of the problem on Rosalind
+    fn url(&self) -> String;
+
+    /// Sample dataset from the problem statement
+    fn sample_dataset(&self) -> String;
+
+    /// Sample output from the problem statement
+    fn sample_output(&self) -> String;
+
+    /// Subcommand for the problem
+    fn command(&self) -> clap::Command<'static>;
+
+    /// Parse, solve, and format the problem
+    fn run(&self, matches: &clap::ArgMatches, format: Format) -> Result<String>;
+
+    /// Solve the sample dataset and compare the answer against the sample output
+    fn verify(&self) -> Result<verify::Verification>;
+}
+
+impl<P> DynProblem for P
+where
+    P: Problem + Sync,
+{
+    fn id(&self) -> &'static str {
+        P::ID
+    }
+
+    fn title(&self) -> &'static str {
+        P::TITLE
+    }
+
+    fn url(&self) -> String {
+        <P as Problem>::url()
+    }
+
+    fn sample_dataset(&self) -> String {
+        verify::trim_lines(P::SAMPLE_DATASET)
+    }
+
+    fn sample_output(&self) -> String {
+        verify::trim_lines(P::SAMPLE_OUTPUT)
+    }
+
+    fn command(&self) -> clap::Command<'static> {
+        <P as Problem>::command()
+    }
+
+    fn run(&self, matches: &clap::ArgMatches, format: Format) -> Result<String> {
+        let input = P::parse(matches).map_err(|e| match Input::from_matches(matches) {
+            Ok(input) => e.in_file(input.name()),
+            Err(_) => e,
+        })?;
+        let output = P::solve(input)?;
+        if let Some(records) = P::records(&output) {
+            if matches.contains_id(common::FASTA_OUT_ARG) {
+                let line_width = match matches.value_of(common::LINE_WIDTH_ARG) {
+                    Some(width) => common::parse_token(width, width)?,
+                    None => fasta::LINE_WIDTH,
+                };
+                if let Some(path) = matches.value_of(common::FASTA_OUT_ARG) {
+                    fasta::write_file(path, &records, line_width).map_err(|e| e.in_file(path))?;
+                    return Ok(String::new());
+                }
+                let fasta = fasta::to_string(&records, line_width)?;
+                return Ok(fasta.trim_end().to_owned());
+            }
+        }
+        match format {
+            Format::Text => Ok(P::format(&output)),
+            Format::Json => Ok(serde_json::to_string(&output)?),
+        }
+    }
+
+    fn verify(&self) -> Result<verify::Verification> {
+        let dataset = self.sample_dataset();
+        let matches = <P as Problem>::command()
+            .try_get_matches_from([P::ID, "--input", &dataset])
+            .map_err(|e| Error::new(ErrorKind::User, &e.to_string()))?;
+        let output = P::solve(P::parse(&matches)?)?;
+        let actual = P::format(&output);
+        let expected = self.sample_output();
+        let passed = P::COMPARISON.matches(&expected, &actual)
+            && P::achieves_score(&P::parse(&matches)?, &output)?;
+        Ok(verify::Verification {
+            passed,
+            expected,
+            actual,
+        })
+    }
+}
+
+/// Name the sequences of an answer after the problem
+///
+/// A single sequence is named after the problem ID, while several sequences are numbered, such as
+/// `orf_1`, `orf_2`, and so on.
+///
+pub fn numbered_records<I>(id: &str, sequences: I) -> Vec<fasta::Record>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let sequences: Vec<I::Item> = sequences.into_iter().collect();
+    if sequences.len() == 1 {
+        return vec![fasta::Record::with_sequence(id, sequences[0].as_ref())];
+    }
+    sequences
+        .iter()
+        .enumerate()
+        .map(|(i, sequence)| {
+            fasta::Record::with_sequence(&format!("{}_{}", id, i + 1), sequence.as_ref())
+        })
+        .collect()
+}
+
+/// Return all registered problems
+pub fn registry() -> &'static [&'static dyn DynProblem] {
+    REGISTRY
+}
+
+/// Find a registered problem by its ID
+pub fn find(id: &str) -> Option<&'static dyn DynProblem> {
+    REGISTRY.iter().find(|problem| problem.id() == id).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_str() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            expected: Option<Format>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should parse text",
+                s: "text",
+                expected: Some(Format::Text),
+            },
+            TestCase {
+                name: "Should parse json",
+                s: "json",
+                expected: Some(Format::Json),
+            },
+            TestCase {
+                name: "Should return an error on an unknown format",
+                s: "xml",
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                test_case.s.parse::<Format>().ok(),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut ids: Vec<&str> = registry().iter().map(|problem| problem.id()).collect();
+        let total = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(total, ids.len(), "{}", "Should not register an ID twice");
+    }
+
+    #[test]
+    fn test_verify() {
+        for problem in registry() {
+            // MPRT fetches its proteins from UniProt
+            if problem.id() == mprt::Mprt::ID {
+                continue;
+            }
+            let verification = problem.verify();
+            assert!(
+                verification.as_ref().is_ok_and(|v| v.passed),
+                "Should solve the sample dataset for {}: {:?}",
+                problem.id(),
+                verification,
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_fasta_out() {
+        let matches = <revc::Revc as Problem>::command()
+            .try_get_matches_from([
+                "revc",
+                "--input",
+                "AAAACCCGGT",
+                "--fasta-out",
+                "--line-width",
+                "4",
+            ])
+            .unwrap();
+        assert_eq!(
+            Ok(">revc\nACCG\nGGTT\nTT".to_owned()),
+            revc::Revc.run(&matches, Format::Text),
+            "{}",
+            "Should write the answer as wrapped fasta",
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        struct TestCase<'a> {
+            name: &'a str,
+            id: &'a str,
+            expected: Option<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find a registered problem",
+                id: "gc",
+                expected: Some("Computing GC Content"),
+            },
+            TestCase {
+                name: "Should return None for an unknown problem",
+                id: "abc",
+                expected: None,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.expected,
+                find(test_case.id).map(|problem| problem.title()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -1,53 +1,94 @@
 //! Finding a Protein Motif (https://rosalind.info/problems/mprt/)
 //!
 //! # Problem
-//!     Given: At most 15 UniProt Protein Database access IDs
+//! ```text
+//! Given: At most 15 UniProt Protein Database access IDs
 //!
-//!     Return: For each protein processing the N-glycoslation motif, output its given access ID
-//!             followed by a list of locations in the protein string where the motif can be found.
-//!
-//! # Sample Dataset
-//!     A2Z669
-//!     B5ZC00
-//!     P07204_TRBM_HUMAN
-//!     P20840_SAG1_YEAST
-//!
-//! # Sample Output
-//!     B5ZC00
-//!     85 118 142 306 395
-//!     P07204_TRBM_HUMAN
-//!     47 115 116 382 409
-//!     P20840_SAG1_YEAST
-//!     79 109 135 248 306 348 364 402 485 501 614
+//! Return: For each protein processing the N-glycoslation motif, output its given access ID
+//!         followed by a list of locations in the protein string where the motif can be found.
+//! ```
 //!
 use crate::common;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "mprt";
 const DELIMITER: &str = "\n";
 const DELIMITER_ID: &str = "_";
 
-/// Return the subcommand for MPRT
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
+/// Rosalind problem MPRT
+pub struct Mprt;
+
+impl Problem for Mprt {
+    type Input = Vec<String>;
+    type Output = Vec<Output>;
+
+    const ID: &'static str = "mprt";
+    const TITLE: &'static str = "Finding a Protein Motif";
+    const SAMPLE_DATASET: &'static str = "
+        A2Z669
+        B5ZC00
+        P07204_TRBM_HUMAN
+        P20840_SAG1_YEAST
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        B5ZC00
+        85 118 142 306 395
+        P07204_TRBM_HUMAN
+        47 115 116 382 409
+        P20840_SAG1_YEAST
+        79 109 135 248 306 348 364 402 485 501 614
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let input = common::load_simple(&input)?;
+        Ok(parse_input(&input).into_iter().map(String::from).collect())
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        let ids: Vec<&str> = input.iter().map(String::as_str).collect();
+        solve(&ids)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
 }
 
-/// Run the mprt workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
-        let ids = parse_input(&input);
-        for id in ids {
-            let record = get_fasta(get_id(id))?;
-            let indices = find_motifs(&record.sequence);
-            if !indices.is_empty() {
-                println!("{}", id);
-                println!("{}", format_indices(indices));
-            }
+/// Solve MPRT
+///
+/// Fetch each protein from UniProt and return the access IDs that contain the N-glycosylation
+/// motif, along with the 1-based locations of the motif.
+///
+pub fn solve(ids: &[&str]) -> Result<Vec<Output>> {
+    let mut output = Vec::new();
+    for id in ids {
+        let record = get_fasta(get_id(id))?;
+        let locations = find_motifs(&record.sequence);
+        if !locations.is_empty() {
+            let header = record.header();
+            output.push(Output {
+                id: id.to_string(),
+                accession: header.accession().into(),
+                organism: header.organism().map(String::from),
+                locations,
+            });
         }
-        return Ok(());
     }
-    Err(common::argument_err())
+    Ok(output)
+}
+
+/// Locations of the N-glycosylation motif in a protein
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    /// UniProt access ID as given in the input
+    pub id: String,
+    /// Accession parsed from the UniProt header
+    pub accession: String,
+    /// Organism parsed from the UniProt header
+    pub organism: Option<String>,
+    /// 1-based locations of the motif
+    pub locations: Vec<usize>,
 }
 
 /// Parse input
@@ -55,8 +96,17 @@ fn parse_input(input: &'_ str) -> Vec<&'_ str> {
     input.split(DELIMITER).collect()
 }
 
+/// Format output
+fn format_output(output: &[Output]) -> String {
+    output
+        .iter()
+        .map(|protein| format!("{}\n{}", protein.id, format_indices(&protein.locations)))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 /// Format indices
-fn format_indices(indices: Vec<usize>) -> String {
+fn format_indices(indices: &[usize]) -> String {
     if indices.is_empty() {
         return "".to_owned();
     }
@@ -74,7 +124,10 @@ fn format_indices(indices: Vec<usize>) -> String {
 fn get_fasta(id: &str) -> Result<common::fasta::Record> {
     let url = format!("http://www.uniprot.org/uniprot/{}.fasta", id);
     let resp = match reqwest::blocking::get(url) {
-        Err(_) => Err(Error::new(ErrorKind::IO, "request fails")),
+        Err(e) => Err(Error::new(
+            ErrorKind::Network,
+            &format!("request for {} fails: {}", id, e),
+        )),
         Ok(resp) => Ok(resp),
     }?;
     let mut reader = common::fasta::Reader::new(resp);
@@ -92,7 +145,7 @@ fn get_id(id: &'_ str) -> &'_ str {
 }
 
 /// Find the start indices of the glycosylation motif in a sequence
-fn find_motifs(sequence: &str) -> Vec<usize> {
+pub fn find_motifs(sequence: &str) -> Vec<usize> {
     let mut indices = Vec::new();
     let sequence_chars: Vec<char> = sequence.chars().collect();
     for (i, substring) in sequence_chars.windows(4).enumerate() {
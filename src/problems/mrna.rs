@@ -1,79 +1,90 @@
 //! Inferring mRNA from Protein (https://rosalind.info/problems/mrna/)
 //!
 //! # Problem
-//!     Given: A protein string of length at most 100 aa.
+//! ```text
+//! Given: A protein string of length at most 100 aa.
 //!
-//!     Return: The total number of different RNA strings from which the protein chould have been
-//!             translated, modulo 1,000,000.  (Don't neglect the importance of the stop codon in
-//!             protein translation.)
-//!
-//! # Sample Dataset
-//!     MA
-//!
-//! # Sample Output
-//!     12
+//! Return: The total number of different RNA strings from which the protein chould have been
+//!         translated, modulo 1,000,000.  (Don't neglect the importance of the stop codon in
+//!         protein translation.)
+//! ```
 //!
 use crate::common;
+use crate::common::genetic_code::{self, GeneticCode};
+use crate::common::seq::ProteinSeq;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "mrna";
+/// Rosalind problem MRNA
+pub struct Mrna;
 
-/// Return subcommand for MRNA
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Mrna {
+    type Input = (ProteinSeq, &'static GeneticCode);
+    type Output = u64;
+
+    const ID: &'static str = "mrna";
+    const TITLE: &'static str = "Inferring mRNA from Protein";
+    const SAMPLE_DATASET: &'static str = "
+        MA
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        12
+    ";
+
+    fn command() -> clap::Command<'static> {
+        common::with_table(common::subcommand_file(Self::ID))
+    }
 
-/// Run the mrna workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let protein_string = common::load_simple(path)?;
-        let output = total(&protein_string)?;
-        println!("{}", output);
-        return Ok(());
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        Ok((
+            common::load_sequence(&input)?,
+            common::genetic_code(matches)?,
+        ))
     }
-    Err(common::argument_err())
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+}
+
+/// Solve MRNA
+///
+/// Return the number of RNA strings that could have been translated into the protein string,
+/// modulo 1,000,000, under the genetic code.
+///
+pub fn solve(protein_string: &ProteinSeq, code: &GeneticCode) -> Result<u64> {
+    total(protein_string.as_str(), code)
 }
 
 /// Calculate the total number of different RNA strings modulo 1,000,000
-fn total(protein_string: &str) -> Result<u64> {
+fn total(protein_string: &str, code: &GeneticCode) -> Result<u64> {
     if protein_string.is_empty() {
         return Ok(0);
     }
     let mut output = 1;
     for protein in protein_string.as_bytes() {
-        let possibilities = rna_possbilities(protein)?;
+        let possibilities = rna_possbilities(protein, code)?;
         output *= possibilities;
         if output > 1_000_000 {
             output %= 1_000_000;
         }
     }
-    Ok(output * 3)
+    Ok(output * code.codon_count(genetic_code::STOP) as u64 % 1_000_000)
 }
 
-/// Given a protein, return the number of possible RNA strings based on the RNA codon table
-fn rna_possbilities(protein: &u8) -> Result<u64> {
-    match protein {
-        b'A' => Ok(4),
-        b'C' => Ok(2),
-        b'D' => Ok(2),
-        b'E' => Ok(2),
-        b'F' => Ok(2),
-        b'G' => Ok(4),
-        b'H' => Ok(2),
-        b'I' => Ok(3),
-        b'K' => Ok(2),
-        b'L' => Ok(6),
-        b'M' => Ok(1),
-        b'N' => Ok(2),
-        b'P' => Ok(4),
-        b'Q' => Ok(2),
-        b'R' => Ok(6),
-        b'S' => Ok(6),
-        b'T' => Ok(4),
-        b'V' => Ok(4),
-        b'W' => Ok(1),
-        b'Y' => Ok(2),
-        _ => Err(Error::new(ErrorKind::IO, "unrecognized protein base")),
+/// Given a protein, return the number of possible RNA strings based on the genetic code
+fn rna_possbilities(protein: &u8, code: &GeneticCode) -> Result<u64> {
+    match code.codon_count(*protein) {
+        0 => Err(Error::new(
+            ErrorKind::Alphabet,
+            &format!("unrecognized protein base: {}", *protein as char),
+        )),
+        count => Ok(count as u64),
     }
 }
 
@@ -86,17 +97,42 @@ mod tests {
         struct TestCase<'a> {
             name: &'a str,
             protein_string: &'a str,
+            table: u8,
             expected: Result<u64>,
         }
-        let test_cases = [TestCase {
-            name: "Sample Dataset",
-            protein_string: "MA",
-            expected: Ok(12),
-        }];
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                protein_string: "MA",
+                table: 1,
+                expected: Ok(12),
+            },
+            TestCase {
+                name: "Should count codons of the vertebrate mitochondrial code",
+                protein_string: "MW",
+                table: 2,
+                expected: Ok(16),
+            },
+            TestCase {
+                name: "Should count the codon that is a stop only at the end of a gene",
+                protein_string: "MA",
+                table: 27,
+                expected: Ok(4),
+            },
+            TestCase {
+                name: "Should count every codon that is a stop only at the end of a gene",
+                protein_string: "MA",
+                table: 31,
+                expected: Ok(8),
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                total(test_case.protein_string),
+                total(
+                    test_case.protein_string,
+                    GeneticCode::from_id(test_case.table).unwrap()
+                ),
                 "{}",
                 test_case.name,
             );
@@ -0,0 +1,287 @@
+//! Multiple Alignment (https://rosalind.info/problems/mult/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: A collection of four DNA strings of length at most 10 bp in FASTA format.
+//!
+//! Return: A multiple alignment of the strings having maximum score, where we score matched
+//!         symbols 0 (including matched gap symbols) and all mismatched symbols -1 (thus
+//!         incorporating a linear gap penalty of 1).
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Scoring, GAP};
+use crate::common::msa;
+use crate::common::seq::DnaSeq;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+/// Largest number of strings aligned exactly
+const MAX_SEQUENCES: usize = 12;
+/// Largest number of columns tried over every cell of the dynamic programming table
+const MAX_WORK: usize = 1 << 24;
+
+/// Rosalind problem MULT
+pub struct Mult;
+
+impl Problem for Mult {
+    type Input = Vec<DnaSeq>;
+    type Output = Output;
+
+    const ID: &'static str = "mult";
+    const TITLE: &'static str = "Multiple Alignment";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_7
+        ATATCCG
+        >Rosalind_35
+        TCCG
+        >Rosalind_23
+        ATGTACTG
+        >Rosalind_44
+        ATGTCTG
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        -18
+        ATAT-CCG
+        -T---CCG
+        ATGTACTG
+        ATGT-CTG
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta(&input)?
+            .iter()
+            .map(|record| record.parse_sequence())
+            .collect()
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        let length = output.rows.first().map_or(0, String::len);
+        let rows_match = output.rows.len() == input.len()
+            && output.rows.iter().all(|row| row.len() == length)
+            && output
+                .rows
+                .iter()
+                .zip(input)
+                .all(|(row, sequence)| align::ungapped(row) == sequence.as_str());
+        Ok(
+            rows_match
+                && msa::sum_of_pairs(&output.rows, &Scoring::edit_distance()) == output.score,
+        )
+    }
+}
+
+/// Solve MULT
+///
+/// Return a multiple alignment of the DNA strings with the best sum-of-pairs score, where
+/// mismatches and gaps score -1, along with the score.
+///
+/// The alignment is exact, trying every column at every cell of a table with a cell for every
+/// combination of prefixes of the strings, so it is only practical for a few short strings.  An
+/// error is returned for more than `MAX_SEQUENCES` strings or more than `MAX_WORK` columns in all.
+/// Among equally good columns, the one advancing the fewest strings is preferred when tracing back.
+///
+pub fn solve(sequences: &[DnaSeq]) -> Result<Output> {
+    if sequences.len() > MAX_SEQUENCES {
+        return Err(Error::new(
+            ErrorKind::Validation,
+            &format!(
+                "too many strings to align exactly, found {} but at most {}",
+                sequences.len(),
+                MAX_SEQUENCES
+            ),
+        ));
+    }
+    let sequences: Vec<&[u8]> = sequences.iter().map(|s| s.as_bytes()).collect();
+    let too_large = || {
+        Error::new(
+            ErrorKind::Validation,
+            &format!("too many columns to align exactly, at most {}", MAX_WORK),
+        )
+    };
+    let mut strides = Vec::with_capacity(sequences.len());
+    let mut cells: usize = 1;
+    for sequence in &sequences {
+        strides.push(cells);
+        cells = cells
+            .checked_mul(sequence.len() + 1)
+            .filter(|cells| *cells <= MAX_WORK)
+            .ok_or_else(too_large)?;
+    }
+    // Subsets of the strings advanced by a column, as bit masks
+    let columns = 1usize..1 << sequences.len();
+    if cells.saturating_mul(columns.len()) > MAX_WORK {
+        return Err(too_large());
+    }
+    let coordinates = |cell: usize| -> Vec<usize> {
+        sequences
+            .iter()
+            .zip(&strides)
+            .map(|(sequence, stride)| cell / stride % (sequence.len() + 1))
+            .collect()
+    };
+    let mut scores = vec![i32::MIN; cells];
+    let mut moves = vec![0; cells];
+    scores[0] = 0;
+    for cell in 1..cells {
+        let position = coordinates(cell);
+        for column in columns.clone() {
+            let advanced = |k: usize| column >> k & 1 == 1;
+            if (0..sequences.len()).any(|k| advanced(k) && position[k] == 0) {
+                continue;
+            }
+            let previous: usize = cell
+                - (0..sequences.len())
+                    .filter(|k| advanced(*k))
+                    .map(|k| strides[k])
+                    .sum::<usize>();
+            let symbols: Vec<u8> = (0..sequences.len())
+                .map(|k| match advanced(k) {
+                    true => sequences[k][position[k] - 1],
+                    false => GAP,
+                })
+                .collect();
+            let score = scores[previous] + column_score(&symbols);
+            if score > scores[cell] {
+                scores[cell] = score;
+                moves[cell] = column;
+            }
+        }
+    }
+    let mut rows = vec![Vec::new(); sequences.len()];
+    let mut cell = cells - 1;
+    while cell > 0 {
+        let position = coordinates(cell);
+        let column = moves[cell];
+        for (k, row) in rows.iter_mut().enumerate() {
+            if column >> k & 1 == 1 {
+                row.push(sequences[k][position[k] - 1]);
+                cell -= strides[k];
+            } else {
+                row.push(GAP);
+            }
+        }
+    }
+    Ok(Output {
+        score: scores[cells - 1],
+        rows: rows
+            .into_iter()
+            .map(|row| row.into_iter().rev().map(char::from).collect())
+            .collect(),
+    })
+}
+
+/// Return the sum-of-pairs score of a column, where pairs of different symbols score -1
+fn column_score(symbols: &[u8]) -> i32 {
+    let mut score = 0;
+    for (i, a) in symbols.iter().enumerate() {
+        score -= symbols[i + 1..].iter().filter(|b| *b != a).count() as i32;
+    }
+    score
+}
+
+/// Sum-of-pairs score and the aligned strings
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    pub score: i32,
+    pub rows: Vec<String>,
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.score)?;
+        for row in &self.rows {
+            write!(f, "\n{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+            expected: i32,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should find the best alignment of the sample",
+                sequences: vec!["ATATCCG", "TCCG", "ATGTACTG", "ATGTCTG"],
+                expected: -18,
+            },
+            TestCase {
+                name: "Should find a better alignment than progressive alignment",
+                sequences: vec!["TT", "ATTGGCAT", "GGAGAGATCG", "CGATCAATCA"],
+                expected: -44,
+            },
+            TestCase {
+                name: "Should align empty strings",
+                sequences: vec!["", "AC", "", "A"],
+                expected: -7,
+            },
+        ];
+        for test_case in test_cases {
+            let sequences: Vec<DnaSeq> = test_case
+                .sequences
+                .iter()
+                .map(|sequence| sequence.parse().unwrap())
+                .collect();
+            let output = solve(&sequences).unwrap();
+            assert_eq!(test_case.expected, output.score, "{}", test_case.name);
+            assert_eq!(
+                Ok(true),
+                Mult::achieves_score(&sequences, &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_limits() {
+        struct TestCase<'a> {
+            name: &'a str,
+            sequences: Vec<&'a str>,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should reject too many strings before trying their columns",
+                sequences: vec!["A"; 64],
+            },
+            TestCase {
+                name: "Should reject strings whose columns are too many to try",
+                sequences: vec!["AC"; 12],
+            },
+        ];
+        for test_case in test_cases {
+            let sequences: Vec<DnaSeq> = test_case
+                .sequences
+                .iter()
+                .map(|sequence| sequence.parse().unwrap())
+                .collect();
+            assert_eq!(
+                Err(ErrorKind::Validation),
+                solve(&sequences).map_err(|e| *e.kind()),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Overlap Alignment (https://rosalind.info/problems/oap/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two DNA strings s and t in FASTA format, each having length at most 10 kbp.
+//!
+//! Return: The score of an optimal overlap alignment of s and t, followed by an alignment of
+//!         a suffix s' of s and a prefix t' of t achieving this optimal score.  Use an
+//!         alignment score in which matching symbols count +1, substitutions count -2, and
+//!         there is a linear gap penalty of 2.  If multiple optimal alignments exist, then you
+//!         may return any one.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Alignment, Gap, Mode, Scoring, Substitution};
+use crate::common::seq::DnaSeq;
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const SCORING: Scoring = Scoring {
+    substitution: Substitution::MatchMismatch {
+        matching: 1,
+        mismatch: -2,
+    },
+    gap: Gap::Linear(2),
+};
+
+/// Rosalind problem OAP
+pub struct Oap;
+
+impl Problem for Oap {
+    type Input = (DnaSeq, DnaSeq);
+    type Output = Alignment;
+
+    const ID: &'static str = "oap";
+    const TITLE: &'static str = "Overlap Alignment";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_54
+        CTAAGGGATTCCGGTAATTAGACAG
+        >Rosalind_45
+        ATAGACCATATGTCAGTGACTGTGTAA
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        1
+        ATTAGAC-AG
+        AT-AGACCAT
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format!("{}\n{}\n{}", output.score, output.aligned1, output.aligned2)
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        let (s, t) = (input.0.as_str(), input.1.as_str());
+        if !s.ends_with(&align::ungapped(&output.aligned1))
+            || !t.starts_with(&align::ungapped(&output.aligned2))
+        {
+            return Ok(false);
+        }
+        let rows = (output.aligned1.as_bytes(), output.aligned2.as_bytes());
+        Ok(align::score(rows.0, rows.1, &SCORING, false)? == output.score)
+    }
+}
+
+/// Solve OAP
+///
+/// Return the best overlap alignment of a suffix of s against a prefix of t, where matches score
+/// 1, mismatches score -2 and gaps score -2.
+///
+pub fn solve(s: &DnaSeq, t: &DnaSeq) -> Result<Alignment> {
+    align::align(s.as_bytes(), t.as_bytes(), &SCORING, Mode::Overlap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should overlap a suffix with a prefix",
+                s: "ACGTTT",
+                t: "TTTGCA",
+                expected: (3, "TTT", "TTT"),
+            },
+            TestCase {
+                name: "Should overlap identical strings",
+                s: "ACGT",
+                t: "ACGT",
+                expected: (4, "ACGT", "ACGT"),
+            },
+            TestCase {
+                name: "Should run the overlap to the end of the first string, unlike a local alignment",
+                s: "GGACGTGG",
+                t: "ACGTCC",
+                expected: (0, "ACGTGG", "ACGTCC"),
+            },
+            TestCase {
+                name: "Should find an empty overlap",
+                s: "AAAA",
+                t: "CCCC",
+                expected: (0, "", ""),
+            },
+        ];
+        for test_case in test_cases {
+            let s: DnaSeq = test_case.s.parse().unwrap();
+            let t: DnaSeq = test_case.t.parse().unwrap();
+            let output = solve(&s, &t).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.score,
+                    output.aligned1.as_str(),
+                    output.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+            assert_eq!(
+                Ok(true),
+                Oap::achieves_score(&(s, t), &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
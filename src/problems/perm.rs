@@ -2,45 +2,70 @@
 //!
 //! # Problem
 //!
-//!     Given: A positive integer n <= 7.
+//! ```text
+//! Given: A positive integer n <= 7.
 //!
-//!     Return: The total number of permutations of length n, followed by a list of all such
-//!             permutations (in any order).
-//!
-//! # Sample Dataset
-//!     3
-//!
-//! # Sample Output
-//!     6
-//!     1 2 3
-//!     1 3 2
-//!     2 1 3
-//!     3 1 2
-//!     3 2 1
+//! Return: The total number of permutations of length n, followed by a list of all such
+//!         permutations (in any order).
+//! ```
 //!
 use crate::common;
 use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 use itertools::Itertools;
 
-pub const SUBCOMMAND: &str = "perm";
+/// Rosalind problem PERM
+pub struct Perm;
 
-/// Return the subcommand for PERM
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Perm {
+    type Input = usize;
+    type Output = Vec<Vec<usize>>;
 
-/// Run the perm workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?.parse::<usize>()?;
-        let perms = permutations(input);
-        println!("{}", perms.len());
-        for perm in perms {
-            println!("{}", format_line(&perm));
-        }
-        return Ok(());
+    const ID: &'static str = "perm";
+    const TITLE: &'static str = "Enumerating Gene Orders";
+    const SAMPLE_DATASET: &'static str = "
+        3
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        6
+        1 2 3
+        1 3 2
+        2 1 3
+        2 3 1
+        3 1 2
+        3 2 1
+    ";
+    const COMPARISON: Comparison = Comparison::Unordered;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let input = common::load_simple(&input)?;
+        common::parse_token::<usize>(&input, &input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(input))
     }
-    Err(common::argument_err())
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
+}
+
+/// Solve PERM
+///
+/// Return all permutations of the integers 1 to n.
+///
+pub fn solve(n: usize) -> Vec<Vec<usize>> {
+    permutations(n)
+}
+
+/// Format output
+fn format_output(perms: &[Vec<usize>]) -> String {
+    let mut output = vec![perms.len().to_string()];
+    output.extend(perms.iter().map(|perm| format_line(perm)));
+    output.join("\n")
 }
 
 fn format_line(line: &[usize]) -> String {
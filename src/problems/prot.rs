@@ -2,81 +2,62 @@
 //!
 //! # Problem
 //!
-//!     Given: An RNA String s corresponding to a strand of mRNA (of length n at most 10 kbp).
+//! ```text
+//! Given: An RNA String s corresponding to a strand of mRNA (of length n at most 10 kbp).
 //!
-//!     Return: The protein string encoded by s.
-//!
-//! # Sample Dataset
-//!     AUGGCCAUGGCGCCCAGAACUGAGAUCAAUAGUACCCGUAUUAACGGGUGA
-//!
-//! # Sample Output
-//!     MAMAPRTEINSTRING
+//! Return: The protein string encoded by s.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::common::genetic_code::GeneticCode;
+use crate::common::seq::{ProteinSeq, RnaSeq};
+use crate::errors::Result;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "prot";
+/// Rosalind problem PROT
+pub struct Prot;
 
-/// Return the command for PROT
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Prot {
+    type Input = (RnaSeq, &'static GeneticCode);
+    type Output = ProteinSeq;
 
-/// Run the prot workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let rna_string = common::load_simple(path)?;
-        let translated = translate(rna_string.as_bytes())?;
-        println!("{}", translated);
-        return Ok(());
+    const ID: &'static str = "prot";
+    const TITLE: &'static str = "Translating RNA into Protein";
+    const SAMPLE_DATASET: &'static str = "
+        AUGGCCAUGGCGCCCAGAACUGAGAUCAAUAGUACCCGUAUUAACGGGUGA
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        MAMAPRTEINSTRING
+    ";
+
+    fn command() -> clap::Command<'static> {
+        common::with_table(common::subcommand_file(Self::ID))
     }
-    Err(common::argument_err())
-}
 
-/// Translate RNA string to protein
-fn translate(rna_string: &[u8]) -> Result<String> {
-    let mut protein = Vec::new();
-    for codon in rna_string.chunks(3) {
-        let p = translate_codon(codon)?;
-        if let Some(x) = p {
-            protein.push(x);
-        } else {
-            break;
-        }
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        Ok((
+            common::load_sequence(&input)?,
+            common::genetic_code(matches)?,
+        ))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input.0, input.1))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    let output = String::from_utf8(protein)?;
-    Ok(output)
 }
 
-/// Translate Codon to Protein
-fn translate_codon(codon: &[u8]) -> Result<Option<u8>> {
-    let protein: Option<u8> = match codon {
-        b"GCU" | b"GCC" | b"GCA" | b"GCG" => Some(b'A'),
-        b"UGU" | b"UGC" => Some(b'C'),
-        b"GAU" | b"GAC" => Some(b'D'),
-        b"GAA" | b"GAG" => Some(b'E'),
-        b"UUU" | b"UUC" => Some(b'F'),
-        b"GGU" | b"GGC" | b"GGA" | b"GGG" => Some(b'G'),
-        b"CAU" | b"CAC" => Some(b'H'),
-        b"AUU" | b"AUC" | b"AUA" => Some(b'I'),
-        b"AAA" | b"AAG" => Some(b'K'),
-        b"UUA" | b"UUG" | b"CUU" | b"CUC" | b"CUA" | b"CUG" => Some(b'L'),
-        b"AUG" => Some(b'M'),
-        b"AAU" | b"AAC" => Some(b'N'),
-        b"CCU" | b"CCC" | b"CCA" | b"CCG" => Some(b'P'),
-        b"CAA" | b"CAG" => Some(b'Q'),
-        b"CGU" | b"CGC" | b"CGA" | b"CGG" | b"AGA" | b"AGG" => Some(b'R'),
-        b"UCU" | b"UCC" | b"UCA" | b"UCG" | b"AGU" | b"AGC" => Some(b'S'),
-        b"ACU" | b"ACC" | b"ACA" | b"ACG" => Some(b'T'),
-        b"GUU" | b"GUC" | b"GUA" | b"GUG" => Some(b'V'),
-        b"UGG" => Some(b'W'),
-        b"UAU" | b"UAC" => Some(b'Y'),
-        b"UAA" | b"UAG" | b"UGA" => None,
-        _ => {
-            return Err(Error::new(ErrorKind::User, "invalid codon"));
-        }
-    };
-    Ok(protein)
+/// Solve PROT
+///
+/// Return the protein string encoded by the RNA string, stopping at the first stop codon of the
+/// genetic code.
+///
+pub fn solve(rna_string: &RnaSeq, code: &GeneticCode) -> ProteinSeq {
+    rna_string.translate_with(code)
 }
 
 #[cfg(test)]
@@ -84,21 +65,35 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_translate() {
+    fn test_solve() {
         struct TestCase<'a> {
             name: &'a str,
-            rna_string: &'a [u8],
-            expected: Result<String>,
+            rna_string: &'a str,
+            table: u8,
+            expected: String,
         }
-        let test_cases = [TestCase {
-            name: "Sample Dataset from problem",
-            rna_string: b"AUGGCCAUGGCGCCCAGAACUGAGAUCAAUAGUACCCGUAUUAACGGGUGA",
-            expected: Ok("MAMAPRTEINSTRING".to_owned()),
-        }];
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset from problem",
+                rna_string: "AUGGCCAUGGCGCCCAGAACUGAGAUCAAUAGUACCCGUAUUAACGGGUGA",
+                table: 1,
+                expected: "MAMAPRTEINSTRING".to_owned(),
+            },
+            TestCase {
+                name: "Should translate with the vertebrate mitochondrial code",
+                rna_string: "AUGUGAAUAAGAUAA",
+                table: 2,
+                expected: "MWM".to_owned(),
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                translate(test_case.rna_string),
+                solve(
+                    &test_case.rna_string.parse().unwrap(),
+                    GeneticCode::from_id(test_case.table).unwrap()
+                )
+                .to_string(),
                 "{}",
                 test_case.name,
             );
@@ -2,38 +2,59 @@
 //!
 //! # Problem
 //!
-//!     Given: A protein string P at length at most 1000 aa.
+//! ```text
+//! Given: A protein string P at length at most 1000 aa.
 //!
-//!     Return: The total weight of P.  Consult the monoisotopic mass table.
-//!
-//! # Sample Dataset
-//!     SKADYEK
-//!
-//! # Sample Output
-//!     821.392
+//! Return: The total weight of P.  Consult the monoisotopic mass table.
+//! ```
 //!
 use crate::common;
+use crate::common::seq::ProteinSeq;
 use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "prtm";
+/// Rosalind problem PRTM
+pub struct Prtm;
 
-/// Return the command for PRTM
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Prtm {
+    type Input = ProteinSeq;
+    type Output = f64;
+
+    const ID: &'static str = "prtm";
+    const TITLE: &'static str = "Calculating Protein Mass";
+    const SAMPLE_DATASET: &'static str = "
+        SKADYEK
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        821.392
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_sequence(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input))
+    }
 
-/// Run the prtm workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let protein = common::load_simple(path)?;
-        println!("{}", mass(protein.as_bytes()));
-        return Ok(());
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
+}
+
+/// Solve PRTM
+///
+/// Return the total monoisotopic mass of the protein string.
+///
+pub fn solve(protein: &ProteinSeq) -> f64 {
+    mass(protein.as_bytes())
 }
 
 /// Calculate the mass of a protein string
-fn mass(protein: &[u8]) -> f64 {
+pub fn mass(protein: &[u8]) -> f64 {
     protein.iter().map(lookup).sum()
 }
 
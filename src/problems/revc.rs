@@ -2,49 +2,61 @@
 //!
 //! # Problem
 //!
-//!     Given: A DNA string s of lenght at most 1000 bp.
+//! ```text
+//! Given: A DNA string s of lenght at most 1000 bp.
 //!
-//!     Return: The reverse complement s^c of s.
-//!
-//! # Sample Dataset
-//!     AAAACCCGGT
-//!
-//! # Sample Output
-//!     ACCGGGTTTT
+//! Return: The reverse complement s^c of s.
+//! ```
 //!
 use crate::common;
+use crate::common::seq::DnaSeq;
 use crate::errors::Result;
+use crate::problems::{self, Problem};
 
-pub const SUBCOMMAND: &str = "revc";
+/// Rosalind problem REVC
+pub struct Revc;
 
-/// Return the command for REVC
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Revc {
+    type Input = DnaSeq;
+    type Output = DnaSeq;
 
-/// Run the revc workflow
-///
-/// Run the workflow by loading the dna string from the input text file, reverse complementing it,
-/// and printing it.
-///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let dna_string = common::load_simple(path)?;
-        let rc = reverse_complement(dna_string)?;
-        println!("{}", rc);
-        return Ok(());
+    const ID: &'static str = "revc";
+    const TITLE: &'static str = "Complementing a Strand of DNA";
+    const SAMPLE_DATASET: &'static str = "
+        AAAACCCGGT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        ACCGGGTTTT
+    ";
+
+    fn command() -> clap::Command<'static> {
+        common::subcommand_fasta_out(Self::ID)
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_sequence(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn records(output: &Self::Output) -> Option<Vec<common::fasta::Record>> {
+        Some(problems::numbered_records(Self::ID, vec![output]))
     }
-    Err(common::argument_err())
 }
 
-/// Reverse Complement a DNA string
+/// Solve REVC
 ///
-/// An error is returned if there is an invalid DNA base in the input dna_string.
+/// Return the reverse complement of the dna string.
 ///
-fn reverse_complement(dna_string: String) -> Result<String> {
-    let rc = common::dna::reverse_complement(dna_string.as_bytes())?;
-    let rc = String::from_utf8(rc.to_vec())?;
-    Ok(rc)
+pub fn solve(dna_string: &DnaSeq) -> DnaSeq {
+    dna_string.reverse_complement()
 }
 
 #[cfg(test)]
@@ -52,21 +64,21 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_reverse_complement() {
+    fn test_solve() {
         struct TestCase<'a> {
             name: &'a str,
-            dna_string: String,
-            expected: Result<String>,
+            dna_string: &'a str,
+            expected: String,
         }
         let test_cases = [TestCase {
             name: "Sample Dataset from problem",
-            dna_string: "AAAACCCGGT".into(),
-            expected: Ok("ACCGGGTTTT".into()),
+            dna_string: "AAAACCCGGT",
+            expected: "ACCGGGTTTT".into(),
         }];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                reverse_complement(test_case.dna_string),
+                solve(&test_case.dna_string.parse().unwrap()).to_string(),
                 "{}",
                 test_case.name
             );
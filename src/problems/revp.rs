@@ -1,57 +1,94 @@
 //! Locating Restriction Sites (https://rosalind.info/problems/revp/)
 //!
 //! # Problem
-//!     Given: A DNA string of length at most 1 kbp in FASTA format.
+//! ```text
+//! Given: A DNA string of length at most 1 kbp in FASTA format.
 //!
-//!     Return: The position and length of every reverse palindrome in the string having length
-//!             between 4 and 12.  You may return these pairs in any order.
-//!
-//! # Sample Dataset
-//!     > Rosalind_24
-//!     TCAATGCATGCGGGTCTATATGCAT
-//!
-//! # Sample Output
-//!     4 6
-//!     5 4
-//!     6 6
-//!     7 4
-//!     17 4
-//!     18 4
-//!     20 6
-//!     21 4
+//! Return: The position and length of every reverse palindrome in the string having length
+//!         between 4 and 12.  You may return these pairs in any order.
+//! ```
 //!
 use crate::common;
+use crate::common::seq::IupacSeq;
 use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "revp";
+/// Rosalind problem REVP
+pub struct Revp;
 
-/// Return subcommand for REVP
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Revp {
+    type Input = IupacSeq;
+    type Output = Vec<Output>;
 
-/// Run the revp workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let file = std::fs::File::open(path)?;
-        let mut reader = common::fasta::Reader::new(file);
+    const ID: &'static str = "revp";
+    const TITLE: &'static str = "Locating Restriction Sites";
+    const SAMPLE_DATASET: &'static str = "
+        > Rosalind_24
+        TCAATGCATGCGGGTCTATATGCAT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        4 6
+        5 4
+        6 6
+        7 4
+        17 4
+        18 4
+        20 6
+        21 4
+    ";
+    const COMPARISON: Comparison = Comparison::Unordered;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let mut reader = common::fasta::Reader::new(input.reader()?);
         let mut record = common::fasta::Record::new();
         reader.read(&mut record)?;
-        let locations = locate(&record.sequence)?;
-        for location in locations {
-            println!("{}", location);
-        }
-        return Ok(());
+        record.parse_sequence()
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
     }
-    Err(common::argument_err())
+}
+
+/// Solve REVP
+///
+/// Return the position and length of every reverse palindrome of length 4 to 12 in the dna
+/// string.
+///
+pub fn solve(dna_string: &IupacSeq) -> Result<Vec<Output>> {
+    locate(dna_string.as_str())
+}
+
+/// Format output
+fn format_output(output: &[Output]) -> String {
+    output
+        .iter()
+        .map(|location| location.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
 
 /// Locate restriction sites
-fn locate(dna_string: &str) -> Result<Vec<Output>> {
+///
+/// IUPAC ambiguity codes match any base they stand for, so a site is reported if it could be a
+/// reverse palindrome.
+///
+pub fn locate(dna_string: &str) -> Result<Vec<Output>> {
     let mut outputs = vec![];
     for size in 4..=12 {
         for (i, substring) in dna_string.as_bytes().windows(size).enumerate() {
-            if substring == common::dna::reverse_complement(substring)? {
+            let reverse_complement = common::dna::reverse_complement(substring)?;
+            if substring
+                .iter()
+                .zip(&reverse_complement)
+                .all(|(base1, base2)| common::dna::matches(base1, base2))
+            {
                 outputs.push(Output::new(i + 1, size));
             }
         }
@@ -59,14 +96,18 @@ fn locate(dna_string: &str) -> Result<Vec<Output>> {
     Ok(outputs)
 }
 
-#[derive(Debug, PartialEq)]
-struct Output {
-    position: usize,
-    length: usize,
+/// Location of a reverse palindrome
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    /// 1-based position of the palindrome
+    pub position: usize,
+    /// Length of the palindrome
+    pub length: usize,
 }
 
 impl Output {
-    fn new(position: usize, length: usize) -> Self {
+    /// Constructor for Output
+    pub fn new(position: usize, length: usize) -> Self {
         Self { position, length }
     }
 }
@@ -88,44 +129,54 @@ mod tests {
             dna_string: &'a str,
             expected: Result<Vec<Output>>,
         }
-        let test_cases = [TestCase {
-            name: "Sample Dataset",
-            dna_string: "TCAATGCATGCGGGTCTATATGCAT",
-            expected: Ok(vec![
-                Output {
-                    position: 5,
-                    length: 4,
-                },
-                Output {
-                    position: 7,
-                    length: 4,
-                },
-                Output {
-                    position: 17,
-                    length: 4,
-                },
-                Output {
-                    position: 18,
-                    length: 4,
-                },
-                Output {
-                    position: 21,
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset",
+                dna_string: "TCAATGCATGCGGGTCTATATGCAT",
+                expected: Ok(vec![
+                    Output {
+                        position: 5,
+                        length: 4,
+                    },
+                    Output {
+                        position: 7,
+                        length: 4,
+                    },
+                    Output {
+                        position: 17,
+                        length: 4,
+                    },
+                    Output {
+                        position: 18,
+                        length: 4,
+                    },
+                    Output {
+                        position: 21,
+                        length: 4,
+                    },
+                    Output {
+                        position: 4,
+                        length: 6,
+                    },
+                    Output {
+                        position: 6,
+                        length: 6,
+                    },
+                    Output {
+                        position: 20,
+                        length: 6,
+                    },
+                ]),
+            },
+            TestCase {
+                name: "Should match ambiguity codes",
+                dna_string: "ACNTA",
+                expected: Ok(vec![Output {
+                    position: 1,
                     length: 4,
-                },
-                Output {
-                    position: 4,
-                    length: 6,
-                },
-                Output {
-                    position: 6,
-                    length: 6,
-                },
-                Output {
-                    position: 20,
-                    length: 6,
-                },
-            ]),
-        }];
+                }]),
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
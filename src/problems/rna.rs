@@ -2,50 +2,61 @@
 //!
 //! # Problem
 //!
-//!     Given: A DNA string t having length at most 1000 nt.
+//! ```text
+//! Given: A DNA string t having length at most 1000 nt.
 //!
-//!     Return: The transcribed RNA string of t.
-//!
-//! # Sample Dataset
-//!     GATGGAACTTGACTACGTAAATT
-//!
-//! # Sample Output
-//!     GAUGGAACUUGACUACGUAAAUU
+//! Return: The transcribed RNA string of t.
+//! ```
 //!
 use crate::common;
+use crate::common::seq::{DnaSeq, RnaSeq};
 use crate::errors::Result;
+use crate::problems::{self, Problem};
 
-pub const SUBCOMMAND: &str = "rna";
+/// Rosalind problem RNA
+pub struct Rna;
 
-/// Return the subcommand for RNA
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Rna {
+    type Input = DnaSeq;
+    type Output = RnaSeq;
 
-/// Run the rna workflow
-///
-/// Run the workflow by loading the dna string from the input text file, transcribing it to rna,
-/// and then printing it.
-///
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let dna_string = common::load_simple(path)?;
-        let rna_string = transcribe(dna_string);
-        println!("{}", rna_string);
-        return Ok(());
+    const ID: &'static str = "rna";
+    const TITLE: &'static str = "Transcribing DNA into RNA";
+    const SAMPLE_DATASET: &'static str = "
+        GATGGAACTTGACTACGTAAATT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        GAUGGAACUUGACUACGUAAAUU
+    ";
+
+    fn command() -> clap::Command<'static> {
+        common::subcommand_fasta_out(Self::ID)
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_sequence(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        Ok(solve(&input))
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn records(output: &Self::Output) -> Option<Vec<common::fasta::Record>> {
+        Some(problems::numbered_records(Self::ID, vec![output]))
     }
-    Err(common::argument_err())
 }
 
-/// Transcribe DNA to RNA
+/// Solve RNA
 ///
-/// Replace any 'T' with 'U'.  Everything else is left alone.
+/// Return the RNA string transcribed from the dna string.
 ///
-fn transcribe(dna_string: String) -> String {
-    dna_string
-        .chars()
-        .map(|base| if base == 'T' { 'U' } else { base })
-        .collect()
+pub fn solve(dna_string: &DnaSeq) -> RnaSeq {
+    dna_string.transcribe()
 }
 
 #[cfg(test)]
@@ -53,28 +64,28 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_transcribe() {
+    fn test_solve() {
         struct TestCase<'a> {
             name: &'a str,
-            dna_string: String,
+            dna_string: &'a str,
             expected: String,
         }
         let test_cases = [
             TestCase {
                 name: "Sample Dataset from problem",
-                dna_string: "GATGGAACTTGACTACGTAAATT".into(),
+                dna_string: "GATGGAACTTGACTACGTAAATT",
                 expected: "GAUGGAACUUGACUACGUAAAUU".into(),
             },
             TestCase {
                 name: "Should not fail on empty string",
-                dna_string: String::new(),
+                dna_string: "",
                 expected: String::new(),
             },
         ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                transcribe(test_case.dna_string),
+                solve(&test_case.dna_string.parse().unwrap()).to_string(),
                 "{}",
                 test_case.name,
             );
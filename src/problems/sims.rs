@@ -0,0 +1,142 @@
+//! Finding a Motif with Modifications (https://rosalind.info/problems/sims/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two DNA strings s and t, where s has length at most 10 kbp and t represents a motif
+//!        of length at most 1 kbp.
+//!
+//! Return: An optimal fitting alignment score with respect to the mismatch score defined
+//!         above, followed by an optimal fitting alignment of a substring of s against t.  If
+//!         multiple such alignments exist, then you may output any one.  Matches score 1, and
+//!         mismatches and gap symbols score -1.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Alignment, Gap, Mode, Scoring, Substitution};
+use crate::common::seq::DnaSeq;
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const SCORING: Scoring = Scoring {
+    substitution: Substitution::DNA,
+    gap: Gap::Linear(1),
+};
+
+/// Rosalind problem SIMS
+pub struct Sims;
+
+impl Problem for Sims {
+    type Input = (DnaSeq, DnaSeq);
+    type Output = Alignment;
+
+    const ID: &'static str = "sims";
+    const TITLE: &'static str = "Finding a Motif with Modifications";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_54
+        GCAAACCATAAGCCCTACGTGCCGCCTGTTTAAACTCGCGAACTGAATCTTCTGCTTCACGGTGAAAGTACCACAATGGTATCACACCCCAAGGAAAC
+        >Rosalind_46
+        GCCGTCAGGCTGGTGTCCG
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        5
+        ACCATAAGCCCTACGTG-CCG
+        GCCGTCAGGC-TG-GTGTCCG
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        format!("{}\n{}\n{}", output.score, output.aligned1, output.aligned2)
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        let (s, t) = (input.0.as_str(), input.1.as_str());
+        if !s.contains(&align::ungapped(&output.aligned1)) || align::ungapped(&output.aligned2) != t
+        {
+            return Ok(false);
+        }
+        let rows = (output.aligned1.as_bytes(), output.aligned2.as_bytes());
+        Ok(align::score(rows.0, rows.1, &SCORING, false)? == output.score)
+    }
+}
+
+/// Solve SIMS
+///
+/// Return the best fitting alignment of the motif t against a substring of s, where matches
+/// score 1 and mismatches and gaps score -1.
+///
+pub fn solve(s: &DnaSeq, t: &DnaSeq) -> Result<Alignment> {
+    align::align(s.as_bytes(), t.as_bytes(), &SCORING, Mode::Fitting)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should fit the motif into the string",
+                s: "GGGGACGTGGGG",
+                t: "ACGT",
+                expected: (4, "ACGT", "ACGT"),
+            },
+            TestCase {
+                name: "Should fit identical strings",
+                s: "ACGT",
+                t: "ACGT",
+                expected: (4, "ACGT", "ACGT"),
+            },
+            TestCase {
+                name: "Should gap the end of a motif longer than the string",
+                s: "AC",
+                t: "ACGT",
+                expected: (0, "AC--", "ACGT"),
+            },
+            TestCase {
+                name: "Should pay for the end of the motif, unlike a local alignment",
+                s: "AAAACCCC",
+                t: "CCCCTT",
+                expected: (2, "CCCC--", "CCCCTT"),
+            },
+        ];
+        for test_case in test_cases {
+            let s: DnaSeq = test_case.s.parse().unwrap();
+            let t: DnaSeq = test_case.t.parse().unwrap();
+            let output = solve(&s, &t).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.score,
+                    output.aligned1.as_str(),
+                    output.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+            assert_eq!(
+                Ok(true),
+                Sims::achieves_score(&(s, t), &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
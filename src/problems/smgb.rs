@@ -0,0 +1,162 @@
+//! Semiglobal Alignment (https://rosalind.info/problems/smgb/)
+//!
+//! # Problem
+//!
+//! ```text
+//! Given: Two DNA strings s and t in FASTA format, each having length at most 10 kbp.
+//!
+//! Return: The maximum semiglobal alignment score of s and t, followed by an alignment of s
+//!         and t achieving this maximum score.  Use an alignment score in which matching
+//!         symbols count +1 and all mismatched symbols (including insertions and deletions)
+//!         receive a cost of -1.  If multiple optimal alignments exist, then you may return
+//!         any one.
+//! ```
+//!
+use crate::common;
+use crate::common::align::{self, Gap, Mode, Scoring, Substitution};
+use crate::common::seq::DnaSeq;
+use crate::errors::Result;
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
+
+const SCORING: Scoring = Scoring {
+    substitution: Substitution::DNA,
+    gap: Gap::Linear(1),
+};
+
+/// Rosalind problem SMGB
+pub struct Smgb;
+
+impl Problem for Smgb {
+    type Input = (DnaSeq, DnaSeq);
+    type Output = Output;
+
+    const ID: &'static str = "smgb";
+    const TITLE: &'static str = "Semiglobal Alignment";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_79
+        CAGCACTTGGATTCTCGG
+        >Rosalind_98
+        CAGCGTGG
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        4
+        CAGCA-CTTGGATTCTCGG
+        ---CAGCGTGG--------
+    ";
+    const COMPARISON: Comparison = Comparison::Score;
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        common::load_fasta_pair(&input)
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn achieves_score(input: &Self::Input, output: &Self::Output) -> Result<bool> {
+        if align::ungapped(&output.aligned1) != input.0.as_str()
+            || align::ungapped(&output.aligned2) != input.1.as_str()
+        {
+            return Ok(false);
+        }
+        let rows = (output.aligned1.as_bytes(), output.aligned2.as_bytes());
+        Ok(align::score(rows.0, rows.1, &SCORING, true)? == output.score)
+    }
+}
+
+/// Solve SMGB
+///
+/// Return the best semiglobal alignment of s and t, where matches score 1 and mismatches and
+/// gaps score -1, with the whole of both strings in the aligned strings.
+///
+pub fn solve(s: &DnaSeq, t: &DnaSeq) -> Result<Output> {
+    let alignment = align::align(s.as_bytes(), t.as_bytes(), &SCORING, Mode::SemiGlobal)?;
+    let (aligned1, aligned2) = alignment.pad(s.as_bytes(), t.as_bytes());
+    Ok(Output {
+        score: alignment.score,
+        aligned1,
+        aligned2,
+    })
+}
+
+/// Semiglobal alignment score and the padded alignment
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Output {
+    pub score: i32,
+    pub aligned1: String,
+    pub aligned2: String,
+}
+
+impl std::fmt::Display for Output {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}\n{}\n{}", self.score, self.aligned1, self.aligned2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        struct TestCase<'a> {
+            name: &'a str,
+            s: &'a str,
+            t: &'a str,
+            expected: (i32, &'a str, &'a str),
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should align identical strings",
+                s: "ACGT",
+                t: "ACGT",
+                expected: (4, "ACGT", "ACGT"),
+            },
+            TestCase {
+                name: "Should not pay for gaps at the ends of either string",
+                s: "AAAACGT",
+                t: "ACGTTTT",
+                expected: (4, "AAAACGT---", "---ACGTTTT"),
+            },
+            TestCase {
+                name: "Should contain the whole of both strings",
+                s: "GGACGTGG",
+                t: "ACGT",
+                expected: (4, "GGACGTGG", "--ACGT--"),
+            },
+            TestCase {
+                name: "Should prefer free end gaps over mismatches",
+                s: "ACGT",
+                t: "AAAA",
+                expected: (1, "---ACGT", "AAAA---"),
+            },
+        ];
+        for test_case in test_cases {
+            let s: DnaSeq = test_case.s.parse().unwrap();
+            let t: DnaSeq = test_case.t.parse().unwrap();
+            let output = solve(&s, &t).unwrap();
+            assert_eq!(
+                test_case.expected,
+                (
+                    output.score,
+                    output.aligned1.as_str(),
+                    output.aligned2.as_str()
+                ),
+                "{}",
+                test_case.name
+            );
+            assert_eq!(
+                Ok(true),
+                Smgb::achieves_score(&(s, t), &output),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}
@@ -1,78 +1,97 @@
 //! RNA Splicing (https://rosalind.info/problems/splc/)
 //!
 //! # Problem
-//!     Given: A DNA string s (of length at most 1 kbp) and a collectin of substrings s acting as
-//!             introns.  All strings are given in FASTA format.
+//! ```text
+//! Given: A DNA string s (of length at most 1 kbp) and a collectin of substrings s acting as
+//!         introns.  All strings are given in FASTA format.
 //!
-//!     Return: A protein string resulting from transcribing and traslating the exons of s. (Note:
-//!             Only one solution will exist for the dataset provided).
-//!
-//! # Sample Dataset
-//!     >Rosalind_10
-//!     ATGGTCTACATAGCTGACAAACAGCACGTAGCAATCGGTCGAATCTCGAGAGGCATATGGTCACATGATCGGTCGAGCGTGTTTCAAAGTTTGCGCCTAG
-//!     >Rosalind_12
-//!     ATCGGTCGAA
-//!     >Rosalind_15
-//!     ATCGGTCGAGCGTGT
-//!
-//! # Sample Output
-//!     MVYIADKQHVASREAYGHMFKVCA
+//! Return: A protein string resulting from transcribing and traslating the exons of s. (Note:
+//!         Only one solution will exist for the dataset provided).
+//! ```
 //!
 use crate::common;
+use crate::common::genetic_code::GeneticCode;
+use crate::common::matching::AhoCorasick;
+use crate::common::seq::{DnaSeq, ProteinSeq};
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::{self, Problem};
 
-pub const SUBCOMMAND: &str = "splc";
+/// Rosalind problem SPLC
+pub struct Splc;
 
-/// Return the subcommand for SPLC
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Splc {
+    type Input = (DnaSeq, Vec<DnaSeq>, &'static GeneticCode);
+    type Output = ProteinSeq;
+
+    const ID: &'static str = "splc";
+    const TITLE: &'static str = "RNA Splicing";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_10
+        ATGGTCTACATAGCTGACAAACAGCACGTAGCAATCGGTCGAATCTCGAGAGGCATATGGTCACATGATCGGTCGAGCGTGTTTCAAAGTTTGCGCCTAG
+        >Rosalind_12
+        ATCGGTCGAA
+        >Rosalind_15
+        ATCGGTCGAGCGTGT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        MVYIADKQHVASREAYGHMFKVCA
+    ";
 
-/// Run the splc workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let file = std::fs::File::open(path)?;
-        let reader = common::fasta::Reader::new(file);
-        let mut record_iter = reader.iter();
+    fn command() -> clap::Command<'static> {
+        common::with_table(common::subcommand_fasta_out(Self::ID))
+    }
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let records = common::load_fasta(&input)?;
+        let mut record_iter = records.into_iter();
         let dna_string = record_iter
             .next()
-            .ok_or_else(|| Error::new(ErrorKind::IO, "empty fasta file"))??
-            .sequence;
-        let mut substrings = vec![];
-        for record in record_iter {
-            let record = record?;
-            substrings.push(record.sequence);
-        }
-        let mut references: Vec<&str> = vec![];
-        for substring in substrings.iter() {
-            references.push(substring);
-        }
-        let output = splice_and_translate(&dna_string, &references)?;
-        println!("{}", output);
-        return Ok(());
+            .ok_or_else(|| Error::new(ErrorKind::Validation, "empty fasta file"))?
+            .parse_sequence()?;
+        let introns = record_iter
+            .map(|record| record.parse_sequence())
+            .collect::<Result<Vec<DnaSeq>>>()?;
+        Ok((dna_string, introns, common::genetic_code(matches)?))
     }
-    Err(common::argument_err())
-}
 
-fn splice_and_translate(dna_string: &str, substrings: &[&str]) -> Result<String> {
-    let spliced = splice(dna_string, substrings);
-    let mut translated = vec![];
-    for chunk in spliced.as_bytes().chunks_exact(3) {
-        if let Some(base) = common::dna::dna_to_protein(chunk)? {
-            translated.push(base);
-        } else {
-            break;
-        }
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1, input.2)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
+    }
+
+    fn records(output: &Self::Output) -> Option<Vec<common::fasta::Record>> {
+        Some(problems::numbered_records(Self::ID, vec![output]))
     }
-    let translated = String::from_utf8(translated.to_vec())?;
-    Ok(translated)
 }
 
+/// Solve SPLC
+///
+/// Remove the introns from the dna string, then transcribe and translate the remaining exons
+/// into a protein string using the genetic code.
+///
+pub fn solve(dna_string: &DnaSeq, introns: &[DnaSeq], code: &GeneticCode) -> Result<ProteinSeq> {
+    let introns: Vec<&str> = introns.iter().map(DnaSeq::as_str).collect();
+    let exons: DnaSeq = splice(dna_string.as_str(), &introns).parse()?;
+    Ok(exons.translate_with(code))
+}
+
+/// Remove the substrings from the string in one pass
+///
+/// Where occurrences overlap, the leftmost-longest is removed and the others are kept, and
+/// removing an occurrence never forms a new one.
+///
 fn splice(dna_string: &str, substrings: &[&str]) -> String {
-    let mut output = dna_string.to_owned();
-    substrings
-        .iter()
-        .for_each(|substring| output = output.replace(substring, ""));
+    let mut output = String::with_capacity(dna_string.len());
+    let mut start = 0;
+    for occurrence in AhoCorasick::new(substrings).find(dna_string.as_bytes()) {
+        output.push_str(&dna_string[start..occurrence.start]);
+        start = occurrence.end;
+    }
+    output.push_str(&dna_string[start..]);
     output
 }
 
@@ -81,25 +100,31 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_splice_and_translate() {
+    fn test_solve() {
         struct TestCase<'a> {
             name: &'a str,
             dna_string: &'a str,
             substrings: &'a [&'a str],
-            expected: Result<String>,
+            expected: String,
         }
         let test_cases = [
             TestCase {
                 name: "Sample Dataset",
                 dna_string: "ATGGTCTACATAGCTGACAAACAGCACGTAGCAATCGGTCGAATCTCGAGAGGCATATGGTCACATGATCGGTCGAGCGTGTTTCAAAGTTTGCGCCTAG",
                 substrings: &["ATCGGTCGAA", "ATCGGTCGAGCGTGT"],
-                expected: Ok("MVYIADKQHVASREAYGHMFKVCA".to_owned()),
+                expected: "MVYIADKQHVASREAYGHMFKVCA".to_owned(),
             },
         ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
-                splice_and_translate(test_case.dna_string, test_case.substrings),
+                solve(
+                    &test_case.dna_string.parse().unwrap(),
+                    &common::parse_lines(&test_case.substrings.join("\n")).unwrap(),
+                    GeneticCode::standard(),
+                )
+                .unwrap()
+                .to_string(),
                 "{}",
                 test_case.name,
             );
@@ -114,12 +139,32 @@ mod tests {
             substrings: &'a [&'a str],
             expected: String,
         }
-        let test_cases = [TestCase {
-            name: "Should remove all substrings",
-            dna_string: "ABCABCDEF",
-            substrings: &["AB", "CD"],
-            expected: "CEF".to_owned(),
-        }];
+        let test_cases = [
+            TestCase {
+                name: "Should remove all substrings",
+                dna_string: "ABCABCDEF",
+                substrings: &["AB", "CD"],
+                expected: "CEF".to_owned(),
+            },
+            TestCase {
+                name: "Should remove the leftmost of overlapping substrings",
+                dna_string: "AABCDD",
+                substrings: &["CD", "ABC"],
+                expected: "ADD".to_owned(),
+            },
+            TestCase {
+                name: "Should remove the longest of substrings at the same start",
+                dna_string: "AABCDD",
+                substrings: &["AB", "ABCD"],
+                expected: "AD".to_owned(),
+            },
+            TestCase {
+                name: "Should not remove substrings formed by splicing",
+                dna_string: "AGCTT",
+                substrings: &["GC", "AT"],
+                expected: "ATT".to_owned(),
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
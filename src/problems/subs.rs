@@ -2,62 +2,93 @@
 //!
 //! # Problem
 //!
-//!     Given: Two DNA Strings s and t (each of length at most 1 kbp).
+//! ```text
+//! Given: Two DNA Strings s and t (each of length at most 1 kbp).
 //!
-//!     Return: All locations of t as a substring of s.
-//!
-//! # Sample Dataset
-//!     GATATATGCATATACTT
-//!     ATAT
-//!
-//! # Sample Output
-//!     2 4 10
+//! Return: All locations of t as a substring of s.
+//! ```
 //!
 use crate::common;
-use crate::errors::{Error, ErrorKind, Result};
+use crate::common::seq::IupacSeq;
+use crate::errors::Result;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "subs";
+/// Rosalind problem SUBS
+pub struct Subs;
 
-/// Return the subcommand for SUBS
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Subs {
+    type Input = (IupacSeq, IupacSeq);
+    type Output = Vec<usize>;
 
-/// Run the subs workflow
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let input = common::load_simple(path)?;
+    const ID: &'static str = "subs";
+    const TITLE: &'static str = "Combing Through the Haystack";
+    const SAMPLE_DATASET: &'static str = "
+        GATATATGCATATACTT
+        ATAT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        2 4 10
+    ";
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let input = common::load_simple(&input)?;
         let (dna_string, substring) = parse_input(&input)?;
-        let positions = find_pos(dna_string, substring)?;
-        print(&positions);
-        return Ok(());
+        Ok((
+            common::parse_token(&input, dna_string)?,
+            common::parse_token(&input, substring.trim())?,
+        ))
     }
-    Err(common::argument_err())
-}
 
-/// Print output
-fn print(positions: &[usize]) {
-    for pos in positions.iter() {
-        print!("{} ", pos);
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
     }
-    println!()
+
+    fn format(output: &Self::Output) -> String {
+        format_output(output)
+    }
+}
+
+/// Format output
+fn format_output(positions: &[usize]) -> String {
+    positions
+        .iter()
+        .map(|pos| pos.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
 }
 
 /// Parse input
 fn parse_input(input: &'_ str) -> Result<(&'_ str, &'_ str)> {
     input
         .split_once('\n')
-        .ok_or_else(|| Error::new(ErrorKind::IO, "invalid input"))
+        .ok_or_else(|| common::invalid_input("expected two lines"))
+}
+
+/// Solve SUBS
+///
+/// Return the 1-based positions of every occurrence of substring in dna_string.
+///
+pub fn solve(dna_string: &IupacSeq, substring: &IupacSeq) -> Result<Vec<usize>> {
+    find_pos(dna_string.as_str(), substring.as_str())
 }
 
 /// Find the positions of the substring
-fn find_pos(dna_string: &str, substring: &str) -> Result<Vec<usize>> {
+///
+/// IUPAC ambiguity codes in either string match any base they stand for, so "ANT" is found at
+/// both "AGT" and "ACT".
+///
+pub fn find_pos(dna_string: &str, substring: &str) -> Result<Vec<usize>> {
     if substring.len() > dna_string.len() {
         return Ok(Vec::new());
     }
     let mut positions = Vec::new();
     for (i, sub) in dna_string.as_bytes().windows(substring.len()).enumerate() {
-        if String::from_utf8(sub.to_owned())? == substring {
+        if sub
+            .iter()
+            .zip(substring.as_bytes())
+            .all(|(base1, base2)| common::dna::matches(base1, base2))
+        {
             positions.push(i + 1);
         }
     }
@@ -77,12 +108,20 @@ mod tests {
             substring: &'a str,
             expected: Result<Vec<usize>>,
         }
-        let test_cases = [TestCase {
-            name: "Sample Dataset from problem",
-            dna_string: "GATATATGCATATACTT",
-            substring: "ATAT",
-            expected: Ok(vec![2, 4, 10]),
-        }];
+        let test_cases = [
+            TestCase {
+                name: "Sample Dataset from problem",
+                dna_string: "GATATATGCATATACTT",
+                substring: "ATAT",
+                expected: Ok(vec![2, 4, 10]),
+            },
+            TestCase {
+                name: "Should match ambiguity codes",
+                dna_string: "AGTNCTACT",
+                substring: "AST",
+                expected: Ok(vec![1, 4, 7]),
+            },
+        ];
         for test_case in test_cases {
             assert_eq!(
                 test_case.expected,
@@ -2,42 +2,65 @@
 //!
 //! # Problem
 //!
-//!     Given: Two DNA strings s_1 and s_2 of equal length (at most 1kbp).
+//! ```text
+//! Given: Two DNA strings s_1 and s_2 of equal length (at most 1kbp).
 //!
-//!     Return: The transition/transversion ratio R(s_1, s_2).
-//!
-//! # Sample Dataset
-//!     >Rosalind_0209
-//!     GCAACGCACAACGAAAACCCTTAGGGACTGGATTATTTCGTGATCGTTGTAGTTATTGGA
-//!     AGTACGGGCATCAACCCAGTT
-//!     >Rosalind_2200
-//!     TTATCTGACAAAGAAAGCCGTCAACGGCTGGATAATTTCGCGATCGTGCTGGTTACTGGC
-//!     GGTACGAGTGTTCCTTTGGGT
-//!
-//! # Sample Output
-//!     1.21428571429
+//! Return: The transition/transversion ratio R(s_1, s_2).
+//! ```
 //!
 use crate::common;
+use crate::common::seq::DnaSeq;
 use crate::errors::{Error, ErrorKind, Result};
+use crate::problems::verify::Comparison;
+use crate::problems::Problem;
 
-pub const SUBCOMMAND: &str = "tran";
+/// Rosalind problem TRAN
+pub struct Tran;
 
-pub fn command() -> clap::Command<'static> {
-    common::subcommand_file(SUBCOMMAND)
-}
+impl Problem for Tran {
+    type Input = (DnaSeq, DnaSeq);
+    type Output = f64;
 
-pub fn run(matches: &clap::ArgMatches) -> Result<()> {
-    if let Some(path) = matches.value_of(common::FILE_ARG) {
-        let mut reader = common::fasta::Reader::new(std::fs::File::open(path)?);
+    const ID: &'static str = "tran";
+    const TITLE: &'static str = "Transitions and Transversions";
+    const SAMPLE_DATASET: &'static str = "
+        >Rosalind_0209
+        GCAACGCACAACGAAAACCCTTAGGGACTGGATTATTTCGTGATCGTTGTAGTTATTGGA
+        AGTACGGGCATCAACCCAGTT
+        >Rosalind_2200
+        TTATCTGACAAAGAAAGCCGTCAACGGCTGGATAATTTCGCGATCGTGCTGGTTACTGGC
+        GGTACGAGTGTTCCTTTGGGT
+    ";
+    const SAMPLE_OUTPUT: &'static str = "
+        1.21428571429
+    ";
+    const COMPARISON: Comparison = Comparison::Tolerance(0.001);
+
+    fn parse(matches: &clap::ArgMatches) -> Result<Self::Input> {
+        let input = common::input::Input::from_matches(matches)?;
+        let mut reader = common::fasta::Reader::new(input.reader()?);
         let mut s_1 = common::fasta::Record::new();
         let mut s_2 = common::fasta::Record::new();
         reader.read(&mut s_1)?;
         reader.read(&mut s_2)?;
-        let ratio = get_ratio(s_1.sequence.as_bytes(), s_2.sequence.as_bytes())?;
-        println!("{}", ratio);
-        return Ok(());
+        Ok((s_1.parse_sequence()?, s_2.parse_sequence()?))
+    }
+
+    fn solve(input: Self::Input) -> Result<Self::Output> {
+        solve(&input.0, &input.1)
+    }
+
+    fn format(output: &Self::Output) -> String {
+        output.to_string()
     }
-    Err(common::argument_err())
+}
+
+/// Solve TRAN
+///
+/// Return the transition/transversion ratio of two DNA strings of equal length.
+///
+pub fn solve(s_1: &DnaSeq, s_2: &DnaSeq) -> Result<f64> {
+    get_ratio(s_1.as_bytes(), s_2.as_bytes())
 }
 
 fn get_ratio(s_1: &[u8], s_2: &[u8]) -> Result<f64> {
@@ -64,7 +87,7 @@ impl Counter {
     fn count(&mut self, s_1: &[u8], s_2: &[u8]) -> Result<()> {
         if s_1.len() != s_2.len() {
             return Err(Error::new(
-                ErrorKind::User,
+                ErrorKind::Validation,
                 "s_1 and s_2 must have the same length",
             ));
         }
@@ -0,0 +1,161 @@
+//! Verification of answers against the sample datasets
+use serde::Serialize;
+
+/// How an answer is compared against the expected output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    /// Lines must match exactly
+    Exact,
+    /// Lines must match, but may be in any order
+    Unordered,
+    /// Numbers must match within the given absolute error, everything else exactly
+    Tolerance(f64),
+    /// First line must match exactly, the rest may be any other optimal solution of the same
+    /// number of lines, which the problem checks with `Problem::achieves_score`
+    Score,
+}
+
+impl Comparison {
+    /// Check if the actual output matches the expected output
+    ///
+    /// Leading and trailing white space on each line is ignored.
+    ///
+    pub fn matches(&self, expected: &str, actual: &str) -> bool {
+        let expected = lines(expected);
+        let actual = lines(actual);
+        match self {
+            Self::Exact => expected == actual,
+            Self::Unordered => sorted(expected) == sorted(actual),
+            Self::Tolerance(tolerance) => {
+                let expected: Vec<&str> =
+                    expected.iter().flat_map(|l| l.split_whitespace()).collect();
+                let actual: Vec<&str> = actual.iter().flat_map(|l| l.split_whitespace()).collect();
+                expected.len() == actual.len()
+                    && expected
+                        .iter()
+                        .zip(actual.iter())
+                        .all(|(e, a)| is_close(e, a, *tolerance))
+            }
+            Self::Score => expected.len() == actual.len() && expected.first() == actual.first(),
+        }
+    }
+}
+
+/// Result of verifying a problem against its sample dataset
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Verification {
+    /// Whether the answer matches the sample output
+    pub passed: bool,
+    /// Sample output from the problem
+    pub expected: String,
+    /// Answer returned by the solver
+    pub actual: String,
+}
+
+/// Trim every line of a multi-line sample and drop leading and trailing blank lines
+pub fn trim_lines(text: &str) -> String {
+    lines(text).join("\n")
+}
+
+/// Return the trimmed lines of the text without leading and trailing blank lines
+fn lines(text: &str) -> Vec<&str> {
+    text.trim().lines().map(|line| line.trim()).collect()
+}
+
+/// Sort lines
+fn sorted(mut lines: Vec<&str>) -> Vec<&str> {
+    lines.sort_unstable();
+    lines
+}
+
+/// Compare two tokens numerically if possible, otherwise exactly
+fn is_close(expected: &str, actual: &str, tolerance: f64) -> bool {
+    match (expected.parse::<f64>(), actual.parse::<f64>()) {
+        (Ok(e), Ok(a)) => (e - a).abs() <= tolerance,
+        _ => expected == actual,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_matches() {
+        struct TestCase<'a> {
+            name: &'a str,
+            comparison: Comparison,
+            expected: &'a str,
+            actual: &'a str,
+            result: bool,
+        }
+        let test_cases = [
+            TestCase {
+                name: "Should match exactly, ignoring surrounding white space",
+                comparison: Comparison::Exact,
+                expected: "\n    1 2\n    3\n",
+                actual: "1 2\n3",
+                result: true,
+            },
+            TestCase {
+                name: "Should not match out of order lines exactly",
+                comparison: Comparison::Exact,
+                expected: "1 2\n3",
+                actual: "3\n1 2",
+                result: false,
+            },
+            TestCase {
+                name: "Should match out of order lines",
+                comparison: Comparison::Unordered,
+                expected: "1 2\n3",
+                actual: "3\n1 2",
+                result: true,
+            },
+            TestCase {
+                name: "Should match numbers within the tolerance",
+                comparison: Comparison::Tolerance(0.001),
+                expected: "Rosalind_0808\n60.919540",
+                actual: "Rosalind_0808\n60.91954022988506",
+                result: true,
+            },
+            TestCase {
+                name: "Should not match numbers outside of the tolerance",
+                comparison: Comparison::Tolerance(0.001),
+                expected: "0.684",
+                actual: "0.686",
+                result: false,
+            },
+            TestCase {
+                name: "Should match non-numeric tokens exactly",
+                comparison: Comparison::Tolerance(0.001),
+                expected: "Rosalind_0808\n60.919540",
+                actual: "Rosalind_0809\n60.919540",
+                result: false,
+            },
+            TestCase {
+                name: "Should match another solution with the same score",
+                comparison: Comparison::Score,
+                expected: "4\nCAGCACTTGG\nCAGCG-T-GG",
+                actual: "4\nCAGCACTTGG\nCAG--CGTGG",
+                result: true,
+            },
+            TestCase {
+                name: "Should not match a solution with a different score",
+                comparison: Comparison::Score,
+                expected: "4\nCAGCACTTGG\nCAGCG-T-GG",
+                actual: "3\nCAGCACTTGG\nCAGCG-T-GG",
+                result: false,
+            },
+        ];
+        for test_case in test_cases {
+            assert_eq!(
+                test_case.result,
+                test_case
+                    .comparison
+                    .matches(test_case.expected, test_case.actual),
+                "{}",
+                test_case.name
+            );
+        }
+    }
+}